@@ -0,0 +1,157 @@
+//! Right-to-left text: lines drawn in visual order put back into logical order
+//!
+//! Glyphs are placed where they appear on the page, so a line of Hebrew or Arabic read left
+//! to right comes out reversed, whatever order the producer drew it in. Lines holding
+//! right-to-left characters are therefore rebuilt from glyph positions in visual order, then
+//! reordered with the Unicode Bidirectional Algorithm (UAX #9): right-to-left runs are
+//! reversed into logical order, while numbers and Latin words embedded in them keep their
+//! left-to-right order.
+
+use crate::content::PositionedGlyph;
+use crate::layout;
+use crate::pdf_reader::BidiOrder;
+use unicode_bidi::{bidi_class, BidiClass, Level, ParagraphBidiInfo};
+
+/// Paired punctuation whose glyphs are mirrored in right-to-left text
+const MIRRORED_PAIRS: [(char, char); 6] =
+    [('(', ')'), ('[', ']'), ('{', '}'), ('<', '>'), ('«', '»'), ('‹', '›')];
+
+/// Whether a text holds a strong right-to-left character (Hebrew, Arabic, Syriac, ...)
+pub(crate) fn is_rtl(text: &str) -> bool {
+    text.chars().any(|c| matches!(bidi_class(c), BidiClass::R | BidiClass::AL))
+}
+
+/// Whether most strongly directional characters of the glyphs are right-to-left
+pub(crate) fn is_mostly_rtl(glyphs: &[PositionedGlyph]) -> bool {
+    let (rtl, ltr) = direction_counts(glyphs.iter().flat_map(|glyph| glyph.text.chars()));
+    rtl > ltr
+}
+
+/// Split a line holding right-to-left text into words, in the requested order
+///
+/// `line` holds the indices of the line's glyphs, whitespace included. Words end at
+/// whitespace and at gaps wider than `word_gap` times the font's space width, measured
+/// between neighbours on the page rather than in drawing order. In logical order, glyphs
+/// of mirrored punctuation in right-to-left runs get the text of their counterpart, so
+/// that a `)` drawn to the left of a Hebrew phrase reads as the `(` that opens it.
+pub(crate) fn order_line(
+    glyphs: &mut [PositionedGlyph],
+    line: &[usize],
+    word_gap: f64,
+    order: BidiOrder,
+) -> Vec<Vec<usize>> {
+    let words = visual_words(glyphs, line, word_gap);
+    match order {
+        BidiOrder::Visual => words,
+        BidiOrder::Logical => logical_words(glyphs, &words),
+    }
+}
+
+/// Words of a line from left to right, as they appear on the page
+fn visual_words(glyphs: &[PositionedGlyph], line: &[usize], word_gap: f64) -> Vec<Vec<usize>> {
+    let Some(&first) = line.first() else {
+        return Vec::new();
+    };
+    // Positions along the line, so that the glyphs of rotated lines sort the same way
+    let (ux, uy) = layout::direction(&glyphs[first]);
+    let start = |glyph: &PositionedGlyph| (glyph.x * ux + glyph.y * uy).min(glyph.end_x * ux + glyph.end_y * uy);
+    let end = |glyph: &PositionedGlyph| (glyph.x * ux + glyph.y * uy).max(glyph.end_x * ux + glyph.end_y * uy);
+
+    let mut visual = line.to_vec();
+    visual.sort_by(|&a, &b| start(&glyphs[a]).total_cmp(&start(&glyphs[b])));
+
+    let mut words: Vec<Vec<usize>> = Vec::new();
+    let mut previous: Option<&PositionedGlyph> = None;
+    let mut pending_break = false;
+    for index in visual {
+        let glyph = &glyphs[index];
+        if glyph.text.is_empty() && !glyph.is_space {
+            // A glyph without text, such as a stripped soft hyphen, still fills its part of the word
+            previous = previous.and(Some(glyph));
+            continue;
+        }
+        if glyph.text.trim().is_empty() {
+            pending_break |= !glyph.text.is_empty() || glyph.is_space;
+            continue;
+        }
+        match (words.last_mut(), previous) {
+            (Some(word), Some(prev)) if !pending_break && start(glyph) - end(prev) <= word_gap * prev.space_width => {
+                word.push(index)
+            }
+            _ => words.push(vec![index]),
+        }
+        previous = Some(glyph);
+        pending_break = false;
+    }
+    words
+}
+
+/// Reorder the words of a line from visual into logical order
+///
+/// The line's text is resolved as one paragraph whose direction is that of the majority of
+/// its strongly directional characters, then every glyph is moved by the resolved levels.
+/// Words stay whole unless a direction change falls inside them.
+fn logical_words(glyphs: &mut [PositionedGlyph], words: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    // The visual line as text, each glyph and word break with its offset in that text
+    let mut text = String::new();
+    let mut items: Vec<(usize, Option<usize>)> = Vec::new();
+    for (n, word) in words.iter().enumerate() {
+        if n > 0 {
+            items.push((text.len(), None));
+            text.push(' ');
+        }
+        for &index in word {
+            items.push((text.len(), Some(index)));
+            text.push_str(&glyphs[index].text);
+        }
+    }
+
+    let (rtl, ltr) = direction_counts(text.chars());
+    let base = if rtl >= ltr { Level::rtl() } else { Level::ltr() };
+    let info = ParagraphBidiInfo::new(&text, Some(base));
+    let levels: Vec<Level> = items.iter().map(|&(offset, _)| info.levels[offset]).collect();
+
+    let mut logical: Vec<Vec<usize>> = Vec::new();
+    let mut word: Vec<usize> = Vec::new();
+    for position in ParagraphBidiInfo::reorder_visual(&levels) {
+        match items[position].1 {
+            Some(index) => {
+                if levels[position].is_rtl() {
+                    glyphs[index].text = glyphs[index].text.chars().map(mirror).collect();
+                }
+                word.push(index);
+            }
+            None if !word.is_empty() => logical.push(std::mem::take(&mut word)),
+            None => {}
+        }
+    }
+    if !word.is_empty() {
+        logical.push(word);
+    }
+    logical
+}
+
+/// Number of strong right-to-left and left-to-right characters
+fn direction_counts(chars: impl Iterator<Item = char>) -> (usize, usize) {
+    chars.fold((0, 0), |(rtl, ltr), c| match bidi_class(c) {
+        BidiClass::R | BidiClass::AL => (rtl + 1, ltr),
+        BidiClass::L => (rtl, ltr + 1),
+        _ => (rtl, ltr),
+    })
+}
+
+/// The mirror image of a paired punctuation character, or the character itself
+fn mirror(c: char) -> char {
+    MIRRORED_PAIRS
+        .iter()
+        .find_map(|&(open, close)| {
+            if c == open {
+                Some(close)
+            } else if c == close {
+                Some(open)
+            } else {
+                None
+            }
+        })
+        .unwrap_or(c)
+}
@@ -0,0 +1,548 @@
+//! CMaps: how composite fonts split strings into codes, and how codes map to Unicode
+//!
+//! Embedded CMap streams (`/ToUnicode`, or an `/Encoding` stream of a Type0 font) are
+//! parsed leniently: codespace ranges, `bfchar`/`bfrange` and `cidchar`/`cidrange`
+//! sections, `usecmap` of a predefined CMap and the writing mode (`/WMode`). Predefined
+//! CMaps are recognised by name, vertical ones by their `-V` suffix; the Unicode ones
+//! (`UniGB-UTF16-H`, ...) and the legacy CJK encodings (Shift-JIS, EUC, GBK, Big5, UHC)
+//! decode codes directly, while CIDs are only known for `Identity-H/V`.
+
+use encoding_rs::{Encoding, BIG5, EUC_JP, EUC_KR, GB18030, GBK, SHIFT_JIS};
+use std::collections::HashMap;
+
+/// Longest character code allowed by the PDF specification
+const MAX_CODE_LENGTH: usize = 4;
+
+/// Range of codes sharing a byte length: every byte lies between the bounds' bytes
+struct CodespaceRange {
+    low: Vec<u8>,
+    high: Vec<u8>,
+}
+
+impl CodespaceRange {
+    fn matches(&self, bytes: &[u8]) -> bool {
+        bytes.len() >= self.low.len()
+            && bytes
+                .iter()
+                .zip(self.low.iter().zip(&self.high))
+                .all(|(byte, (low, high))| (low..=high).contains(&byte))
+    }
+}
+
+/// Destination of a `bfrange`
+enum RangeTarget {
+    /// UTF-16 text whose last code unit is incremented along the range
+    Offset(Vec<u16>),
+    /// One destination per code
+    List(Vec<Option<String>>),
+}
+
+/// A `bfrange` entry
+struct UnicodeRange {
+    length: usize,
+    low: u32,
+    high: u32,
+    target: RangeTarget,
+}
+
+/// A `cidrange` entry
+struct CidRange {
+    length: usize,
+    low: u32,
+    high: u32,
+    cid: u32,
+}
+
+/// A CMap parsed from a stream
+#[derive(Default)]
+pub(crate) struct CMap {
+    codespaces: Vec<CodespaceRange>,
+    unicode: HashMap<(usize, u32), String>,
+    unicode_ranges: Vec<UnicodeRange>,
+    cid_ranges: Vec<CidRange>,
+    /// Predefined CMap named by `usecmap`
+    parent: Option<PredefinedCMap>,
+    /// Writing mode 1 (`/WMode 1`), or `usecmap` of a vertical predefined CMap
+    vertical: bool,
+}
+
+impl CMap {
+    /// Parse a CMap stream; unreadable parts are skipped
+    pub fn parse(data: &[u8]) -> CMap {
+        let tokens = tokenize(data);
+        let mut cmap = CMap::default();
+
+        let mut index = 0;
+        while index < tokens.len() {
+            let Token::Keyword(keyword) = &tokens[index] else {
+                index += 1;
+                continue;
+            };
+            let section_end = |end: &[u8]| {
+                tokens[index + 1..]
+                    .iter()
+                    .position(|token| matches!(token, Token::Keyword(k) if *k == end))
+                    .map_or(tokens.len(), |offset| index + 1 + offset)
+            };
+
+            match *keyword {
+                b"usecmap" => {
+                    if let Some(Token::Name(name)) = index.checked_sub(1).map(|previous| &tokens[previous]) {
+                        let name = String::from_utf8_lossy(name);
+                        cmap.parent = PredefinedCMap::from_name(&name);
+                        cmap.vertical |= is_vertical_name(&name);
+                    }
+                }
+                b"def" => {
+                    if let [.., Token::Name(b"WMode"), Token::Number(mode)] = &tokens[..index] {
+                        cmap.vertical = *mode == 1;
+                    }
+                }
+                b"begincodespacerange" => {
+                    let end = section_end(b"endcodespacerange");
+                    cmap.read_codespaces(&tokens[index + 1..end]);
+                    index = end;
+                }
+                b"beginbfchar" => {
+                    let end = section_end(b"endbfchar");
+                    cmap.read_bfchars(&tokens[index + 1..end]);
+                    index = end;
+                }
+                b"beginbfrange" => {
+                    let end = section_end(b"endbfrange");
+                    cmap.read_bfranges(&tokens[index + 1..end]);
+                    index = end;
+                }
+                b"begincidchar" | b"begincidrange" => {
+                    let ranges = *keyword == b"begincidrange";
+                    let end = section_end(if ranges { b"endcidrange" } else { b"endcidchar" });
+                    cmap.read_cids(&tokens[index + 1..end], ranges);
+                    index = end;
+                }
+                _ => {}
+            }
+            index += 1;
+        }
+        cmap
+    }
+
+    fn read_codespaces(&mut self, tokens: &[Token]) {
+        for pair in tokens.chunks(2) {
+            if let [Token::Hex(low), Token::Hex(high)] = pair
+                && low.len() == high.len()
+                && (1..=MAX_CODE_LENGTH).contains(&low.len())
+            {
+                self.codespaces.push(CodespaceRange { low: low.clone(), high: high.clone() });
+            }
+        }
+    }
+
+    fn read_bfchars(&mut self, tokens: &[Token]) {
+        for pair in tokens.chunks(2) {
+            if let [Token::Hex(code), destination] = pair
+                && let Some(text) = destination_text(destination)
+            {
+                self.unicode.insert((code.len(), code_value(code)), text);
+            }
+        }
+    }
+
+    fn read_bfranges(&mut self, tokens: &[Token]) {
+        let mut index = 0;
+        while let (Some(Token::Hex(low)), Some(Token::Hex(high))) = (tokens.get(index), tokens.get(index + 1)) {
+            let (length, low, high) = (low.len(), code_value(low), code_value(high));
+            index += 2;
+
+            let target = match tokens.get(index) {
+                Some(Token::Hex(destination)) => {
+                    index += 1;
+                    RangeTarget::Offset(utf16_units(destination))
+                }
+                Some(Token::ArrayStart) => {
+                    let end = tokens[index..]
+                        .iter()
+                        .position(|token| matches!(token, Token::ArrayEnd))
+                        .map_or(tokens.len(), |offset| index + offset);
+                    let list = tokens[index + 1..end].iter().map(destination_text).collect();
+                    index = end + 1;
+                    RangeTarget::List(list)
+                }
+                _ => break,
+            };
+            if low <= high {
+                self.unicode_ranges.push(UnicodeRange { length, low, high, target });
+            }
+        }
+    }
+
+    fn read_cids(&mut self, tokens: &[Token], ranges: bool) {
+        let width = if ranges { 3 } else { 2 };
+        for entry in tokens.chunks(width) {
+            let range = match entry {
+                [Token::Hex(low), Token::Hex(high), Token::Number(cid)] if ranges => {
+                    CidRange { length: low.len(), low: code_value(low), high: code_value(high), cid: *cid as u32 }
+                }
+                [Token::Hex(code), Token::Number(cid)] if !ranges => {
+                    let value = code_value(code);
+                    CidRange { length: code.len(), low: value, high: value, cid: *cid as u32 }
+                }
+                _ => continue,
+            };
+            self.cid_ranges.push(range);
+        }
+    }
+
+    /// Whether the CMap defines how to split strings into codes
+    pub fn has_codespaces(&self) -> bool {
+        !self.codespaces.is_empty() || self.parent.is_some()
+    }
+
+    /// Whether the CMap is for vertical writing
+    pub fn is_vertical(&self) -> bool {
+        self.vertical
+    }
+
+    /// Length of the code at the start of `bytes`
+    fn code_length(&self, bytes: &[u8]) -> usize {
+        if self.codespaces.is_empty() {
+            return self.parent.as_ref().map_or(2, |parent| parent.code_length(bytes));
+        }
+        (1..=MAX_CODE_LENGTH)
+            .find(|length| self.codespaces.iter().any(|range| range.low.len() == *length && range.matches(bytes)))
+            // Bytes outside every codespace: consume as many as the shortest range does
+            .unwrap_or_else(|| self.codespaces.iter().map(|range| range.low.len()).min().unwrap_or(1))
+    }
+
+    /// Unicode text of a code
+    ///
+    /// Codes are matched by length and value first, then by value alone, since some
+    /// producers write ToUnicode CMaps whose code lengths differ from the font's.
+    pub fn to_unicode(&self, code: &[u8]) -> Option<String> {
+        let value = code_value(code);
+        self.lookup_unicode(Some(code.len()), value)
+            .or_else(|| self.lookup_unicode(None, value))
+            .or_else(|| self.parent.as_ref().and_then(|parent| parent.decode(code)))
+    }
+
+    fn lookup_unicode(&self, length: Option<usize>, value: u32) -> Option<String> {
+        let length_matches = |candidate: usize| length.is_none_or(|length| length == candidate);
+
+        if let Some(length) = length
+            && let Some(text) = self.unicode.get(&(length, value))
+        {
+            return Some(text.clone());
+        }
+        if length.is_none()
+            && let Some((_, text)) = self.unicode.iter().find(|((_, code), _)| *code == value)
+        {
+            return Some(text.clone());
+        }
+
+        let range = self
+            .unicode_ranges
+            .iter()
+            .find(|range| length_matches(range.length) && (range.low..=range.high).contains(&value))?;
+        let offset = value - range.low;
+        match &range.target {
+            RangeTarget::Offset(units) => {
+                let mut units = units.clone();
+                let last = units.last_mut()?;
+                *last = last.wrapping_add(offset as u16);
+                Some(String::from_utf16_lossy(&units))
+            }
+            RangeTarget::List(list) => list.get(offset as usize).cloned().flatten(),
+        }
+    }
+
+    /// CID of a code, from `cidchar`/`cidrange` or the predefined parent CMap
+    fn cid(&self, code: &[u8]) -> Option<u32> {
+        let value = code_value(code);
+        self.cid_ranges
+            .iter()
+            .find(|range| range.length == code.len() && (range.low..=range.high).contains(&value))
+            .map(|range| range.cid + (value - range.low))
+            .or_else(|| self.parent.as_ref().and_then(|parent| parent.cid(code)))
+    }
+}
+
+/// A predefined CMap, named by a Type0 font's `/Encoding`
+#[derive(Clone, Copy)]
+pub(crate) enum PredefinedCMap {
+    /// Two-byte codes equal to CIDs
+    Identity,
+    /// Codes are UTF-16BE (the `UCS2` and `UTF16` CMaps)
+    Utf16,
+    Utf8,
+    Utf32,
+    /// Codes in a legacy CJK encoding
+    Legacy(LegacyEncoding),
+}
+
+/// Legacy multi-byte encodings used by predefined CJK CMaps
+#[derive(Clone, Copy)]
+pub(crate) enum LegacyEncoding {
+    ShiftJis,
+    EucJp,
+    /// JIS X 0208 row and cell bytes (the `H` and `V` CMaps)
+    Jis,
+    Gbk,
+    Gb18030,
+    Big5,
+    EucKr,
+}
+
+impl PredefinedCMap {
+    /// Recognise a predefined CMap by name
+    pub fn from_name(name: &str) -> Option<Self> {
+        let base = name.strip_suffix("-H").or_else(|| name.strip_suffix("-V"));
+        let legacy = |encoding| Some(PredefinedCMap::Legacy(encoding));
+
+        match (name, base) {
+            ("Identity-H" | "Identity-V", _) => Some(PredefinedCMap::Identity),
+            ("H" | "V", _) => legacy(LegacyEncoding::Jis),
+            (_, None) => None,
+            _ if name.starts_with("Uni") && (name.contains("UCS2") || name.contains("UTF16")) => {
+                Some(PredefinedCMap::Utf16)
+            }
+            _ if name.starts_with("Uni") && name.contains("UTF8") => Some(PredefinedCMap::Utf8),
+            _ if name.starts_with("Uni") && name.contains("UTF32") => Some(PredefinedCMap::Utf32),
+            _ if name.contains("RKSJ") => legacy(LegacyEncoding::ShiftJis),
+            (_, Some("EUC")) => legacy(LegacyEncoding::EucJp),
+            _ if name.starts_with("GBK2K") => legacy(LegacyEncoding::Gb18030),
+            _ if name.starts_with("GB") => legacy(LegacyEncoding::Gbk),
+            _ if name.contains("B5") => legacy(LegacyEncoding::Big5),
+            _ if name.starts_with("KSC") => legacy(LegacyEncoding::EucKr),
+            _ => None,
+        }
+    }
+
+    /// Length of the code at the start of `bytes`
+    fn code_length(&self, bytes: &[u8]) -> usize {
+        let lead = bytes.first().copied().unwrap_or(0);
+        match self {
+            PredefinedCMap::Identity | PredefinedCMap::Legacy(LegacyEncoding::Jis) => 2,
+            PredefinedCMap::Utf16 if (0xD8..=0xDB).contains(&lead) => 4,
+            PredefinedCMap::Utf16 => 2,
+            PredefinedCMap::Utf32 => 4,
+            PredefinedCMap::Utf8 => match lead {
+                0xF0..=0xF7 => 4,
+                0xE0..=0xEF => 3,
+                0xC0..=0xDF => 2,
+                _ => 1,
+            },
+            PredefinedCMap::Legacy(LegacyEncoding::ShiftJis) => match lead {
+                0x81..=0x9F | 0xE0..=0xFC => 2,
+                _ => 1,
+            },
+            PredefinedCMap::Legacy(LegacyEncoding::EucJp) => match lead {
+                0x8F => 3,
+                0x8E | 0xA1..=0xFE => 2,
+                _ => 1,
+            },
+            PredefinedCMap::Legacy(LegacyEncoding::Gb18030)
+                if lead >= 0x81 && bytes.get(1).is_some_and(|byte| byte.is_ascii_digit()) =>
+            {
+                4
+            }
+            PredefinedCMap::Legacy(_) => match lead {
+                0x81..=0xFE => 2,
+                _ => 1,
+            },
+        }
+    }
+
+    /// Unicode text of a code, for CMaps whose codes carry their own meaning
+    fn decode(&self, code: &[u8]) -> Option<String> {
+        let decode = |encoding: &'static Encoding, bytes: &[u8]| {
+            encoding.decode_without_bom_handling_and_without_replacement(bytes).map(|text| text.into_owned())
+        };
+
+        match self {
+            PredefinedCMap::Identity => None,
+            PredefinedCMap::Utf16 => {
+                let units: Vec<u16> = code.chunks(2).map(|pair| code_value(pair) as u16).collect();
+                String::from_utf16(&units).ok()
+            }
+            PredefinedCMap::Utf8 => String::from_utf8(code.to_vec()).ok(),
+            PredefinedCMap::Utf32 => char::from_u32(code_value(code)).map(String::from),
+            PredefinedCMap::Legacy(encoding) => match encoding {
+                LegacyEncoding::ShiftJis => decode(SHIFT_JIS, code),
+                LegacyEncoding::EucJp => decode(EUC_JP, code),
+                // Row and cell bytes are EUC-JP with the high bit cleared
+                LegacyEncoding::Jis => decode(EUC_JP, &code.iter().map(|byte| byte | 0x80).collect::<Vec<_>>()),
+                LegacyEncoding::Gbk => decode(GBK, code),
+                LegacyEncoding::Gb18030 => decode(GB18030, code),
+                LegacyEncoding::Big5 => decode(BIG5, code),
+                LegacyEncoding::EucKr => decode(EUC_KR, code),
+            },
+        }
+    }
+
+    /// CID of a code; only known for the identity CMaps
+    fn cid(&self, code: &[u8]) -> Option<u32> {
+        matches!(self, PredefinedCMap::Identity).then(|| code_value(code))
+    }
+}
+
+/// The encoding CMap of a composite font
+pub(crate) enum CodeMap {
+    Predefined(PredefinedCMap),
+    Embedded(CMap),
+}
+
+impl CodeMap {
+    /// Split a string operand into character codes
+    pub fn split<'s>(&self, bytes: &'s [u8]) -> Vec<&'s [u8]> {
+        let mut codes = Vec::new();
+        let mut rest = bytes;
+        while !rest.is_empty() {
+            let length = match self {
+                CodeMap::Predefined(cmap) => cmap.code_length(rest),
+                CodeMap::Embedded(cmap) => cmap.code_length(rest),
+            };
+            let (code, tail) = rest.split_at(length.clamp(1, rest.len()));
+            codes.push(code);
+            rest = tail;
+        }
+        codes
+    }
+
+    /// CID of a code, when the CMap defines it
+    pub fn cid(&self, code: &[u8]) -> Option<u32> {
+        match self {
+            CodeMap::Predefined(cmap) => cmap.cid(code),
+            CodeMap::Embedded(cmap) => cmap.cid(code),
+        }
+    }
+
+    /// Unicode text of a code, for CMaps whose codes carry their own meaning
+    pub fn to_unicode(&self, code: &[u8]) -> Option<String> {
+        match self {
+            CodeMap::Predefined(cmap) => cmap.decode(code),
+            CodeMap::Embedded(cmap) => cmap.parent.as_ref().and_then(|parent| parent.decode(code)),
+        }
+    }
+}
+
+/// Whether a predefined CMap name is that of a vertical CMap (`Identity-V`, `UniJIS-UTF16-V`)
+pub(crate) fn is_vertical_name(name: &str) -> bool {
+    name == "V" || name.ends_with("-V")
+}
+
+/// Numeric value of a big-endian code
+pub(crate) fn code_value(code: &[u8]) -> u32 {
+    code.iter().fold(0, |value, byte| (value << 8) | *byte as u32)
+}
+
+/// UTF-16 code units of a hex string; single bytes are taken as one unit
+fn utf16_units(bytes: &[u8]) -> Vec<u16> {
+    if bytes.len() == 1 {
+        return vec![bytes[0] as u16];
+    }
+    bytes.chunks(2).map(|pair| code_value(pair) as u16).collect()
+}
+
+/// Text of a `bfchar` or `bfrange` destination: UTF-16BE hex or a glyph name
+fn destination_text(token: &Token) -> Option<String> {
+    match token {
+        Token::Hex(bytes) => Some(String::from_utf16_lossy(&utf16_units(bytes))),
+        Token::Name(name) => crate::glyph_list::glyph_to_unicode(&String::from_utf8_lossy(name)),
+        _ => None,
+    }
+}
+
+/// Lexical tokens of a CMap
+#[derive(Debug)]
+enum Token<'a> {
+    Hex(Vec<u8>),
+    Name(&'a [u8]),
+    Number(i64),
+    Keyword(&'a [u8]),
+    ArrayStart,
+    ArrayEnd,
+    /// Strings, dictionaries and reals, which the parser does not need
+    Other,
+}
+
+/// Split CMap data into tokens
+fn tokenize(data: &[u8]) -> Vec<Token<'_>> {
+    let is_delimiter = |byte: u8| byte.is_ascii_whitespace() || b"()<>[]{}/%".contains(&byte);
+    let mut tokens = Vec::new();
+    let mut index = 0;
+
+    while index < data.len() {
+        let byte = data[index];
+        match byte {
+            _ if byte.is_ascii_whitespace() || byte == 0 => index += 1,
+            b'%' => {
+                while index < data.len() && data[index] != b'\n' && data[index] != b'\r' {
+                    index += 1;
+                }
+            }
+            b'<' if data.get(index + 1) == Some(&b'<') => {
+                tokens.push(Token::Other);
+                index += 2;
+            }
+            b'>' if data.get(index + 1) == Some(&b'>') => {
+                tokens.push(Token::Other);
+                index += 2;
+            }
+            b'<' => {
+                let end = data[index..].iter().position(|b| *b == b'>').map_or(data.len(), |offset| index + offset);
+                let digits: Vec<u8> = data[index + 1..end].iter().copied().filter(u8::is_ascii_hexdigit).collect();
+                let bytes = digits
+                    .chunks(2)
+                    .map(|pair| {
+                        let high = (pair[0] as char).to_digit(16).unwrap_or(0);
+                        let low = pair.get(1).and_then(|digit| (*digit as char).to_digit(16)).unwrap_or(0);
+                        (high * 16 + low) as u8
+                    })
+                    .collect();
+                tokens.push(Token::Hex(bytes));
+                index = end + 1;
+            }
+            b'(' => {
+                // Literal strings only name things in CMaps; skip them, minding nesting
+                let mut depth = 0;
+                while index < data.len() {
+                    match data[index] {
+                        b'\\' => index += 1,
+                        b'(' => depth += 1,
+                        b')' => depth -= 1,
+                        _ => {}
+                    }
+                    index += 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                tokens.push(Token::Other);
+            }
+            b'[' => {
+                tokens.push(Token::ArrayStart);
+                index += 1;
+            }
+            b']' => {
+                tokens.push(Token::ArrayEnd);
+                index += 1;
+            }
+            _ => {
+                let start = index;
+                index += 1;
+                while index < data.len() && !is_delimiter(data[index]) {
+                    index += 1;
+                }
+                let word = &data[start..index];
+                tokens.push(match word {
+                    [b'/', name @ ..] => Token::Name(name),
+                    _ => match std::str::from_utf8(word).ok().and_then(|word| word.parse().ok()) {
+                        Some(number) => Token::Number(number),
+                        None if word.iter().all(u8::is_ascii_alphanumeric) => Token::Keyword(word),
+                        None => Token::Other,
+                    },
+                });
+            }
+        }
+    }
+    tokens
+}
@@ -0,0 +1,596 @@
+//! Content stream interpreter that positions every glyph on the page
+//!
+//! Follows the text state model of ISO 32000-1 section 9: the text matrix, the current
+//! transformation matrix, font size, character and word spacing, horizontal scaling and
+//! rise. Fonts in vertical writing mode advance downwards and place each glyph by its
+//! position vector. Form XObjects are interpreted in place. Paths are only kept as the
+//! horizontal and vertical rulings that table detection needs; images are ignored.
+//!
+//! Marked-content sequences are tracked for tagged PDF: glyphs carry the marked-content
+//! identifier (MCID) that links them to the structure tree, and the glyphs of a sequence
+//! with an `/ActualText` replacement give that text instead of their own.
+
+use crate::fonts::{self, Font};
+use crate::layout::FontRecovery;
+use lopdf::content::{Content, Operation};
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use std::collections::HashMap;
+
+/// Upper bound on nested form XObjects, guarding against cycles
+const MAX_FORM_DEPTH: usize = 16;
+
+/// Space width, as a fraction of the font size, for fonts that do not define one
+const DEFAULT_SPACE_WIDTH: f64 = 0.25;
+
+/// Largest deviation, in points, of a horizontal or vertical ruling from the axis
+const RULING_SKEW: f64 = 1.0;
+
+/// Thickest filled rectangle, in points, that is drawn as a ruling rather than a box
+const MAX_RULING_WIDTH: f64 = 3.0;
+
+/// An affine transformation `[a b c d e f]` applied to row vectors
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Matrix {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub e: f64,
+    pub f: f64,
+}
+
+impl Matrix {
+    pub const IDENTITY: Matrix = Matrix { a: 1.0, b: 0.0, c: 0.0, d: 1.0, e: 0.0, f: 0.0 };
+
+    pub fn translate(x: f64, y: f64) -> Self {
+        Matrix { e: x, f: y, ..Self::IDENTITY }
+    }
+
+    /// Build a matrix from six numeric operands
+    fn from_operands(doc: &Document, operands: &[Object]) -> Option<Self> {
+        let values: Vec<f64> = operands.iter().map(|o| fonts::number(doc, o)).collect::<Option<_>>()?;
+        match values[..] {
+            [a, b, c, d, e, f] => Some(Matrix { a, b, c, d, e, f }),
+            _ => None,
+        }
+    }
+
+    /// `self` followed by `other`
+    pub fn then(&self, other: &Matrix) -> Matrix {
+        Matrix {
+            a: self.a * other.a + self.b * other.c,
+            b: self.a * other.b + self.b * other.d,
+            c: self.c * other.a + self.d * other.c,
+            d: self.c * other.b + self.d * other.d,
+            e: self.e * other.a + self.f * other.c + other.e,
+            f: self.e * other.b + self.f * other.d + other.f,
+        }
+    }
+
+    pub fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        (x * self.a + y * self.c + self.e, x * self.b + y * self.d + self.f)
+    }
+}
+
+/// A glyph placed on the page, in default user space
+#[derive(Debug, Clone)]
+pub(crate) struct PositionedGlyph {
+    /// Decoded text (may be empty or several characters, e.g. ligatures)
+    pub text: String,
+    /// Baseline origin; for vertical text the vertical origin, at the top centre of the glyph
+    pub x: f64,
+    pub y: f64,
+    /// Baseline end point after the glyph's advance (without spacing)
+    pub end_x: f64,
+    pub end_y: f64,
+    /// Axis-aligned bounding box: x0, y0, x1, y1
+    pub bbox: [f64; 4],
+    /// Font size after all transformations
+    pub font_size: f64,
+    /// Width of a space in the glyph's font, after all transformations
+    pub space_width: f64,
+    pub font_name: String,
+    /// Whether the glyph is the single-byte space character
+    pub is_space: bool,
+    /// Whether the glyph's font writes vertically, top to bottom
+    pub vertical: bool,
+    /// How the text was recovered, when the PDF does not map the glyph to Unicode
+    pub recovery: Option<FontRecovery>,
+    /// Marked-content identifier of the innermost tagged sequence of the page's own content
+    /// holding the glyph
+    pub mcid: Option<u32>,
+}
+
+/// A horizontal or vertical line drawn on the page, in default user space
+///
+/// The end points are ordered: `x0 <= x1` and `y0 <= y1`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Ruling {
+    pub x0: f64,
+    pub y0: f64,
+    pub x1: f64,
+    pub y1: f64,
+}
+
+impl Ruling {
+    /// The segment between two points, if it runs along an axis
+    fn between((x0, y0): (f64, f64), (x1, y1): (f64, f64)) -> Option<Ruling> {
+        let ruling = Ruling { x0: x0.min(x1), y0: y0.min(y1), x1: x0.max(x1), y1: y0.max(y1) };
+        (ruling.is_horizontal() || ruling.is_vertical()).then_some(ruling)
+    }
+
+    pub fn is_horizontal(&self) -> bool {
+        self.y1 - self.y0 <= RULING_SKEW && self.x1 - self.x0 > RULING_SKEW
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        self.x1 - self.x0 <= RULING_SKEW && self.y1 - self.y0 > RULING_SKEW
+    }
+}
+
+/// A point of a subpath, in default user space, and whether it is reached by a straight line
+type PathPoint = ((f64, f64), bool);
+
+/// Graphics state entries that affect text placement
+#[derive(Clone)]
+struct GraphicsState {
+    ctm: Matrix,
+    char_spacing: f64,
+    word_spacing: f64,
+    horizontal_scaling: f64,
+    leading: f64,
+    rise: f64,
+    font_size: f64,
+    font: Option<FontKey>,
+}
+
+/// Identifies a loaded font: the address of its dictionary
+type FontKey = usize;
+
+/// A marked-content sequence open at the current point of the content
+struct MarkedContent {
+    /// Marked-content identifier, for sequences of the page's own content; those in form
+    /// XObjects belong to the form's structure instead
+    mcid: Option<u32>,
+    /// Replacement text for the glyphs of the sequence, and whether a glyph has given it
+    actual_text: Option<(String, bool)>,
+}
+
+/// Interpreter over one page
+struct Interpreter<'a> {
+    doc: &'a Document,
+    fonts: HashMap<FontKey, Font>,
+    glyphs: Vec<PositionedGlyph>,
+    rulings: Vec<Ruling>,
+    visited_forms: Vec<ObjectId>,
+    marked: Vec<MarkedContent>,
+}
+
+/// Interpret a page's content streams and return its glyphs in content order
+pub(crate) fn page_glyphs(doc: &Document, page_id: ObjectId) -> Result<Vec<PositionedGlyph>, lopdf::Error> {
+    page_content(doc, page_id).map(|(glyphs, _)| glyphs)
+}
+
+/// Interpret a page's content streams and return its glyphs in content order, with the
+/// horizontal and vertical lines stroked or filled on it
+pub(crate) fn page_content(
+    doc: &Document,
+    page_id: ObjectId,
+) -> Result<(Vec<PositionedGlyph>, Vec<Ruling>), lopdf::Error> {
+    let content = Content::decode(&doc.get_page_content(page_id)?)?;
+    let resources = page_resources(doc, page_id);
+
+    let mut interpreter = Interpreter {
+        doc,
+        fonts: HashMap::new(),
+        glyphs: Vec::new(),
+        rulings: Vec::new(),
+        visited_forms: Vec::new(),
+        marked: Vec::new(),
+    };
+    let state = GraphicsState {
+        ctm: Matrix::IDENTITY,
+        char_spacing: 0.0,
+        word_spacing: 0.0,
+        horizontal_scaling: 1.0,
+        leading: 0.0,
+        rise: 0.0,
+        font_size: 0.0,
+        font: None,
+    };
+    interpreter.run(&content.operations, &resources, state);
+    Ok((interpreter.glyphs, interpreter.rulings))
+}
+
+/// Resource dictionaries of a page, innermost first (inherited ones last)
+fn page_resources(doc: &Document, page_id: ObjectId) -> Vec<&Dictionary> {
+    let Ok((direct, inherited)) = doc.get_page_resources(page_id) else {
+        return Vec::new();
+    };
+    direct
+        .into_iter()
+        .chain(inherited.into_iter().filter_map(|id| doc.get_dictionary(id).ok()))
+        .collect()
+}
+
+impl<'a> Interpreter<'a> {
+    fn run(&mut self, operations: &[Operation], resources: &[&'a Dictionary], mut state: GraphicsState) {
+        let doc = self.doc;
+        let mut stack: Vec<GraphicsState> = Vec::new();
+        let mut text_matrix = Matrix::IDENTITY;
+        let mut line_matrix = Matrix::IDENTITY;
+        // Subpaths of the path under construction
+        let mut path: Vec<Vec<PathPoint>> = Vec::new();
+
+        for operation in operations {
+            let operands = &operation.operands;
+            let num = |index: usize| operands.get(index).and_then(|o| fonts::number(doc, o));
+
+            match operation.operator.as_str() {
+                "q" => stack.push(state.clone()),
+                "Q" => {
+                    if let Some(saved) = stack.pop() {
+                        state = saved;
+                    }
+                }
+                "cm" => {
+                    if let Some(matrix) = Matrix::from_operands(doc, operands) {
+                        state.ctm = matrix.then(&state.ctm);
+                    }
+                }
+                "BT" => {
+                    text_matrix = Matrix::IDENTITY;
+                    line_matrix = Matrix::IDENTITY;
+                }
+                "Tc" => state.char_spacing = num(0).unwrap_or(0.0),
+                "Tw" => state.word_spacing = num(0).unwrap_or(0.0),
+                "Tz" => state.horizontal_scaling = num(0).unwrap_or(100.0) / 100.0,
+                "TL" => state.leading = num(0).unwrap_or(0.0),
+                "Ts" => state.rise = num(0).unwrap_or(0.0),
+                "Tf" => {
+                    state.font_size = num(1).unwrap_or(0.0);
+                    state.font = operands
+                        .first()
+                        .and_then(|name| name.as_name().ok())
+                        .and_then(|name| self.load_font(resources, name));
+                }
+                "Td" | "TD" => {
+                    let (tx, ty) = (num(0).unwrap_or(0.0), num(1).unwrap_or(0.0));
+                    if operation.operator == "TD" {
+                        state.leading = -ty;
+                    }
+                    line_matrix = Matrix::translate(tx, ty).then(&line_matrix);
+                    text_matrix = line_matrix;
+                }
+                "Tm" => {
+                    if let Some(matrix) = Matrix::from_operands(doc, operands) {
+                        line_matrix = matrix;
+                        text_matrix = matrix;
+                    }
+                }
+                "T*" => {
+                    line_matrix = Matrix::translate(0.0, -state.leading).then(&line_matrix);
+                    text_matrix = line_matrix;
+                }
+                "Tj" => {
+                    if let Some(Object::String(bytes, _)) = operands.first() {
+                        self.show_string(bytes, &state, &mut text_matrix);
+                    }
+                }
+                "'" | "\"" => {
+                    if operation.operator == "\"" {
+                        state.word_spacing = num(0).unwrap_or(state.word_spacing);
+                        state.char_spacing = num(1).unwrap_or(state.char_spacing);
+                    }
+                    line_matrix = Matrix::translate(0.0, -state.leading).then(&line_matrix);
+                    text_matrix = line_matrix;
+                    if let Some(Object::String(bytes, _)) = operands.last() {
+                        self.show_string(bytes, &state, &mut text_matrix);
+                    }
+                }
+                "TJ" => {
+                    let Some(Ok(items)) = operands.first().map(Object::as_array) else {
+                        continue;
+                    };
+                    let vertical = state.font.and_then(|key| self.fonts.get(&key)).is_some_and(|font| font.vertical);
+                    for item in items {
+                        match item {
+                            Object::String(bytes, _) => self.show_string(bytes, &state, &mut text_matrix),
+                            item => {
+                                // Positive adjustments move left (down in vertical writing), in
+                                // thousandths of text space
+                                let adjustment = fonts::number(doc, item).unwrap_or(0.0) / 1000.0 * state.font_size;
+                                let shift = if vertical {
+                                    Matrix::translate(0.0, -adjustment)
+                                } else {
+                                    Matrix::translate(-adjustment * state.horizontal_scaling, 0.0)
+                                };
+                                text_matrix = shift.then(&text_matrix);
+                            }
+                        }
+                    }
+                }
+                "Do" => {
+                    if let Some(name) = operands.first().and_then(|name| name.as_name().ok()) {
+                        self.run_form(resources, name, &state);
+                    }
+                }
+                "BMC" => self.marked.push(MarkedContent { mcid: None, actual_text: None }),
+                "BDC" => {
+                    // Properties are given inline or by name from the /Properties resources
+                    let properties = match operands.get(1) {
+                        Some(Object::Name(name)) => lookup(doc, resources, b"Properties", name).map(|(_, dict)| dict),
+                        properties => properties,
+                    };
+                    let properties = properties.and_then(|properties| properties.as_dict().ok());
+                    let entry = |key: &[u8]| properties.and_then(|properties| properties.get(key).ok());
+                    let mcid = entry(b"MCID")
+                        .and_then(|mcid| mcid.as_i64().ok())
+                        .and_then(|mcid| u32::try_from(mcid).ok())
+                        .filter(|_| self.visited_forms.is_empty());
+                    let actual_text = entry(b"ActualText").and_then(|text| lopdf::decode_text_string(text).ok());
+                    self.marked.push(MarkedContent { mcid, actual_text: actual_text.map(|text| (text, false)) });
+                }
+                "EMC" => {
+                    self.marked.pop();
+                }
+                "m" => {
+                    if let (Some(x), Some(y)) = (num(0), num(1)) {
+                        path.push(vec![(state.ctm.apply(x, y), true)]);
+                    }
+                }
+                "l" | "c" | "v" | "y" => {
+                    // The end point is the last pair of operands; curves never make rulings
+                    let end = operands.len().saturating_sub(2);
+                    if let (Some(x), Some(y), Some(subpath)) = (num(end), num(end + 1), path.last_mut()) {
+                        subpath.push((state.ctm.apply(x, y), operation.operator == "l"));
+                    }
+                }
+                "h" => {
+                    if let Some(subpath) = path.last_mut()
+                        && let Some(&(start, _)) = subpath.first()
+                    {
+                        subpath.push((start, true));
+                    }
+                }
+                "re" => {
+                    if let (Some(x), Some(y), Some(width), Some(height)) = (num(0), num(1), num(2), num(3)) {
+                        let corners = [(x, y), (x + width, y), (x + width, y + height), (x, y + height), (x, y)];
+                        path.push(corners.iter().map(|&(x, y)| (state.ctm.apply(x, y), true)).collect());
+                    }
+                }
+                "S" | "s" | "B" | "B*" | "b" | "b*" => {
+                    let close = matches!(operation.operator.as_str(), "s" | "b" | "b*");
+                    self.stroke_path(&mut path, close);
+                }
+                "f" | "F" | "f*" => self.fill_path(&mut path),
+                "n" => path.clear(),
+                _ => {}
+            }
+        }
+    }
+
+    /// Keep the straight horizontal and vertical segments of a stroked path as rulings
+    fn stroke_path(&mut self, path: &mut Vec<Vec<PathPoint>>, close: bool) {
+        for mut subpath in path.drain(..) {
+            if close && let Some(&(start, _)) = subpath.first() {
+                subpath.push((start, true));
+            }
+            for pair in subpath.windows(2) {
+                let ((from, _), (to, straight)) = (pair[0], pair[1]);
+                if straight && let Some(ruling) = Ruling::between(from, to) {
+                    self.rulings.push(ruling);
+                }
+            }
+        }
+    }
+
+    /// Keep filled rectangles thin enough to be lines as rulings along their middle
+    fn fill_path(&mut self, path: &mut Vec<Vec<PathPoint>>) {
+        for subpath in path.drain(..) {
+            if subpath.len() < 4 || subpath.len() > 5 || subpath.iter().any(|&(_, straight)| !straight) {
+                continue;
+            }
+            let (x0, y0, x1, y1) = subpath.iter().fold(
+                (f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+                |(x0, y0, x1, y1), &((x, y), _)| (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+            );
+            // Only axis-aligned rectangles: every point on a corner of the bounding box
+            let on_corner = |&((x, y), _): &PathPoint| {
+                ((x - x0).abs() <= RULING_SKEW || (x - x1).abs() <= RULING_SKEW)
+                    && ((y - y0).abs() <= RULING_SKEW || (y - y1).abs() <= RULING_SKEW)
+            };
+            if !subpath.iter().all(on_corner) {
+                continue;
+            }
+            let ruling = if y1 - y0 <= MAX_RULING_WIDTH && y1 - y0 < x1 - x0 {
+                Ruling::between((x0, (y0 + y1) / 2.0), (x1, (y0 + y1) / 2.0))
+            } else if x1 - x0 <= MAX_RULING_WIDTH {
+                Ruling::between(((x0 + x1) / 2.0, y0), ((x0 + x1) / 2.0, y1))
+            } else {
+                None
+            };
+            self.rulings.extend(ruling);
+        }
+    }
+
+    /// Interpret a form XObject with its own matrix and resources
+    fn run_form(&mut self, resources: &[&'a Dictionary], name: &[u8], state: &GraphicsState) {
+        let doc = self.doc;
+        let Some((id, stream)) = lookup(doc, resources, b"XObject", name).and_then(|object| match object {
+            (Some(id), Object::Stream(stream)) => Some((id, stream)),
+            _ => None,
+        }) else {
+            return;
+        };
+        if stream.dict.get(b"Subtype").and_then(Object::as_name).ok() != Some(b"Form".as_slice())
+            || self.visited_forms.len() >= MAX_FORM_DEPTH
+            || self.visited_forms.contains(&id)
+        {
+            return;
+        }
+
+        let content = match stream.decompressed_content() {
+            Ok(content) => content,
+            Err(_) => stream.content.clone(),
+        };
+        let Ok(content) = Content::decode(&content) else {
+            return;
+        };
+
+        let mut form_state = state.clone();
+        if let Some(matrix) = stream
+            .dict
+            .get(b"Matrix")
+            .ok()
+            .and_then(|matrix| doc.dereference(matrix).ok())
+            .and_then(|(_, matrix)| matrix.as_array().ok())
+            .and_then(|matrix| Matrix::from_operands(doc, matrix))
+        {
+            form_state.ctm = matrix.then(&state.ctm);
+        }
+
+        // Forms without their own resources inherit the page's
+        let mut form_resources = Vec::with_capacity(resources.len() + 1);
+        if let Some(own) = stream
+            .dict
+            .get(b"Resources")
+            .ok()
+            .and_then(|own| doc.dereference(own).ok())
+            .and_then(|(_, own)| own.as_dict().ok())
+        {
+            form_resources.push(own);
+        }
+        form_resources.extend_from_slice(resources);
+
+        // Sequences left open by the form end with it
+        let marked = self.marked.len();
+        self.visited_forms.push(id);
+        self.run(&content.operations, &form_resources, form_state);
+        self.visited_forms.pop();
+        self.marked.truncate(marked);
+    }
+
+    /// Load (or reuse) the font registered under `name`
+    fn load_font(&mut self, resources: &[&'a Dictionary], name: &[u8]) -> Option<FontKey> {
+        let doc = self.doc;
+        let (_, font) = lookup(doc, resources, b"Font", name)?;
+        let dict = font.as_dict().ok()?;
+        let key = dict as *const Dictionary as FontKey;
+        self.fonts.entry(key).or_insert_with(|| {
+            let mut font = Font::load(doc, dict);
+            if font.name.is_empty() {
+                // Unnamed Type3 fonts are known by their resource name
+                font.name = String::from_utf8_lossy(name).into_owned();
+            }
+            font
+        });
+        Some(key)
+    }
+
+    /// Place the glyphs of a string operand, advancing the text matrix
+    fn show_string(&mut self, bytes: &[u8], state: &GraphicsState, text_matrix: &mut Matrix) {
+        let Some(font) = state.font.and_then(|key| self.fonts.get(&key)) else {
+            return;
+        };
+        let size = state.font_size;
+        let scaling = state.horizontal_scaling;
+        // Fonts without a usable space width get a typical one; full-width spaces of CJK
+        // fonts would hide every gap between Latin words
+        let space_width = font.space_width().unwrap_or(DEFAULT_SPACE_WIDTH).clamp(0.1, 0.5);
+        let mcid = self.marked.iter().rev().find_map(|marked| marked.mcid);
+
+        for code in font.codes(bytes) {
+            let (text, recovery) = font.decode(code);
+            let width = font.width(code, &text);
+            // The outermost replacement goes to the sequence's first glyph; the others give no text
+            let text = match self.marked.iter_mut().find_map(|marked| marked.actual_text.as_mut()) {
+                Some((replacement, given)) if !*given => {
+                    *given = true;
+                    replacement.clone()
+                }
+                Some(_) => String::new(),
+                None => text,
+            };
+            let is_space = Font::is_word_space(code);
+
+            // Text rendering matrix: font size, scaling and rise, then Tm and CTM
+            let render = Matrix { a: size * scaling, b: 0.0, c: 0.0, d: size, e: 0.0, f: state.rise }
+                .then(text_matrix)
+                .then(&state.ctm);
+
+            // Vertical fonts advance downwards, with the glyph hanging from its vertical
+            // origin: the horizontal origin sits at minus the position vector
+            let (advance, (ox, oy)) = if font.vertical {
+                let (advance, vx, vy) = font.vertical_metrics(code, width);
+                ((0.0, advance), (-vx, -vy))
+            } else {
+                ((width, 0.0), (0.0, 0.0))
+            };
+
+            let corners = [
+                render.apply(ox, oy + font.descent),
+                render.apply(ox + width, oy + font.descent),
+                render.apply(ox, oy + font.ascent),
+                render.apply(ox + width, oy + font.ascent),
+            ];
+            let bbox = corners.iter().fold(
+                [f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY],
+                |[x0, y0, x1, y1], (x, y)| [x0.min(*x), y0.min(*y), x1.max(*x), y1.max(*y)],
+            );
+            let (x, y) = render.apply(0.0, 0.0);
+            let (end_x, end_y) = render.apply(advance.0, advance.1);
+
+            let matrix = text_matrix.then(&state.ctm);
+            let space_scale = if font.vertical { render_scale(&matrix) } else { scaling * advance_scale(&matrix) };
+            self.glyphs.push(PositionedGlyph {
+                text,
+                x,
+                y,
+                end_x,
+                end_y,
+                bbox,
+                font_size: size * render_scale(&matrix),
+                space_width: space_width * size * space_scale,
+                font_name: font.name.clone(),
+                is_space,
+                vertical: font.vertical,
+                recovery,
+                mcid,
+            });
+
+            let spacing = state.char_spacing + if is_space { state.word_spacing } else { 0.0 };
+            let step = if font.vertical {
+                Matrix::translate(0.0, advance.1 * size + spacing)
+            } else {
+                Matrix::translate((width * size + spacing) * scaling, 0.0)
+            };
+            *text_matrix = step.then(text_matrix);
+        }
+    }
+}
+
+/// Vertical scale factor of a matrix, used to turn font sizes into page units
+fn render_scale(matrix: &Matrix) -> f64 {
+    matrix.c.hypot(matrix.d)
+}
+
+/// Horizontal scale factor of a matrix, used to turn advances into page units
+fn advance_scale(matrix: &Matrix) -> f64 {
+    matrix.a.hypot(matrix.b)
+}
+
+/// Find a named resource of the given category, searching innermost resources first
+fn lookup<'a>(
+    doc: &'a Document,
+    resources: &[&'a Dictionary],
+    category: &[u8],
+    name: &[u8],
+) -> Option<(Option<ObjectId>, &'a Object)> {
+    resources.iter().find_map(|dict| {
+        let (_, category) = doc.dereference(dict.get(category).ok()?).ok()?;
+        let entry = category.as_dict().ok()?.get(name).ok()?;
+        let (id, object) = doc.dereference(entry).ok()?;
+        Some((id, object))
+    })
+}
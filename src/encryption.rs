@@ -0,0 +1,311 @@
+//! Decryption of password-protected PDF documents
+
+use crate::error::PdfError;
+use lopdf::encryption::{DecryptionError, PasswordAlgorithm};
+use lopdf::{Dictionary, Document, Object, ObjectId, Permissions, Reader};
+use md5::{Digest, Md5};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Same-length stand-in for the trailer's `/Encrypt` key while parsing encrypted files
+const MASKED_ENCRYPT_KEY: &[u8] = b"/NoCrypt";
+
+/// Temporary `/Type` of object streams that still hold encrypted data
+const HIDDEN_OBJECT_STREAM_TYPE: &[u8] = b"EncryptedObjStm";
+
+/// Padding string used to extend passwords to 32 bytes (revision 4 and earlier)
+const PAD_BYTES: [u8; 32] = [
+    0x28, 0xBF, 0x4E, 0x5E, 0x4E, 0x75, 0x8A, 0x41, 0x64, 0x00, 0x4E, 0x56, 0xFF, 0xFA, 0x01, 0x08,
+    0x2E, 0x2E, 0x00, 0xB6, 0xD0, 0x68, 0x3E, 0x80, 0x2F, 0x0C, 0xA9, 0xFE, 0x64, 0x53, 0x69, 0x7A,
+];
+
+/// Encryption settings of a document
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EncryptionInfo {
+    /// Name of the security handler (`/Filter`)
+    pub security_handler: String,
+    /// Algorithm version (`/V`)
+    pub version: i64,
+    /// Security handler revision (`/R`)
+    pub revision: i64,
+    /// File encryption key length in bits
+    pub key_length: usize,
+    /// Cipher used for streams: RC4, AES-128, AES-256 or Identity
+    pub cipher: String,
+    /// Access permissions granted by the `/P` entry
+    pub permissions: DocumentPermissions,
+}
+
+/// Decoded `/P` permission flags
+///
+/// These describe document policy only; they are not enforced by the reader.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DocumentPermissions {
+    pub print: bool,
+    pub print_high_quality: bool,
+    pub modify: bool,
+    pub copy: bool,
+    pub annotate: bool,
+    pub fill_forms: bool,
+    pub extract_for_accessibility: bool,
+    pub assemble: bool,
+}
+
+impl DocumentPermissions {
+    /// Decode permission bits for the given security handler revision
+    fn from_bits(permissions: Permissions, revision: i64) -> Self {
+        let print = permissions.contains(Permissions::PRINTABLE);
+        let modify = permissions.contains(Permissions::MODIFIABLE);
+        let copy = permissions.contains(Permissions::COPYABLE);
+
+        // Revision 2 has no separate bits for these; they follow print, copy and modify
+        if revision < 3 {
+            return Self {
+                print,
+                print_high_quality: print,
+                modify,
+                copy,
+                annotate: permissions.contains(Permissions::ANNOTABLE),
+                fill_forms: permissions.contains(Permissions::ANNOTABLE),
+                extract_for_accessibility: copy,
+                assemble: modify,
+            };
+        }
+
+        Self {
+            print,
+            print_high_quality: print && permissions.contains(Permissions::PRINTABLE_IN_HIGH_QUALITY),
+            modify,
+            copy,
+            annotate: permissions.contains(Permissions::ANNOTABLE),
+            fill_forms: permissions.intersects(Permissions::FILLABLE | Permissions::ANNOTABLE),
+            extract_for_accessibility: permissions.contains(Permissions::COPYABLE_FOR_ACCESSIBILITY),
+            assemble: permissions.contains(Permissions::ASSEMBLABLE),
+        }
+    }
+}
+
+/// Describe how a document is encrypted, if it is
+///
+/// Reads the trailer's `/Encrypt` dictionary, whose entries are never encrypted, so the
+/// settings and permissions are the same whether or not the document was decrypted.
+pub(crate) fn encryption_info(doc: &Document) -> Option<EncryptionInfo> {
+    let encrypt = doc.get_encrypted().ok()?;
+    let number = |key: &[u8]| encrypt.get(key).and_then(Object::as_i64).ok();
+    let name = |dict: &Dictionary, key: &[u8]| dict.get(key).and_then(Object::as_name).ok().map(<[u8]>::to_vec);
+
+    let version = number(b"V").unwrap_or(0);
+    let revision = number(b"R").unwrap_or(2);
+    // The stream filter names an entry of /CF, whose /CFM is the cipher
+    let stream_method = name(encrypt, b"StmF").and_then(|filter| {
+        let filters = doc.dereference(encrypt.get(b"CF").ok()?).ok()?.1.as_dict().ok()?;
+        let filter = doc.dereference(filters.get(&filter).ok()?).ok()?.1.as_dict().ok()?;
+        name(filter, b"CFM")
+    });
+    let (cipher, default_key_length) = cipher(version, stream_method.as_deref());
+    // /P is a 32-bit signed integer; permissions are its bit pattern
+    let permissions = Permissions::from_bits_truncate(number(b"P").unwrap_or(0) as u32 as u64);
+
+    Some(EncryptionInfo {
+        security_handler: name(encrypt, b"Filter")
+            .map(|filter| String::from_utf8_lossy(&filter).into_owned())
+            .unwrap_or_else(|| "Standard".to_string()),
+        version,
+        revision,
+        key_length: number(b"Length")
+            .filter(|_| version >= 2)
+            .and_then(|length| usize::try_from(length).ok())
+            .unwrap_or(default_key_length),
+        cipher: cipher.to_string(),
+        permissions: DocumentPermissions::from_bits(permissions, revision),
+    })
+}
+
+/// Cipher of the streams and its default key length in bits, from the algorithm version
+/// and the crypt filter method
+fn cipher(version: i64, stream_method: Option<&[u8]>) -> (&'static str, usize) {
+    // Crypt filters only exist from version 4 on; earlier versions always use RC4
+    match (version, stream_method) {
+        (1..=3, _) => ("RC4", 40),
+        (_, Some(b"V2")) => ("RC4", 128),
+        (_, Some(b"AESV2")) => ("AES-128", 128),
+        (_, Some(b"AESV3")) => ("AES-256", 256),
+        (5, _) => ("AES-256", 256),
+        _ => ("Identity", 128),
+    }
+}
+
+/// Parse an encrypted document without decrypting it
+///
+/// The objects hold their encrypted strings and streams, but dictionaries, such as the
+/// page tree and the `/Encrypt` dictionary, can be read. Objects packed in object streams
+/// are not available.
+pub(crate) fn load_locked_document(bytes: &[u8]) -> Result<Document, PdfError> {
+    let masked = mask_encrypt_entries(bytes);
+
+    let mut doc = Reader {
+        buffer: &masked,
+        document: Document::new(),
+        encryption_state: None,
+        raw_objects: BTreeMap::new(),
+    }
+    .read(Some(hide_object_streams))
+    .map_err(PdfError::from)?;
+
+    // Restore what was hidden so lopdf sees the document as written
+    if let Some(encrypt) = doc.trailer.remove(&MASKED_ENCRYPT_KEY[1..]) {
+        doc.trailer.set("Encrypt", encrypt);
+    }
+    for object in doc.objects.values_mut() {
+        if let Ok(stream) = object.as_stream_mut()
+            && stream.dict.has_type(HIDDEN_OBJECT_STREAM_TYPE)
+        {
+            stream.dict.set("Type", Object::Name(b"ObjStm".to_vec()));
+        }
+    }
+    Ok(doc)
+}
+
+/// Load and decrypt a document that lopdf could not open with the empty user password
+///
+/// lopdf skips parsing the objects of such files, so the file is parsed with its
+/// `/Encrypt` entry hidden and then decrypted in place once every object is available.
+/// `password` may be either the user or the owner password.
+pub(crate) fn load_encrypted_document(bytes: &[u8], password: Option<&str>) -> Result<Document, PdfError> {
+    let mut doc = load_locked_document(bytes)?;
+
+    let key_password = password_for_key(&doc, password.unwrap_or("")).map_err(|e| map_decrypt_error(e, password))?;
+    let encrypt = doc.trailer.get(b"Encrypt").and_then(Object::as_reference).ok();
+    let encrypt = encrypt.and_then(|id| Some((id, doc.objects.get(&id)?.clone())));
+    doc.decrypt_raw(&key_password).map_err(|e| map_decrypt_error(e, password))?;
+
+    // lopdf drops the /Encrypt dictionary here but keeps it for documents it decrypts on
+    // load; keep it either way so the encryption settings can be reported
+    if let Some((id, dictionary)) = encrypt {
+        doc.objects.insert(id, dictionary);
+        doc.trailer.set("Encrypt", id);
+    }
+
+    Ok(doc)
+}
+
+/// Convert a lopdf decryption failure into a `PdfError`
+fn map_decrypt_error(err: lopdf::Error, password: Option<&str>) -> PdfError {
+    match err {
+        lopdf::Error::Decryption(DecryptionError::IncorrectPassword) => match password {
+            Some(_) => PdfError::IncorrectPassword,
+            None => PdfError::EncryptedDocument,
+        },
+        lopdf::Error::Decryption(_) => PdfError::ParseError(format!("Failed to decrypt document: {}", err)),
+        err => PdfError::from(err),
+    }
+}
+
+/// Return the password bytes the file encryption key must be derived from
+///
+/// On revision 4 and earlier the key is always computed from the user password, so an
+/// owner password is first turned back into the user password (ISO 32000-2, Algorithm 7).
+fn password_for_key(doc: &Document, password: &str) -> Result<Vec<u8>, lopdf::Error> {
+    let algorithm = PasswordAlgorithm::try_from(doc)?;
+    let password = algorithm.sanitize_password(password)?;
+
+    let encrypt = doc.get_encrypted()?;
+    let revision = encrypt.get(b"R").and_then(Object::as_i64)?;
+
+    if revision <= 4
+        && doc.authenticate_raw_user_password(&password).is_err()
+        && doc.authenticate_raw_owner_password(&password).is_ok()
+    {
+        let key_length = encrypt.get(b"Length").and_then(Object::as_i64).unwrap_or(40);
+        let owner_value = encrypt.get(b"O").and_then(Object::as_str)?;
+        return Ok(recover_user_password(&password, owner_value, revision, key_length));
+    }
+
+    Ok(password)
+}
+
+/// Decrypt the `/O` value with a key derived from the owner password
+fn recover_user_password(owner_password: &[u8], owner_value: &[u8], revision: i64, key_length: i64) -> Vec<u8> {
+    let len = owner_password.len().min(32);
+
+    let mut hasher = Md5::new();
+    hasher.update(&owner_password[..len]);
+    hasher.update(&PAD_BYTES[..32 - len]);
+    let mut hash = hasher.finalize();
+
+    let n = if revision >= 3 {
+        (key_length / 8).clamp(5, 16) as usize
+    } else {
+        5
+    };
+
+    if revision >= 3 {
+        for _ in 0..50 {
+            hash = Md5::digest(hash);
+        }
+    }
+
+    let mut result = owner_value.to_vec();
+    if revision >= 3 {
+        for i in (1..=19u8).rev() {
+            let key: Vec<u8> = hash[..n].iter().map(|b| b ^ i).collect();
+            result = rc4(&key, &result);
+        }
+    }
+
+    // The padded user password; key derivation pads to 32 bytes anyway
+    rc4(&hash[..n], &result)
+}
+
+/// Apply the RC4 keystream for `key` to `data`
+fn rc4(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut state: Vec<u8> = (0..=255).collect();
+    let mut j = 0u8;
+    for i in 0..256 {
+        j = j.wrapping_add(state[i]).wrapping_add(key[i % key.len()]);
+        state.swap(i, j as usize);
+    }
+
+    let (mut i, mut j) = (0u8, 0u8);
+    data.iter()
+        .map(|byte| {
+            i = i.wrapping_add(1);
+            j = j.wrapping_add(state[i as usize]);
+            state.swap(i as usize, j as usize);
+            byte ^ state[state[i as usize].wrapping_add(state[j as usize]) as usize]
+        })
+        .collect()
+}
+
+/// Rename every `/Encrypt` key so lopdf parses the file as unencrypted
+///
+/// The replacement has the same length, which keeps all xref offsets valid.
+fn mask_encrypt_entries(bytes: &[u8]) -> Vec<u8> {
+    const KEY: &[u8] = b"/Encrypt";
+
+    let mut masked = bytes.to_vec();
+    let mut pos = 0;
+    while let Some(offset) = masked[pos..].windows(KEY.len()).position(|w| w == KEY) {
+        let start = pos + offset;
+        let end = start + KEY.len();
+        // Only whole names: `/EncryptMetadata` must stay untouched
+        let is_whole_name = masked
+            .get(end)
+            .is_none_or(|b| b.is_ascii_whitespace() || b"/<>[]()%".contains(b));
+        if is_whole_name {
+            masked[start..end].copy_from_slice(MASKED_ENCRYPT_KEY);
+        }
+        pos = end;
+    }
+    masked
+}
+
+/// Keep lopdf from unpacking object streams before they are decrypted
+fn hide_object_streams(id: ObjectId, object: &mut Object) -> Option<(ObjectId, Object)> {
+    if let Ok(stream) = object.as_stream_mut()
+        && stream.dict.has_type(b"ObjStm")
+    {
+        stream.dict.set("Type", Object::Name(HIDDEN_OBJECT_STREAM_TYPE.to_vec()));
+    }
+    Some((id, object.clone()))
+}
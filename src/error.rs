@@ -1,6 +1,8 @@
 //! Error types for the PDF Reader MCP Server
 
+use lopdf::encryption::DecryptionError;
 use rmcp::model::ErrorData;
+use serde_json::{json, Value};
 use thiserror::Error;
 
 /// Errors that can occur during PDF operations
@@ -15,39 +17,175 @@ pub enum PdfError {
     #[error("PDF parsing failed: {0}")]
     ParseError(String),
 
+    #[error("Corrupt cross-reference table: {0}")]
+    CorruptXref(String),
+
+    #[error("PDF file is truncated: {0}")]
+    TruncatedFile(String),
+
+    #[error("Object {0} {1} R is missing from the document")]
+    MissingObject(u32, u16),
+
+    #[error("No valid object at byte offset {0}")]
+    DamagedObject(usize),
+
+    #[error("Reference cycle through object {0} {1} R")]
+    ReferenceCycle(u32, u16),
+
+    #[error("Invalid page tree: {0}")]
+    PageTree(String),
+
+    #[error("Unsupported PDF feature: {0}")]
+    UnsupportedFeature(String),
+
+    #[error("Unsupported encryption: {0}")]
+    UnsupportedEncryption(String),
+
     #[error("Page {0} does not exist (document has {1} pages)")]
     PageNotFound(u32, usize),
 
+    #[error("Invalid page selection '{0}': {1}")]
+    InvalidPageSelection(String, String),
+
+    #[error("Invalid value for option '{0}': {1}")]
+    InvalidOption(String, String),
+
+    #[error("Page label not found: {0}")]
+    PageLabelNotFound(String),
+
+    #[error("Section not found in document outline: {0}")]
+    SectionNotFound(String),
+
     #[error("Document is encrypted and requires a password")]
     EncryptedDocument,
 
+    #[error("Incorrect password for encrypted document")]
+    IncorrectPassword,
+
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
+
+    /// An error while reading one page; its code and details are those of the cause, with
+    /// the page number added to the details
+    #[error("Failed to read page {0}: {1}")]
+    Page(u32, Box<PdfError>),
 }
 
-impl From<PdfError> for ErrorData {
-    fn from(err: PdfError) -> Self {
-        match err {
-            PdfError::FileNotFound(path) => {
-                ErrorData::invalid_params(format!("File not found: {}", path), None)
-            }
-            PdfError::InvalidFormat(msg) => {
-                ErrorData::invalid_params(format!("Invalid PDF format: {}", msg), None)
+impl PdfError {
+    /// Stable machine-readable identifier of the error kind
+    pub fn code(&self) -> &'static str {
+        match self {
+            PdfError::FileNotFound(_) => "file_not_found",
+            PdfError::InvalidFormat(_) => "invalid_format",
+            PdfError::ParseError(_) => "parse_error",
+            PdfError::CorruptXref(_) => "corrupt_xref",
+            PdfError::TruncatedFile(_) => "truncated_file",
+            PdfError::MissingObject(..) => "missing_object",
+            PdfError::DamagedObject(_) => "damaged_object",
+            PdfError::ReferenceCycle(..) => "reference_cycle",
+            PdfError::PageTree(_) => "invalid_page_tree",
+            PdfError::UnsupportedFeature(_) => "unsupported_feature",
+            PdfError::UnsupportedEncryption(_) => "unsupported_encryption",
+            PdfError::PageNotFound(..) => "page_not_found",
+            PdfError::InvalidPageSelection(..) => "invalid_page_selection",
+            PdfError::InvalidOption(..) => "invalid_option",
+            PdfError::PageLabelNotFound(_) => "page_label_not_found",
+            PdfError::SectionNotFound(_) => "section_not_found",
+            PdfError::EncryptedDocument => "encrypted_document",
+            PdfError::IncorrectPassword => "incorrect_password",
+            PdfError::IoError(_) => "io_error",
+            PdfError::Page(_, cause) => cause.code(),
+        }
+    }
+
+    /// Structured details of the error, for the `data` field of MCP errors
+    pub fn details(&self) -> Value {
+        match self {
+            PdfError::FileNotFound(path) => json!({ "path": path }),
+            PdfError::InvalidFormat(reason)
+            | PdfError::ParseError(reason)
+            | PdfError::CorruptXref(reason)
+            | PdfError::TruncatedFile(reason)
+            | PdfError::PageTree(reason) => json!({ "reason": reason }),
+            PdfError::MissingObject(number, generation) | PdfError::ReferenceCycle(number, generation) => {
+                json!({ "object": number, "generation": generation })
             }
-            PdfError::ParseError(msg) => {
-                ErrorData::internal_error(format!("PDF parsing failed: {}", msg), None)
+            PdfError::DamagedObject(offset) => json!({ "offset": offset }),
+            PdfError::UnsupportedFeature(feature) => json!({ "feature": feature }),
+            PdfError::UnsupportedEncryption(scheme) => json!({ "scheme": scheme }),
+            PdfError::PageNotFound(page, page_count) => json!({ "page": page, "page_count": page_count }),
+            PdfError::InvalidPageSelection(token, reason) => json!({ "token": token, "reason": reason }),
+            PdfError::InvalidOption(option, reason) => json!({ "option": option, "reason": reason }),
+            PdfError::PageLabelNotFound(label) => json!({ "label": label }),
+            PdfError::SectionNotFound(section) => json!({ "section": section }),
+            PdfError::EncryptedDocument | PdfError::IncorrectPassword => json!({}),
+            PdfError::IoError(e) => json!({ "kind": e.kind().to_string(), "reason": e.to_string() }),
+            PdfError::Page(page, cause) => {
+                let mut details = cause.details();
+                if let Some(details) = details.as_object_mut() {
+                    details.insert("page".to_string(), json!(page));
+                }
+                details
             }
-            PdfError::PageNotFound(page, total) => ErrorData::invalid_params(
-                format!("Page {} does not exist (document has {} pages)", page, total),
-                None,
-            ),
-            PdfError::EncryptedDocument => ErrorData::invalid_params(
-                "Document is encrypted and requires a password",
-                None,
-            ),
-            PdfError::IoError(e) => {
-                ErrorData::internal_error(format!("IO error: {}", e), None)
+        }
+    }
+}
+
+impl From<lopdf::Error> for PdfError {
+    fn from(err: lopdf::Error) -> Self {
+        use lopdf::Error as E;
+
+        match err {
+            // A mismatch means the object at an xref offset is not the one the xref promised
+            E::Xref(_) | E::MissingXrefEntry | E::InvalidOffset(_) | E::ObjectIdMismatch => {
+                PdfError::CorruptXref(err.to_string())
             }
+            // lopdf does not export its parse error type, so its variants are told apart
+            // by their fixed messages
+            E::Parse(ref parse) => match parse.to_string().as_str() {
+                "unexpected end of input" => PdfError::TruncatedFile(err.to_string()),
+                "invalid file header" => PdfError::InvalidFormat(err.to_string()),
+                "invalid cross reference table" | "invalid file trailer" => PdfError::CorruptXref(err.to_string()),
+                _ => PdfError::ParseError(err.to_string()),
+            },
+            E::ObjectNotFound((number, generation)) => PdfError::MissingObject(number, generation),
+            E::IndirectObject { offset } => PdfError::DamagedObject(offset),
+            E::ReferenceCycle((number, generation)) => PdfError::ReferenceCycle(number, generation),
+            E::PageNumberNotFound(page) => PdfError::PageTree(format!("page {} is not in the page tree", page)),
+            // lopdf cannot decode some stream filters and inline image colour spaces
+            E::Unimplemented(feature) => PdfError::UnsupportedFeature(feature.to_string()),
+            E::UnsupportedSecurityHandler(handler) => PdfError::UnsupportedEncryption(format!(
+                "security handler /{}",
+                String::from_utf8_lossy(&handler)
+            )),
+            E::Decryption(
+                DecryptionError::UnsupportedEncryption
+                | DecryptionError::UnsupportedVersion
+                | DecryptionError::UnsupportedRevision,
+            ) => PdfError::UnsupportedEncryption(err.to_string()),
+            E::Decryption(DecryptionError::IncorrectPassword) => PdfError::IncorrectPassword,
+            E::IO(e) => PdfError::IoError(e),
+            _ => PdfError::ParseError(err.to_string()),
+        }
+    }
+}
+
+impl From<PdfError> for ErrorData {
+    fn from(err: PdfError) -> Self {
+        let message = err.to_string();
+        let data = Some(json!({ "code": err.code(), "details": err.details() }));
+
+        let mut cause = &err;
+        while let PdfError::Page(_, inner) = cause {
+            cause = inner;
+        }
+        match cause {
+            // Failures on our side rather than problems with the request or the document
+            PdfError::ParseError(_)
+            | PdfError::UnsupportedFeature(_)
+            | PdfError::UnsupportedEncryption(_)
+            | PdfError::IoError(_) => ErrorData::internal_error(message, data),
+            _ => ErrorData::invalid_params(message, data),
         }
     }
 }
@@ -0,0 +1,232 @@
+//! Text recovery for fonts whose PDF dictionaries give no usable Unicode mapping
+//!
+//! Embedded font programs carry their own glyph names and character maps: the built-in
+//! encoding of a Type 1 program, the charset and encoding of a CFF program, and the
+//! `cmap` and `post` tables of a TrueType or OpenType program. Type3 fonts have no program,
+//! but their glyph procedures are named, and producers such as TeX name them after the
+//! character code (`/a65`). Text found this way is reported as recovered heuristically.
+
+use crate::glyph_list::glyph_to_unicode;
+use lopdf::{Dictionary, Document, Object};
+use std::collections::HashMap;
+use ttf_parser::{cff, Face, GlyphId, PlatformId};
+
+/// Text of the TeX OT1 codes below 32 that Type3 bitmap fonts from TeX keep at their
+/// original positions: ligatures and letters missing from ASCII
+const TEX_CONTROL_CODES: [(u8, &str); 13] = [
+    (0x0B, "ff"),
+    (0x0C, "fi"),
+    (0x0D, "fl"),
+    (0x0E, "ffi"),
+    (0x0F, "ffl"),
+    (0x10, "ı"),
+    (0x19, "ß"),
+    (0x1A, "æ"),
+    (0x1B, "œ"),
+    (0x1C, "ø"),
+    (0x1D, "Æ"),
+    (0x1E, "Œ"),
+    (0x1F, "Ø"),
+];
+
+/// Formats of embedded font programs
+enum ProgramKind {
+    /// `/FontFile`: a Type 1 program, whose first `cleartext` bytes hold the encoding
+    Type1 { cleartext: usize },
+    /// `/FontFile3` with subtype `Type1C` or `CIDFontType0C`: a bare CFF program
+    Cff,
+    /// `/FontFile2`, or `/FontFile3` with subtype `OpenType`
+    OpenType,
+}
+
+/// A font program embedded in a font descriptor
+pub(crate) struct FontProgram {
+    kind: ProgramKind,
+    data: Vec<u8>,
+}
+
+impl FontProgram {
+    /// Load the program embedded in a font descriptor, if any
+    pub fn load(doc: &Document, descriptor: &Dictionary) -> Option<Self> {
+        let stream = |key: &[u8]| {
+            let (_, object) = doc.dereference(descriptor.get(key).ok()?).ok()?;
+            object.as_stream().ok()
+        };
+
+        let (kind, stream) = if let Some(stream) = stream(b"FontFile") {
+            let cleartext = stream.dict.get(b"Length1").and_then(Object::as_i64).unwrap_or(0).max(0) as usize;
+            (ProgramKind::Type1 { cleartext }, stream)
+        } else if let Some(stream) = stream(b"FontFile2") {
+            (ProgramKind::OpenType, stream)
+        } else if let Some(stream) = stream(b"FontFile3") {
+            match stream.dict.get(b"Subtype").and_then(Object::as_name) {
+                Ok(b"OpenType") => (ProgramKind::OpenType, stream),
+                _ => (ProgramKind::Cff, stream),
+            }
+        } else {
+            return None;
+        };
+
+        let data = match stream.filters() {
+            Ok(filters) if !filters.is_empty() => stream.decompressed_content().ok()?,
+            _ => stream.content.clone(),
+        };
+        Some(FontProgram { kind, data })
+    }
+
+    /// Text of each single-byte code under the program's built-in encoding
+    ///
+    /// Returns `None` when the program has no encoding of its own (a Type 1 program that
+    /// uses StandardEncoding) or cannot be parsed.
+    pub fn builtin_encoding(&self) -> Option<Vec<Option<String>>> {
+        match self.kind {
+            ProgramKind::Type1 { cleartext } => type1_encoding(&self.data, cleartext),
+            ProgramKind::Cff => {
+                let table = cff::Table::parse(&self.data)?;
+                Some(
+                    (0..=255u8)
+                        .map(|code| {
+                            let glyph = table.glyph_index(code).filter(|glyph| glyph.0 != 0)?;
+                            glyph_to_unicode(table.glyph_name(glyph)?)
+                        })
+                        .collect(),
+                )
+            }
+            ProgramKind::OpenType => {
+                let face = Face::parse(&self.data, 0).ok()?;
+                let texts = glyph_texts(&face);
+                Some(
+                    (0..=255u8)
+                        .map(|code| texts.get(&symbolic_glyph(&face, code)?.0).cloned())
+                        .collect(),
+                )
+            }
+        }
+    }
+
+    /// Text of a glyph name unknown to the Adobe Glyph List, via the glyph it names in the
+    /// program's `post` table and the program's Unicode `cmap`
+    pub fn glyph_name_text(&self, name: &str) -> Option<String> {
+        let ProgramKind::OpenType = self.kind else {
+            return None;
+        };
+        let face = Face::parse(&self.data, 0).ok()?;
+        let glyph = face.glyph_index_by_name(name)?;
+        glyph_texts(&face).remove(&glyph.0)
+    }
+
+    /// Text of each glyph, by glyph id
+    ///
+    /// Glyphs are mapped through the program's Unicode `cmap` where it has one, otherwise
+    /// through their names. CID-keyed CFF programs have neither and yield nothing.
+    pub fn glyph_texts(&self) -> HashMap<u16, String> {
+        match self.kind {
+            ProgramKind::Type1 { .. } => HashMap::new(),
+            ProgramKind::Cff => {
+                let Some(table) = cff::Table::parse(&self.data) else {
+                    return HashMap::new();
+                };
+                (1..table.number_of_glyphs())
+                    .filter_map(|glyph| Some((glyph, glyph_to_unicode(table.glyph_name(GlyphId(glyph))?)?)))
+                    .collect()
+            }
+            ProgramKind::OpenType => Face::parse(&self.data, 0).map(|face| glyph_texts(&face)).unwrap_or_default(),
+        }
+    }
+}
+
+/// Text of each glyph of a TrueType or OpenType program: the lowest code point mapping
+/// to it in a Unicode `cmap` subtable, otherwise its glyph name
+fn glyph_texts(face: &Face) -> HashMap<u16, String> {
+    let mut texts: HashMap<u16, (u32, String)> = HashMap::new();
+    if let Some(cmap) = face.tables().cmap {
+        for subtable in cmap.subtables.into_iter().filter(|subtable| subtable.is_unicode()) {
+            subtable.codepoints(|code_point| {
+                // Private use code points carry no meaning of their own
+                let Some(c) = char::from_u32(code_point).filter(|c| !('\u{E000}'..='\u{F8FF}').contains(c)) else {
+                    return;
+                };
+                if let Some(glyph) = subtable.glyph_index(code_point).filter(|glyph| glyph.0 != 0) {
+                    let entry = texts.entry(glyph.0).or_insert((code_point, c.to_string()));
+                    if code_point < entry.0 {
+                        *entry = (code_point, c.to_string());
+                    }
+                }
+            });
+        }
+    }
+
+    let mut texts: HashMap<u16, String> = texts.into_iter().map(|(glyph, (_, text))| (glyph, text)).collect();
+    for glyph in 1..face.number_of_glyphs() {
+        if let std::collections::hash_map::Entry::Vacant(entry) = texts.entry(glyph)
+            && let Some(text) = face.glyph_name(GlyphId(glyph)).and_then(glyph_to_unicode)
+        {
+            entry.insert(text);
+        }
+    }
+    texts
+}
+
+/// Glyph of a single-byte code in a symbolic TrueType font: through the (3, 0) subtable,
+/// whose codes may be offset into the private use area, otherwise the (1, 0) subtable
+fn symbolic_glyph(face: &Face, code: u8) -> Option<GlyphId> {
+    let cmap = face.tables().cmap?;
+    let subtable = |platform: PlatformId, encoding: u16| {
+        cmap.subtables.into_iter().find(|subtable| subtable.platform_id == platform && subtable.encoding_id == encoding)
+    };
+    let code = u32::from(code);
+
+    if let Some(symbol) = subtable(PlatformId::Windows, 0) {
+        return [0xF000, 0xF100, 0xF200, 0]
+            .into_iter()
+            .find_map(|offset| symbol.glyph_index(offset + code))
+            .filter(|glyph| glyph.0 != 0);
+    }
+    subtable(PlatformId::Macintosh, 0)?.glyph_index(code).filter(|glyph| glyph.0 != 0)
+}
+
+/// Text of each code under the `/Encoding` array in the cleartext part of a Type 1 program
+fn type1_encoding(data: &[u8], cleartext: usize) -> Option<Vec<Option<String>>> {
+    let end = if cleartext > 0 && cleartext <= data.len() {
+        cleartext
+    } else {
+        data.windows(5).position(|window| window == b"eexec").unwrap_or(data.len())
+    };
+    let header = String::from_utf8_lossy(&data[..end]);
+    let (_, encoding) = header.split_once("/Encoding")?;
+    let encoding = encoding.replace('/', " /");
+    let mut tokens = encoding.split_ascii_whitespace();
+    if tokens.next()? == "StandardEncoding" {
+        return None;
+    }
+
+    let mut table = vec![None; 256];
+    let tokens: Vec<&str> = tokens.take_while(|token| *token != "def").collect();
+    for window in tokens.windows(4) {
+        if let ["dup", code, name, "put"] = window
+            && let (Ok(code), Some(name)) = (code.parse::<usize>(), name.strip_prefix('/'))
+            && code < table.len()
+        {
+            table[code] = glyph_to_unicode(name);
+        }
+    }
+    Some(table)
+}
+
+/// Text of a Type3 glyph whose name is unknown to the Adobe Glyph List
+///
+/// Names made of a short prefix and a number (`a65`, `c7`) are read as that
+/// character code. Otherwise the code itself is taken as ASCII, or as one of the TeX
+/// ligatures and letters TeX fonts keep below 32.
+pub(crate) fn type3_glyph_text(name: &str, code: u8) -> Option<String> {
+    let digits = name.trim_start_matches(|c: char| c.is_ascii_alphabetic());
+    let code = match digits.parse::<u32>() {
+        Ok(number) if name.len() - digits.len() <= 5 && !digits.is_empty() => u8::try_from(number).ok()?,
+        _ => code,
+    };
+
+    match code {
+        0x21..=0x7E => Some(char::from(code).to_string()),
+        _ => TEX_CONTROL_CODES.iter().find(|(tex, _)| *tex == code).map(|(_, text)| text.to_string()),
+    }
+}
@@ -0,0 +1,594 @@
+//! Font metrics and character decoding for the content stream interpreter
+
+use crate::cmap::{code_value, is_vertical_name, CMap, CodeMap, PredefinedCMap};
+use crate::font_program::{self, FontProgram};
+use crate::layout::FontRecovery;
+use crate::{encodings, glyph_list};
+use lopdf::{Dictionary, Document, Object};
+use std::cell::OnceCell;
+use std::collections::HashMap;
+
+/// Font descriptor flag of fonts using glyphs outside the standard Latin set
+const SYMBOLIC: i64 = 1 << 2;
+
+/// Font descriptor flag of fonts using only the standard Latin set
+const NONSYMBOLIC: i64 = 1 << 5;
+
+/// Text of a character code, and how it was found when the PDF does not say
+type CodeText = Option<(String, Option<FontRecovery>)>;
+
+/// Advance widths of the printable ASCII range (0x20..=0x7E), in glyph units
+type AsciiWidths = [u16; 95];
+
+#[rustfmt::skip]
+const HELVETICA_WIDTHS: AsciiWidths = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556,
+    1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556,
+    333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556,
+    556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+#[rustfmt::skip]
+const HELVETICA_BOLD_WIDTHS: AsciiWidths = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611,
+    975, 722, 722, 722, 722, 667, 611, 778, 722, 278, 556, 722, 611, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 333, 278, 333, 584, 556,
+    333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556, 278, 889, 611, 611,
+    611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+#[rustfmt::skip]
+const TIMES_ROMAN_WIDTHS: AsciiWidths = [
+    250, 333, 408, 500, 500, 833, 778, 180, 333, 333, 500, 564, 250, 333, 250, 278,
+    500, 500, 500, 500, 500, 500, 500, 500, 500, 500, 278, 278, 564, 564, 564, 444,
+    921, 722, 667, 667, 722, 611, 556, 722, 722, 333, 389, 722, 611, 889, 722, 722,
+    556, 722, 667, 556, 611, 722, 722, 944, 722, 722, 611, 333, 278, 333, 469, 500,
+    333, 444, 500, 444, 500, 444, 333, 500, 500, 278, 278, 500, 278, 778, 500, 500,
+    500, 500, 333, 389, 278, 500, 500, 722, 500, 500, 444, 480, 200, 480, 541,
+];
+
+#[rustfmt::skip]
+const TIMES_BOLD_WIDTHS: AsciiWidths = [
+    250, 333, 555, 500, 500, 1000, 833, 278, 333, 333, 500, 570, 250, 333, 250, 278,
+    500, 500, 500, 500, 500, 500, 500, 500, 500, 500, 333, 333, 570, 570, 570, 500,
+    930, 722, 667, 722, 722, 667, 611, 778, 778, 389, 500, 778, 667, 944, 722, 778,
+    611, 778, 722, 556, 667, 722, 722, 1000, 722, 722, 667, 333, 278, 333, 581, 500,
+    333, 500, 556, 444, 556, 444, 333, 500, 556, 278, 333, 556, 278, 833, 556, 500,
+    556, 556, 444, 389, 333, 556, 500, 722, 500, 500, 444, 394, 220, 394, 520,
+];
+
+/// Metrics of the standard 14 fonts, which PDFs may use without embedding widths
+#[derive(Clone, Copy)]
+enum StandardFont {
+    Helvetica,
+    HelveticaBold,
+    TimesRoman,
+    TimesBold,
+    Courier,
+}
+
+impl StandardFont {
+    /// Recognise a standard font (or a common alias) from its base font name
+    fn from_base_font(name: &str) -> Option<Self> {
+        // Subset prefixes ("ABCDEF+Helvetica") and style suffixes vary between producers
+        let name = name.split_once('+').map_or(name, |(_, rest)| rest).to_ascii_lowercase();
+        let bold = name.contains("bold");
+
+        if name.starts_with("courier") {
+            Some(StandardFont::Courier)
+        } else if name.starts_with("times") {
+            Some(if bold { StandardFont::TimesBold } else { StandardFont::TimesRoman })
+        } else if name.starts_with("helvetica") || name.starts_with("arial") {
+            Some(if bold { StandardFont::HelveticaBold } else { StandardFont::Helvetica })
+        } else {
+            None
+        }
+    }
+
+    /// Advance width of a character in glyph units
+    fn width(self, c: char) -> f64 {
+        let table = match self {
+            StandardFont::Courier => return 600.0,
+            StandardFont::Helvetica => &HELVETICA_WIDTHS,
+            StandardFont::HelveticaBold => &HELVETICA_BOLD_WIDTHS,
+            StandardFont::TimesRoman => &TIMES_ROMAN_WIDTHS,
+            StandardFont::TimesBold => &TIMES_BOLD_WIDTHS,
+        };
+        match c {
+            ' '..='~' => table[c as usize - 0x20] as f64,
+            _ => table[b'n' as usize - 0x20] as f64,
+        }
+    }
+}
+
+/// A font resource, ready to split strings into character codes, decode and measure them
+pub(crate) struct Font {
+    /// Base font name without subset prefix
+    pub name: String,
+    /// `/ToUnicode` CMap, which takes precedence over every other source of text
+    to_unicode: Option<CMap>,
+    /// Code to text table of a simple font: its base encoding with `/Differences` applied
+    simple_encoding: Vec<CodeText>,
+    /// Encoding CMap of a composite (Type0) font
+    code_map: Option<CodeMap>,
+    /// Text of CIDs recovered from the embedded font program of a composite font
+    cid_texts: HashMap<u32, String>,
+    widths: HashMap<u32, f64>,
+    default_width: f64,
+    /// Whether the font writes top to bottom (a composite font with a vertical CMap)
+    pub vertical: bool,
+    /// Vertical advance and position vector of CIDs, from `/W2`: w1y, v_x, v_y
+    vertical_metrics: HashMap<u32, [f64; 3]>,
+    /// Default position vector height and vertical advance, from `/DW2`
+    default_vertical: [f64; 2],
+    standard: Option<StandardFont>,
+    /// Ascent and descent as fractions of the font size
+    pub ascent: f64,
+    pub descent: f64,
+    /// Converts glyph space widths to thousandths of text space (Type3 fonts)
+    width_scale: f64,
+}
+
+impl Font {
+    /// Load the metrics and encoding of a font dictionary
+    pub fn load(doc: &Document, dict: &Dictionary) -> Self {
+        let subtype = dict.get(b"Subtype").and_then(Object::as_name).unwrap_or(b"");
+        let composite = subtype == b"Type0";
+        // Type3 fonts have an optional /Name instead of a base font
+        let base_font = dict
+            .get(b"BaseFont")
+            .or_else(|_| dict.get(b"Name"))
+            .and_then(Object::as_name)
+            .map(|name| String::from_utf8_lossy(name).into_owned())
+            .unwrap_or_default();
+        let name = base_font.split_once('+').map_or(base_font.as_str(), |(_, rest)| rest).to_string();
+
+        // Widths and the descriptor of composite fonts live on the descendant font
+        let metrics_dict = if composite {
+            dict.get(b"DescendantFonts")
+                .ok()
+                .and_then(|fonts| doc.dereference(fonts).ok())
+                .and_then(|(_, fonts)| fonts.as_array().ok())
+                .and_then(|fonts| fonts.first())
+                .and_then(|font| doc.dereference(font).ok())
+                .and_then(|(_, font)| font.as_dict().ok())
+                .unwrap_or(dict)
+        } else {
+            dict
+        };
+
+        let descriptor = metrics_dict
+            .get(b"FontDescriptor")
+            .ok()
+            .and_then(|descriptor| doc.dereference(descriptor).ok())
+            .and_then(|(_, descriptor)| descriptor.as_dict().ok());
+        let descriptor_number = |key: &[u8]| descriptor.and_then(|d| d.get(key).ok()).and_then(|n| number(doc, n));
+
+        let (widths, default_width) = if composite {
+            (cid_widths(doc, metrics_dict), metrics_dict.get(b"DW").ok().and_then(|w| number(doc, w)).unwrap_or(1000.0))
+        } else {
+            (simple_widths(doc, dict), descriptor_number(b"MissingWidth").unwrap_or(0.0))
+        };
+
+        let width_scale = if subtype == b"Type3" {
+            dict.get(b"FontMatrix")
+                .ok()
+                .and_then(|matrix| doc.dereference(matrix).ok())
+                .and_then(|(_, matrix)| matrix.as_array().ok())
+                .and_then(|matrix| matrix.first())
+                .and_then(|a| number(doc, a))
+                .map_or(1.0, |a| a * 1000.0)
+        } else {
+            1.0
+        };
+
+        let ascent = descriptor_number(b"Ascent").filter(|a| *a > 0.0).unwrap_or(800.0) / 1000.0;
+        let descent = descriptor_number(b"Descent").filter(|d| *d < 0.0).unwrap_or(-200.0) / 1000.0;
+
+        let to_unicode = stream_data(doc, dict.get(b"ToUnicode").ok()).map(|data| CMap::parse(&data));
+        // Font programs are only read when the PDF's own mappings fall short
+        let program = OnceCell::new();
+        let program = || program.get_or_init(|| descriptor.and_then(|d| FontProgram::load(doc, d))).as_ref();
+        let flags = descriptor.and_then(|d| d.get(b"Flags").ok()).and_then(|f| f.as_i64().ok()).unwrap_or(0);
+
+        let (simple_encoding, code_map, cid_texts, vertical) = if composite {
+            let (code_map, vertical) = code_map(doc, dict);
+            let cid_texts = match (&to_unicode, &code_map) {
+                (None, CodeMap::Predefined(PredefinedCMap::Identity) | CodeMap::Embedded(_)) => {
+                    program().map(|program| cid_texts(doc, metrics_dict, program)).unwrap_or_default()
+                }
+                _ => HashMap::new(),
+            };
+            (Vec::new(), Some(code_map), cid_texts, vertical)
+        } else {
+            let program = || program().filter(|_| to_unicode.is_none());
+            (simple_encoding(doc, dict, subtype, &name, flags, program), None, HashMap::new(), false)
+        };
+
+        let (vertical_metrics, default_vertical) = if vertical {
+            let default = metrics_dict
+                .get(b"DW2")
+                .ok()
+                .and_then(|dw2| doc.dereference(dw2).ok())
+                .and_then(|(_, dw2)| dw2.as_array().ok())
+                .and_then(|dw2| Some([number(doc, dw2.first()?)?, number(doc, dw2.get(1)?)?]))
+                .unwrap_or([880.0, -1000.0]);
+            (cid_vertical_metrics(doc, metrics_dict), default)
+        } else {
+            (HashMap::new(), [880.0, -1000.0])
+        };
+
+        Font {
+            standard: StandardFont::from_base_font(&name),
+            name,
+            to_unicode,
+            simple_encoding,
+            code_map,
+            cid_texts,
+            widths,
+            default_width,
+            vertical,
+            vertical_metrics,
+            default_vertical,
+            ascent,
+            descent,
+            width_scale,
+        }
+    }
+
+    /// Split a string operand into character codes
+    pub fn codes<'s>(&self, bytes: &'s [u8]) -> Vec<&'s [u8]> {
+        match &self.code_map {
+            Some(code_map) => code_map.split(bytes),
+            None => bytes.chunks(1).collect(),
+        }
+    }
+
+    /// Whether word spacing applies to a code (single-byte code 32 only)
+    pub fn is_word_space(code: &[u8]) -> bool {
+        code == [b' ']
+    }
+
+    /// Unicode text of a character code, and how it was recovered when the PDF does not
+    /// map it
+    ///
+    /// The `/ToUnicode` CMap is consulted first, then the font's encoding, then text
+    /// recovered from the font program. Codes that none of them maps decode to U+FFFD, so
+    /// the glyph still takes its place in the text.
+    pub fn decode(&self, code: &[u8]) -> (String, Option<FontRecovery>) {
+        if let Some(text) = self.to_unicode.as_ref().and_then(|cmap| cmap.to_unicode(code)) {
+            return (text, None);
+        }
+        let text = match &self.code_map {
+            Some(code_map) => code_map.to_unicode(code).map(|text| (text, None)).or_else(|| {
+                let text = self.cid_texts.get(&code_map.cid(code)?)?;
+                Some((text.clone(), Some(FontRecovery::FontProgram)))
+            }),
+            None => self.simple_encoding.get(code_value(code) as usize).cloned().flatten(),
+        };
+        text.unwrap_or_else(|| (char::REPLACEMENT_CHARACTER.to_string(), None))
+    }
+
+    /// Width of the space character as a fraction of the font size, when the font defines it
+    pub fn space_width(&self) -> Option<f64> {
+        if self.code_map.is_some() {
+            // CIDs of spaces depend on the font's character collection
+            return None;
+        }
+        match (self.widths.get(&u32::from(b' ')), self.standard) {
+            (Some(width), _) if *width > 0.0 => Some(*width * self.width_scale / 1000.0),
+            (_, Some(standard)) => Some(standard.width(' ') / 1000.0),
+            _ => None,
+        }
+    }
+
+    /// Horizontal advance of a code as a fraction of the font size
+    pub fn width(&self, code: &[u8], text: &str) -> f64 {
+        // Composite fonts index widths by CID, simple fonts by code
+        let key = match &self.code_map {
+            Some(code_map) => code_map.cid(code),
+            None => Some(code_value(code)),
+        };
+        let width = match key.and_then(|key| self.widths.get(&key)) {
+            Some(width) => *width * self.width_scale,
+            None => match (self.standard, text.chars().next()) {
+                (Some(standard), Some(c)) => standard.width(c),
+                _ if self.default_width > 0.0 => self.default_width,
+                // Unknown metrics: an average Latin glyph
+                _ => 500.0,
+            },
+        };
+        width / 1000.0
+    }
+
+    /// Vertical advance (negative, downwards) and position vector of a code in a vertical
+    /// font, as fractions of the font size
+    ///
+    /// The position vector runs from the glyph's horizontal origin to its vertical origin,
+    /// the point that is placed at the current text position; `width` is the glyph's
+    /// horizontal advance, half of which is the default horizontal offset.
+    pub fn vertical_metrics(&self, code: &[u8], width: f64) -> (f64, f64, f64) {
+        let cid = self.code_map.as_ref().and_then(|code_map| code_map.cid(code));
+        match cid.and_then(|cid| self.vertical_metrics.get(&cid)) {
+            Some([advance, vx, vy]) => (advance / 1000.0, vx / 1000.0, vy / 1000.0),
+            None => {
+                let [vy, advance] = self.default_vertical;
+                (advance / 1000.0, width / 2.0, vy / 1000.0)
+            }
+        }
+    }
+}
+
+/// Code to text table of a simple font
+///
+/// The base encoding is the one named by `/Encoding` or its `/BaseEncoding`, otherwise the
+/// font's built-in encoding: the one in its embedded program where that differs from the
+/// defaults (Type 1 programs and symbolic TrueType fonts), Symbol's own for the Symbol
+/// font, WinAnsi for TrueType fonts (which mostly use it without saying so) and
+/// StandardEncoding for the rest. Glyph names in `/Differences` are mapped through the
+/// Adobe Glyph List and `uniXXXX` conventions, falling back to Type3 glyph name heuristics
+/// and the font program's glyph names.
+fn simple_encoding<'p>(
+    doc: &Document,
+    dict: &Dictionary,
+    subtype: &[u8],
+    name: &str,
+    flags: i64,
+    program: impl Fn() -> Option<&'p FontProgram>,
+) -> Vec<CodeText> {
+    let encoding = dict.get(b"Encoding").ok().and_then(|encoding| doc.dereference(encoding).ok()).map(|(_, e)| e);
+    let (base_name, differences) = match encoding {
+        Some(Object::Name(name)) => (Some(name.as_slice()), None),
+        Some(Object::Dictionary(encoding)) => (
+            encoding.get(b"BaseEncoding").and_then(Object::as_name).ok(),
+            encoding
+                .get(b"Differences")
+                .ok()
+                .and_then(|differences| doc.dereference(differences).ok())
+                .and_then(|(_, differences)| differences.as_array().ok()),
+        ),
+        _ => (None, None),
+    };
+
+    let declared = base_name.and_then(encodings::named_encoding);
+    let base = declared.unwrap_or(if name.starts_with("Symbol") {
+        &encodings::SYMBOL_ENCODING
+    } else if subtype == b"TrueType" {
+        &encodings::WIN_ANSI_ENCODING
+    } else {
+        &encodings::STANDARD_ENCODING
+    });
+    let mut table: Vec<CodeText> = base
+        .iter()
+        .map(|unit| char::from_u32(u32::from(*unit)).filter(|c| *c != '\0').map(|c| (c.to_string(), None)))
+        .collect();
+
+    let builtin = match subtype {
+        b"Type1" | b"MMType1" => true,
+        b"TrueType" => flags & SYMBOLIC != 0 && flags & NONSYMBOLIC == 0,
+        _ => false,
+    };
+    if declared.is_none()
+        && builtin
+        && let Some(builtin) = program().and_then(FontProgram::builtin_encoding)
+    {
+        for (entry, text) in table.iter_mut().zip(builtin) {
+            if let Some(text) = text
+                && entry.as_ref().is_none_or(|(default, _)| *default != text)
+            {
+                *entry = Some((text, Some(FontRecovery::FontProgram)));
+            }
+        }
+    }
+
+    let mut code = 0usize;
+    for entry in differences.into_iter().flatten() {
+        match doc.dereference(entry).map(|(_, entry)| entry) {
+            Ok(Object::Integer(next)) => code = (*next).clamp(0, 256) as usize,
+            Ok(Object::Name(glyph)) => {
+                if let Some(slot) = table.get_mut(code) {
+                    let glyph = String::from_utf8_lossy(glyph);
+                    *slot = glyph_list::glyph_to_unicode(&glyph).map(|text| (text, None)).or_else(|| {
+                        if subtype == b"Type3" {
+                            let text = font_program::type3_glyph_text(&glyph, code as u8)?;
+                            Some((text, Some(FontRecovery::Type3GlyphNames)))
+                        } else {
+                            let text = program()?.glyph_name_text(&glyph)?;
+                            Some((text, Some(FontRecovery::FontProgram)))
+                        }
+                    });
+                }
+                code += 1;
+            }
+            _ => {}
+        }
+    }
+    table
+}
+
+/// Text of the CIDs of a composite font, from the glyphs of its embedded program
+///
+/// CIDs map to glyphs through the descendant font's `/CIDToGIDMap`, identity by default.
+fn cid_texts(doc: &Document, descendant: &Dictionary, program: &FontProgram) -> HashMap<u32, String> {
+    let glyph_texts = program.glyph_texts();
+    match stream_data(doc, descendant.get(b"CIDToGIDMap").ok()) {
+        Some(map) => map
+            .chunks_exact(2)
+            .enumerate()
+            .filter_map(|(cid, glyph)| Some((cid as u32, glyph_texts.get(&(code_value(glyph) as u16))?.clone())))
+            .collect(),
+        None => glyph_texts.into_iter().map(|(glyph, text)| (u32::from(glyph), text)).collect(),
+    }
+}
+
+/// Encoding CMap of a composite font, a predefined CMap by name or an embedded stream,
+/// and whether it is for vertical writing
+///
+/// Unrecognised encodings fall back to two-byte identity codes, the most common case.
+fn code_map(doc: &Document, dict: &Dictionary) -> (CodeMap, bool) {
+    let encoding = dict.get(b"Encoding").ok();
+    if let Some(name) = encoding.and_then(|encoding| encoding.as_name().ok()) {
+        let name = String::from_utf8_lossy(name);
+        let cmap = PredefinedCMap::from_name(&name).unwrap_or(PredefinedCMap::Identity);
+        return (CodeMap::Predefined(cmap), is_vertical_name(&name));
+    }
+    // The stream dictionary may carry the writing mode instead of the CMap itself
+    let declared_vertical = encoding
+        .and_then(|encoding| doc.dereference(encoding).ok())
+        .and_then(|(_, encoding)| encoding.as_stream().ok())
+        .and_then(|stream| stream.dict.get(b"WMode").and_then(Object::as_i64).ok())
+        == Some(1);
+    match stream_data(doc, encoding).map(|data| CMap::parse(&data)) {
+        Some(cmap) if cmap.has_codespaces() => {
+            let vertical = declared_vertical || cmap.is_vertical();
+            (CodeMap::Embedded(cmap), vertical)
+        }
+        _ => (CodeMap::Predefined(PredefinedCMap::Identity), declared_vertical),
+    }
+}
+
+/// Decompressed content of a stream object, following references
+fn stream_data(doc: &Document, object: Option<&Object>) -> Option<Vec<u8>> {
+    let (_, object) = doc.dereference(object?).ok()?;
+    let stream = object.as_stream().ok()?;
+    match stream.filters() {
+        Ok(filters) if !filters.is_empty() => stream.decompressed_content().ok(),
+        _ => Some(stream.content.clone()),
+    }
+}
+
+/// Widths of a simple font from `/FirstChar` and `/Widths`
+fn simple_widths(doc: &Document, dict: &Dictionary) -> HashMap<u32, f64> {
+    let Ok(first_char) = u32::try_from(dict.get(b"FirstChar").and_then(Object::as_i64).unwrap_or(0)) else {
+        return HashMap::new();
+    };
+    let Some(widths) = dict
+        .get(b"Widths")
+        .ok()
+        .and_then(|widths| doc.dereference(widths).ok())
+        .and_then(|(_, widths)| widths.as_array().ok())
+    else {
+        return HashMap::new();
+    };
+
+    widths
+        .iter()
+        .enumerate()
+        .map_while(|(index, width)| Some((first_char.checked_add(u32::try_from(index).ok()?)?, width)))
+        .filter_map(|(code, width)| Some((code, number(doc, width)?)))
+        .collect()
+}
+
+/// Widths of a CID font from its `/W` array (`c [w1 w2 ...]` and `c_first c_last w` forms)
+fn cid_widths(doc: &Document, dict: &Dictionary) -> HashMap<u32, f64> {
+    let mut widths = HashMap::new();
+    let Some(entries) = dict
+        .get(b"W")
+        .ok()
+        .and_then(|w| doc.dereference(w).ok())
+        .and_then(|(_, w)| w.as_array().ok())
+    else {
+        return widths;
+    };
+
+    let mut index = 0;
+    while index < entries.len() {
+        let Some(first) = number(doc, &entries[index]) else {
+            break;
+        };
+        // Groups that start outside the CID range are skipped
+        let first = cid(first);
+        match entries.get(index + 1).map(|entry| doc.dereference(entry).map(|(_, entry)| entry)) {
+            Some(Ok(Object::Array(list))) => {
+                for (cid, width) in first.into_iter().flat_map(|first| (first..=u32::MAX).zip(list)) {
+                    if let Some(width) = number(doc, width) {
+                        widths.insert(cid, width);
+                    }
+                }
+                index += 2;
+            }
+            Some(Ok(last)) => {
+                let (Some(last), Some(width)) = (number(doc, last), entries.get(index + 2).and_then(|w| number(doc, w)))
+                else {
+                    break;
+                };
+                for cid in first.map(|first| cid_range(first, last)).into_iter().flatten() {
+                    widths.insert(cid, width);
+                }
+                index += 3;
+            }
+            _ => break,
+        }
+    }
+    widths
+}
+
+/// Vertical metrics of a CID font from its `/W2` array (`c [w1y v_x v_y ...]` and
+/// `c_first c_last w1y v_x v_y` forms)
+fn cid_vertical_metrics(doc: &Document, dict: &Dictionary) -> HashMap<u32, [f64; 3]> {
+    let mut metrics = HashMap::new();
+    let Some(entries) = dict
+        .get(b"W2")
+        .ok()
+        .and_then(|w2| doc.dereference(w2).ok())
+        .and_then(|(_, w2)| w2.as_array().ok())
+    else {
+        return metrics;
+    };
+
+    let mut index = 0;
+    while index < entries.len() {
+        let Some(first) = number(doc, &entries[index]) else {
+            break;
+        };
+        // Groups that start outside the CID range are skipped
+        let first = cid(first);
+        match entries.get(index + 1).map(|entry| doc.dereference(entry).map(|(_, entry)| entry)) {
+            Some(Ok(Object::Array(list))) => {
+                let values: Vec<f64> = list.iter().filter_map(|value| number(doc, value)).collect();
+                let triples = first.into_iter().flat_map(|first| (first..=u32::MAX).zip(values.chunks_exact(3)));
+                for (cid, triple) in triples {
+                    metrics.insert(cid, [triple[0], triple[1], triple[2]]);
+                }
+                index += 2;
+            }
+            Some(Ok(last)) => {
+                let values: Option<Vec<f64>> =
+                    (index + 2..index + 5).map(|i| entries.get(i).and_then(|value| number(doc, value))).collect();
+                let (Some(last), Some([advance, vx, vy])) = (number(doc, last), values.as_deref()) else {
+                    break;
+                };
+                for cid in first.map(|first| cid_range(first, last)).into_iter().flatten() {
+                    metrics.insert(cid, [*advance, *vx, *vy]);
+                }
+                index += 5;
+            }
+            _ => break,
+        }
+    }
+    metrics
+}
+
+/// A CID from a number of a `/W` or `/W2` array, if it is one
+fn cid(value: f64) -> Option<u32> {
+    (value >= 0.0 && value <= u32::MAX as f64).then_some(value as u32)
+}
+
+/// The CIDs of a `c_first c_last` range
+fn cid_range(first: u32, last: f64) -> std::ops::RangeInclusive<u32> {
+    // Guard against absurd ranges in malformed files
+    let last = (last.clamp(0.0, u32::MAX as f64) as u32).min(first.saturating_add(0xFFFF));
+    first..=last
+}
+
+/// Numeric value of an integer or real object, following references
+pub(crate) fn number(doc: &Document, object: &Object) -> Option<f64> {
+    match doc.dereference(object).ok()?.1 {
+        Object::Integer(i) => Some(*i as f64),
+        Object::Real(r) => Some(*r as f64),
+        _ => None,
+    }
+}
@@ -0,0 +1,147 @@
+//! Page furniture: running headers, footers and page numbers
+//!
+//! Furniture is found by comparing pages with each other. The rows of text nearest the top
+//! and bottom edge of every page are reduced to a key in which page numbers and the page's
+//! own label are masked, so that "Page 3 of 40" and "Page 4 of 40" match. A row whose key
+//! recurs at the same height on enough of the compared pages is furniture, and so is a first
+//! or last row holding its page's number where the other pages hold theirs too, which
+//! catches running headers that name the current chapter.
+
+use crate::content::PositionedGlyph;
+use crate::layout;
+
+/// Rows at the top and at the bottom of a page that may hold furniture
+const EDGE_ROWS: usize = 3;
+
+/// Pages a row must recur on to count as furniture, fewer only when fewer pages are compared
+pub(crate) const MIN_PAGES: usize = 3;
+
+/// Neighbouring pages, on each side, compared with small selections; enough for headers
+/// that alternate between left and right-hand pages to recur on pages of the same side
+pub(crate) const NEIGHBOUR_PAGES: u32 = 4;
+
+/// A row of glyphs sharing a baseline, near the top or bottom edge of a page
+pub(crate) struct EdgeRow {
+    /// Indices of the row's glyphs, whitespace included
+    glyphs: Vec<usize>,
+    /// Words of the row joined by single spaces
+    text: String,
+    /// The text with page numbers masked
+    key: String,
+    /// Whether one of the words is the page's own number or label
+    numbered: bool,
+    /// Whether the row is the first or the last of the page
+    outermost: bool,
+    baseline: f64,
+    font_size: f64,
+}
+
+/// The rows near the top and bottom edge of a page
+///
+/// `number` is the page's label, or its physical number when the document has no labels; it
+/// is masked like a page number when it appears as a word of its own (roman numerals such
+/// as "iv", or "A-12"). Vertical text is left out.
+pub(crate) fn edge_rows(glyphs: &[PositionedGlyph], number: &str, word_gap: f64) -> Vec<EdgeRow> {
+    let mut order: Vec<usize> = (0..glyphs.len()).filter(|&index| !glyphs[index].vertical).collect();
+    order.sort_by(|&a, &b| glyphs[b].y.total_cmp(&glyphs[a].y).then(glyphs[a].x.total_cmp(&glyphs[b].x)));
+
+    let mut rows: Vec<(f64, f64, Vec<usize>)> = Vec::new();
+    for index in order {
+        let glyph = &glyphs[index];
+        match rows.last_mut() {
+            Some((baseline, size, row)) if *baseline - glyph.y < layout::LINE_SHIFT * size.max(glyph.font_size) => {
+                *size = size.max(glyph.font_size);
+                row.push(index);
+            }
+            _ => rows.push((glyph.y, glyph.font_size, vec![index])),
+        }
+    }
+    rows.retain(|(_, _, row)| row.iter().any(|&index| !glyphs[index].text.trim().is_empty()));
+
+    let edge = rows.len().min(EDGE_ROWS);
+    let bottom = rows.split_off(rows.len() - edge);
+    rows.truncate(edge);
+    let last = (rows.len() + bottom.len()).saturating_sub(1);
+    rows.into_iter()
+        .chain(bottom)
+        .enumerate()
+        .map(|(position, (baseline, font_size, mut row))| {
+            row.sort_by(|&a, &b| glyphs[a].x.total_cmp(&glyphs[b].x));
+            let words: Vec<String> = layout::split_words(glyphs, &row, word_gap)
+                .iter()
+                .map(|word| word.iter().map(|&index| glyphs[index].text.as_str()).collect())
+                .collect();
+            let key = words.iter().map(|word| mask_page_number(word, number)).collect::<Vec<_>>().join(" ");
+            let numbered = words
+                .iter()
+                .any(|word| word.trim_matches(|c: char| !c.is_alphanumeric()).eq_ignore_ascii_case(number));
+            let outermost = position == 0 || position == last;
+            EdgeRow { glyphs: row, text: words.join(" "), key, numbered, outermost, baseline, font_size }
+        })
+        .collect()
+}
+
+/// For each of the first `selected` pages, the rows that are furniture
+///
+/// `pages` holds the edge rows of the selected pages followed by those of any neighbouring
+/// pages compared with them. The only row of a page is never furniture: a page holding a
+/// single line, such as a slide title, keeps it.
+pub(crate) fn find(pages: &[Vec<EdgeRow>], selected: usize) -> Vec<Vec<&EdgeRow>> {
+    let compared = pages.iter().filter(|rows| !rows.is_empty()).count();
+    let needed = MIN_PAGES.min(compared).max(2);
+
+    pages[..selected]
+        .iter()
+        .map(|rows| {
+            rows.iter()
+                .filter(|_| rows.len() > 1)
+                .filter(|row| {
+                    let recurrences = |matches: &dyn Fn(&EdgeRow) -> bool| {
+                        let at_height = |candidate: &EdgeRow| {
+                            (candidate.baseline - row.baseline).abs()
+                                < layout::LINE_SHIFT * candidate.font_size.max(row.font_size)
+                        };
+                        pages.iter().filter(|other| other.iter().any(|c| at_height(c) && matches(c))).count()
+                    };
+                    recurrences(&|candidate| candidate.key == row.key) >= needed
+                        || (row.numbered
+                            && row.outermost
+                            && recurrences(&|candidate| candidate.numbered && candidate.outermost) >= needed)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Remove the glyphs of furniture rows from a page, returning the text of the rows
+pub(crate) fn strip(glyphs: &mut Vec<PositionedGlyph>, rows: &[&EdgeRow]) -> Vec<String> {
+    let mut furniture = vec![false; glyphs.len()];
+    for row in rows {
+        for &index in &row.glyphs {
+            furniture[index] = true;
+        }
+    }
+    let mut index = 0;
+    glyphs.retain(|_| {
+        index += 1;
+        !furniture[index - 1]
+    });
+    rows.iter().map(|row| row.text.clone()).collect()
+}
+
+/// A word with its page number masked: the page's number or label as a whole word, or runs
+/// of digits
+fn mask_page_number(word: &str, number: &str) -> String {
+    if word.eq_ignore_ascii_case(number) {
+        return "#".to_string();
+    }
+    let mut masked = String::with_capacity(word.len());
+    for c in word.chars() {
+        if !c.is_ascii_digit() {
+            masked.push(c);
+        } else if !masked.ends_with('#') {
+            masked.push('#');
+        }
+    }
+    masked
+}
@@ -0,0 +1,166 @@
+//! HTML: pages as a semantic document with an anchor per page
+//!
+//! The document is built from the same elements as the Markdown conversion (see the
+//! `markdown` module). Every page becomes a `<section id="page-N" data-page="N">`, so that
+//! citations can link to `#page-N` and scripts can find the page of any element. Links keep
+//! the words their annotations cover: web and mail links point at their URI, links to pages
+//! of the document at the page's section when it is exported. Links with other schemes,
+//! such as `javascript:`, are left out.
+
+use crate::links::LinkTarget;
+use crate::markdown::{Anchor, Element, Marker};
+use crate::tables::Table;
+
+/// URI schemes kept as links
+const LINK_SCHEMES: [&str; 4] = ["http", "https", "mailto", "ftp"];
+
+/// Render the elements of the selected pages as an HTML document
+///
+/// `notes` are added as paragraphs after the last page.
+pub(crate) fn render(title: &str, pages: &[(u32, Vec<Element>)], notes: &[String]) -> String {
+    let exported: Vec<u32> = pages.iter().map(|(page, _)| *page).collect();
+    let mut html = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>{}</title>\n</head>\n<body>\n", escape(title)));
+
+    for (page, elements) in pages {
+        html.push_str(&format!("<section id=\"page-{0}\" data-page=\"{0}\">\n", page));
+        // Tags of the lists the current item is nested in, outermost first; each has an open <li>
+        let mut lists: Vec<&str> = Vec::new();
+        for element in elements {
+            let depth = match element {
+                Element::ListItem { depth, .. } => *depth,
+                _ => 0,
+            };
+            while lists.len() > depth {
+                html.push_str(&format!("</li>\n</{}>\n", lists.pop().unwrap_or("ul")));
+            }
+            match element {
+                Element::Heading { level, text, links } => {
+                    let level = (*level).min(6);
+                    html.push_str(&format!("<h{0}>{1}</h{0}>\n", level, inline(text, links, &exported)));
+                }
+                Element::Paragraph { text, links } => {
+                    html.push_str(&format!("<p>{}</p>\n", inline(text, links, &exported)));
+                }
+                Element::Table(table) => html.push_str(&render_table(table)),
+                Element::ListItem { depth, marker, text, links } => {
+                    // Items marked "a)" or "(iv)" keep their label, as in Markdown
+                    let tag = if matches!(marker, Marker::Number(_)) { "ol" } else { "ul" };
+                    let label = match marker {
+                        Marker::Label(label) => format!("{} ", escape(label)),
+                        _ => String::new(),
+                    };
+                    if lists.len() == *depth {
+                        if lists.last() == Some(&tag) {
+                            html.push_str("</li>\n");
+                        } else {
+                            html.push_str(&format!("</li>\n</{}>\n", lists.pop().unwrap_or("ul")));
+                        }
+                    }
+                    if lists.len() < *depth {
+                        if !lists.is_empty() {
+                            html.push('\n');
+                        }
+                        match marker {
+                            Marker::Number(start) if *start != 1 => {
+                                html.push_str(&format!("<ol start=\"{}\">\n", start))
+                            }
+                            _ => html.push_str(&format!("<{}>\n", tag)),
+                        }
+                        lists.push(tag);
+                    }
+                    html.push_str(&format!("<li>{}{}", label, inline(text, links, &exported)));
+                }
+            }
+        }
+        while let Some(tag) = lists.pop() {
+            html.push_str(&format!("</li>\n</{}>\n", tag));
+        }
+        html.push_str("</section>\n");
+    }
+
+    for note in notes {
+        html.push_str(&format!("<p>{}</p>\n", escape(note)));
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// A table with its header rows as heading cells
+fn render_table(table: &Table) -> String {
+    let mut html = String::from("<table>\n");
+    for row in 0..table.rows {
+        let header = row < table.header_rows;
+        if row == 0 && header {
+            html.push_str("<thead>\n");
+        } else if row == table.header_rows {
+            html.push_str("<tbody>\n");
+        }
+        html.push_str("<tr>");
+        for cell in table.cells.iter().filter(|cell| cell.row == row) {
+            let tag = if header { "th" } else { "td" };
+            html.push_str(&format!("<{}", tag));
+            if cell.row_span > 1 {
+                html.push_str(&format!(" rowspan=\"{}\"", cell.row_span));
+            }
+            if cell.column_span > 1 {
+                html.push_str(&format!(" colspan=\"{}\"", cell.column_span));
+            }
+            html.push_str(&format!(">{}</{}>", escape(&cell.text), tag));
+        }
+        html.push_str("</tr>\n");
+        if row + 1 == table.header_rows {
+            html.push_str("</thead>\n");
+        }
+    }
+    if table.rows > table.header_rows {
+        html.push_str("</tbody>\n");
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+/// Text with the parts its links cover wrapped in `<a>` elements
+///
+/// `links` are in order of their ranges; a link overlapping the one before it is dropped.
+fn inline(text: &str, links: &[Anchor], exported: &[u32]) -> String {
+    let mut html = String::new();
+    let mut cursor = 0;
+    for anchor in links {
+        let Some(href) = href(&anchor.target, exported).filter(|_| anchor.range.start >= cursor) else {
+            continue;
+        };
+        html.push_str(&escape(&text[cursor..anchor.range.start]));
+        html.push_str(&format!("<a href=\"{}\">{}</a>", escape(&href), escape(&text[anchor.range.clone()])));
+        cursor = anchor.range.end;
+    }
+    html.push_str(&escape(&text[cursor..]));
+    html
+}
+
+/// Where a link points in the exported document, if it is kept
+fn href(target: &LinkTarget, exported: &[u32]) -> Option<String> {
+    match target {
+        LinkTarget::Uri(uri) => {
+            let (scheme, _) = uri.split_once(':')?;
+            LINK_SCHEMES.iter().any(|allowed| scheme.eq_ignore_ascii_case(allowed)).then(|| uri.clone())
+        }
+        LinkTarget::Page(page) => exported.contains(page).then(|| format!("#page-{}", page)),
+    }
+}
+
+/// Escape the characters HTML reads as markup, in text and attribute values
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,494 @@
+//! Page layout: words and lines with their positions on the page
+//!
+//! Glyphs from the content stream interpreter are grouped into words at whitespace and at
+//! gaps along the baseline, and into lines where the baseline moves. Lines holding
+//! right-to-left text are reordered by the `bidi` module. Vertical text forms lines that
+//! run down the page. Coordinates are in PDF user space: points, origin at the bottom-left
+//! of the media box's coordinate system.
+
+use crate::bidi;
+use crate::content::{self, PositionedGlyph};
+use crate::fonts;
+use crate::pdf_reader::{BidiOrder, TextOptions};
+use crate::reading_order;
+use lopdf::{Document, Object, ObjectId};
+use serde::{Deserialize, Serialize};
+
+/// Baseline shift, as a fraction of the font size, that starts a new line
+pub(crate) const LINE_SHIFT: f64 = 0.5;
+
+/// Gap between words, as a fraction of the font size, below which the preserved layout
+/// separates them with a single space rather than placing them on the grid
+const PHRASE_GAP: f64 = 0.6;
+
+/// Most blank lines the preserved layout puts between two rows of text
+const MAX_BLANK_LINES: usize = 2;
+
+/// Page size used when a page has no usable `/MediaBox` (US Letter)
+const DEFAULT_MEDIA_BOX: [f64; 4] = [0.0, 0.0, 612.0, 792.0];
+
+/// An axis-aligned rectangle in PDF user space
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BoundingBox {
+    /// Left edge
+    pub x0: f64,
+    /// Bottom edge
+    pub y0: f64,
+    /// Right edge
+    pub x1: f64,
+    /// Top edge
+    pub y1: f64,
+}
+
+impl BoundingBox {
+    pub(crate) fn new([x0, y0, x1, y1]: [f64; 4]) -> Self {
+        BoundingBox { x0: round(x0), y0: round(y0), x1: round(x1), y1: round(y1) }
+    }
+
+    /// Smallest box containing both boxes
+    fn union(&self, other: &BoundingBox) -> BoundingBox {
+        BoundingBox {
+            x0: self.x0.min(other.x0),
+            y0: self.y0.min(other.y0),
+            x1: self.x1.max(other.x1),
+            y1: self.y1.max(other.y1),
+        }
+    }
+}
+
+/// A single glyph and its box
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextGlyph {
+    pub text: String,
+    pub bbox: BoundingBox,
+}
+
+/// A run of glyphs without whitespace or wide gaps between them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextWord {
+    pub text: String,
+    pub bbox: BoundingBox,
+    /// Base font name of the word's first glyph
+    pub font: String,
+    /// Font size in points, after the text and transformation matrices
+    pub font_size: f64,
+    /// Individual glyphs, when requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub glyphs: Option<Vec<TextGlyph>>,
+}
+
+/// Words sharing a baseline, in content stream order; lines holding right-to-left text are
+/// in logical order unless visual order is requested
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextLine {
+    /// Words joined by single spaces
+    pub text: String,
+    pub bbox: BoundingBox,
+    /// Whether the line is a column of vertical text, read top to bottom
+    pub vertical: bool,
+    pub words: Vec<TextWord>,
+}
+
+/// How the text of a font was recovered when the PDF gives no Unicode mapping for it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FontRecovery {
+    /// From the embedded font program: its built-in encoding, charset, `cmap` or glyph names
+    FontProgram,
+    /// From the names of Type3 glyph procedures, or their character codes
+    Type3GlyphNames,
+}
+
+/// A font on a page whose text could only be recovered heuristically
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecoveredFont {
+    /// Base font name without subset prefix; the resource name for unnamed Type3 fonts
+    pub font: String,
+    pub method: FontRecovery,
+}
+
+/// Positioned text of a single page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageLayout {
+    /// Physical page number (1-indexed)
+    pub page: u32,
+    /// Page label, when the document defines page labels
+    pub label: Option<String>,
+    /// The page's `/MediaBox`, which the boxes of words and lines are relative to
+    pub media_box: BoundingBox,
+    /// Media box width in points
+    pub width: f64,
+    /// Media box height in points
+    pub height: f64,
+    /// Clockwise rotation applied when the page is displayed (0, 90, 180 or 270)
+    pub rotation: i64,
+    pub lines: Vec<TextLine>,
+    /// Fonts whose text was recovered heuristically and may be inaccurate
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recovered_fonts: Vec<RecoveredFont>,
+}
+
+/// Lay out the text of one page
+pub(crate) fn page_layout(
+    doc: &Document,
+    page_id: ObjectId,
+    page: u32,
+    label: Option<String>,
+    include_glyphs: bool,
+    options: &TextOptions,
+) -> Result<PageLayout, lopdf::Error> {
+    let mut glyphs = content::page_glyphs(doc, page_id)?;
+    let recovered_fonts = recovered_fonts(&glyphs);
+
+    let media_box = inherited_attribute(doc, page_id, b"MediaBox")
+        .and_then(|media_box| media_box.as_array().ok())
+        .and_then(|media_box| {
+            let values: Vec<f64> = media_box.iter().map(|v| fonts::number(doc, v)).collect::<Option<_>>()?;
+            match values[..] {
+                [x0, y0, x1, y1] => Some([x0.min(x1), y0.min(y1), x0.max(x1), y0.max(y1)]),
+                _ => None,
+            }
+        })
+        .unwrap_or(DEFAULT_MEDIA_BOX);
+    let rotation = inherited_attribute(doc, page_id, b"Rotate")
+        .and_then(|rotate| rotate.as_i64().ok())
+        .unwrap_or(0)
+        .rem_euclid(360)
+        / 90
+        * 90;
+
+    Ok(PageLayout {
+        page,
+        label,
+        media_box: BoundingBox::new(media_box),
+        width: round(media_box[2] - media_box[0]),
+        height: round(media_box[3] - media_box[1]),
+        rotation,
+        lines: group_lines(&mut glyphs, include_glyphs, options),
+        recovered_fonts,
+    })
+}
+
+/// The text of one page in reading order
+///
+/// Multi-column pages are read one column at a time, from the right on pages of mostly
+/// right-to-left text; see the `reading_order` module. Pages of mostly vertical text are
+/// read column by column from the right, each column top to bottom.
+pub(crate) fn reading_text(glyphs: &mut [PositionedGlyph], options: &TextOptions) -> String {
+    let mut turned;
+    let glyphs = if is_mostly_vertical(glyphs) {
+        turned = turn_vertical(glyphs);
+        &mut turned[..]
+    } else {
+        glyphs
+    };
+    let right_to_left = options.bidi == BidiOrder::Logical && bidi::is_mostly_rtl(glyphs);
+    let segments = reading_order::segments(group_glyphs(glyphs, options));
+    reading_order::reading_text(segments, right_to_left, options.normalization.dehyphenate)
+}
+
+/// Render the text of one page as a fixed-width character grid
+///
+/// Words keep their horizontal position, so columns, indentation and table alignment
+/// survive, and large vertical gaps become blank lines. Pages of mostly vertical text are
+/// turned a quarter turn first, so that their columns become rows, the rightmost on top.
+pub(crate) fn preserved_text(glyphs: &mut [PositionedGlyph], options: &TextOptions) -> String {
+    let mut turned;
+    let glyphs = if is_mostly_vertical(glyphs) {
+        turned = turn_vertical(glyphs);
+        &mut turned[..]
+    } else {
+        glyphs
+    };
+
+    let char_width = median(glyphs.iter().filter(|glyph| !glyph.text.trim().is_empty()).filter_map(|glyph| {
+        let width = (glyph.end_x - glyph.x) / glyph.text.chars().count() as f64;
+        (width > 0.0).then_some(width)
+    }))
+    .unwrap_or(5.0);
+
+    let words: Vec<WordGlyphs> = group_glyphs(glyphs, options).into_iter().flatten().collect();
+    if words.is_empty() {
+        return String::new();
+    }
+    let left = words.iter().map(|word| word_left(word)).fold(f64::INFINITY, f64::min);
+
+    // Words whose baselines are within half a font size share a row, even when they come
+    // from different parts of the content stream
+    let mut words = words;
+    words.sort_by(|a, b| b[0].y.total_cmp(&a[0].y));
+    let mut rows: Vec<(f64, f64, Vec<WordGlyphs>)> = Vec::new();
+    for word in words {
+        let (baseline, size) = (word[0].y, word[0].font_size);
+        match rows.last_mut() {
+            Some((row_baseline, row_size, row)) if *row_baseline - baseline < LINE_SHIFT * row_size.max(size) => {
+                *row_size = row_size.max(size);
+                row.push(word);
+            }
+            _ => rows.push((baseline, size, vec![word])),
+        }
+    }
+
+    let line_height = median(rows.windows(2).map(|pair| pair[0].0 - pair[1].0).filter(|gap| *gap > 0.0))
+        .unwrap_or(rows[0].1 * 1.2);
+
+    let mut text = String::new();
+    let mut previous_baseline: Option<f64> = None;
+    for (baseline, _, mut row) in rows {
+        if let Some(previous) = previous_baseline {
+            let blank_lines = ((previous - baseline) / line_height).round() as usize;
+            text.push_str(&"\n".repeat(blank_lines.clamp(1, MAX_BLANK_LINES + 1)));
+        }
+        previous_baseline = Some(baseline);
+
+        row.sort_by(|a, b| word_left(a).total_cmp(&word_left(b)));
+        let mut line = String::new();
+        let mut column = 0;
+        let mut previous: Option<(f64, f64)> = None;
+        for word in row {
+            let target = ((word_left(&word) - left) / char_width).round().max(0.0) as usize;
+            let target = match previous {
+                // Words of one phrase stay one space apart, whatever their font size
+                Some((end, size)) if word_left(&word) - end < PHRASE_GAP * size => column + 1,
+                // Proportional text can overrun its grid cells; keep words apart
+                Some(_) => target.max(column + 1),
+                None => target,
+            };
+            line.push_str(&" ".repeat(target - column));
+            for glyph in &word {
+                line.push_str(&glyph.text);
+            }
+            column = target + word.iter().map(|glyph| glyph.text.chars().count()).sum::<usize>();
+            let last = word.last().expect("words are never empty");
+            previous = Some((word_right(&word), last.font_size));
+        }
+        text.push_str(&line);
+    }
+    text
+}
+
+/// Fonts on a page whose glyphs needed heuristic recovery, in order of first use
+pub(crate) fn recovered_fonts(glyphs: &[PositionedGlyph]) -> Vec<RecoveredFont> {
+    let mut fonts: Vec<RecoveredFont> = Vec::new();
+    for glyph in glyphs {
+        if let Some(method) = glyph.recovery
+            && !fonts.iter().any(|font| font.font == glyph.font_name && font.method == method)
+        {
+            fonts.push(RecoveredFont { font: glyph.font_name.clone(), method });
+        }
+    }
+    fonts
+}
+
+/// Whether most visible glyphs of a page are in vertical writing mode
+pub(crate) fn is_mostly_vertical(glyphs: &[PositionedGlyph]) -> bool {
+    let visible: Vec<&PositionedGlyph> = glyphs.iter().filter(|glyph| !glyph.text.trim().is_empty()).collect();
+    visible.iter().filter(|glyph| glyph.vertical).count() * 2 > visible.len()
+}
+
+/// Copies of a page's glyphs turned a quarter turn anticlockwise, so that columns of
+/// vertical text become lines read left to right, the rightmost column on top
+pub(crate) fn turn_vertical(glyphs: &[PositionedGlyph]) -> Vec<PositionedGlyph> {
+    glyphs
+        .iter()
+        .map(|glyph| {
+            let [x0, y0, x1, y1] = glyph.bbox;
+            PositionedGlyph {
+                x: -glyph.y,
+                y: glyph.x,
+                end_x: -glyph.end_y,
+                end_y: glyph.end_x,
+                bbox: [-y1, x0, -y0, x1],
+                ..glyph.clone()
+            }
+        })
+        .collect()
+}
+
+/// Median of a set of values, or `None` when it is empty
+pub(crate) fn median(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let mut values: Vec<f64> = values.collect();
+    values.sort_by(f64::total_cmp);
+    values.get(values.len() / 2).copied()
+}
+
+/// A page attribute, looked up through the `/Parent` chain for inheritable entries
+fn inherited_attribute<'a>(doc: &'a Document, page_id: ObjectId, key: &[u8]) -> Option<&'a Object> {
+    let mut node = doc.get_dictionary(page_id).ok()?;
+    // The depth limit guards against cyclic parent links
+    for _ in 0..64 {
+        if let Ok(value) = node.get(key) {
+            return doc.dereference(value).ok().map(|(_, value)| value);
+        }
+        node = doc.get_dictionary(node.get(b"Parent").and_then(Object::as_reference).ok()?).ok()?;
+    }
+    None
+}
+
+/// Glyphs of one word
+pub(crate) type WordGlyphs<'a> = Vec<&'a PositionedGlyph>;
+
+/// Group glyphs into words and lines, following content stream order
+fn group_lines(glyphs: &mut [PositionedGlyph], include_glyphs: bool, options: &TextOptions) -> Vec<TextLine> {
+    group_glyphs(glyphs, options)
+        .into_iter()
+        .map(|words| {
+            let vertical = words[0][0].vertical;
+            let words: Vec<TextWord> = words.iter().map(|glyphs| make_word(glyphs, include_glyphs)).collect();
+            let text = words.iter().map(|word| word.text.as_str()).collect::<Vec<_>>().join(" ");
+            let bbox = words[1..].iter().fold(words[0].bbox, |bbox, word| bbox.union(&word.bbox));
+            TextLine { text, bbox, vertical, words }
+        })
+        .collect()
+}
+
+/// Split glyphs into lines of words
+///
+/// Words end at whitespace glyphs and at gaps wider than `word_gap` times the width of a
+/// space in the font, so text positioned word by word without space characters (as with
+/// `TJ` kerning arrays) still comes out as separate words. Lines end where the baseline
+/// moves or the text jumps back. Lines holding right-to-left text are split into words by
+/// position and put in the requested bidi order, which may change the text of mirrored
+/// punctuation glyphs.
+pub(crate) fn group_glyphs<'a>(glyphs: &'a mut [PositionedGlyph], options: &TextOptions) -> Vec<Vec<WordGlyphs<'a>>> {
+    // Indices of each line's glyphs, including whitespace between its visible glyphs
+    let mut lines: Vec<Vec<usize>> = Vec::new();
+    let mut previous: Option<&PositionedGlyph> = None;
+    let mut whitespace: Vec<usize> = Vec::new();
+    for (index, glyph) in glyphs.iter().enumerate() {
+        if glyph.text.trim().is_empty() {
+            whitespace.push(index);
+            continue;
+        }
+        match previous {
+            Some(prev) if !starts_new_line(prev, glyph) => {
+                let line = lines.last_mut().expect("a previous glyph implies a line");
+                line.append(&mut whitespace);
+                line.push(index);
+            }
+            _ => {
+                whitespace.clear();
+                lines.push(vec![index]);
+            }
+        }
+        previous = Some(glyph);
+    }
+
+    let lines: Vec<Vec<Vec<usize>>> = lines
+        .iter()
+        .map(|line| {
+            if line.iter().any(|&index| bidi::is_rtl(&glyphs[index].text)) {
+                bidi::order_line(glyphs, line, options.word_gap, options.bidi)
+            } else {
+                split_words(glyphs, line, options.word_gap)
+            }
+        })
+        .collect();
+
+    let glyphs: &'a [PositionedGlyph] = glyphs;
+    lines
+        .into_iter()
+        .map(|words| words.into_iter().map(|word| word.into_iter().map(|index| &glyphs[index]).collect()).collect())
+        .collect()
+}
+
+/// Split one line of glyphs, in content stream order, into words
+pub(crate) fn split_words(glyphs: &[PositionedGlyph], line: &[usize], word_gap: f64) -> Vec<Vec<usize>> {
+    let mut words: Vec<Vec<usize>> = Vec::new();
+    let mut previous: Option<&PositionedGlyph> = None;
+    // Whitespace seen since the previous visible glyph
+    let mut pending_break = false;
+
+    for &index in line {
+        let glyph = &glyphs[index];
+        if glyph.text.is_empty() && !glyph.is_space {
+            // A glyph without text, such as a stripped soft hyphen, still fills its part of the word
+            previous = previous.and(Some(glyph));
+            continue;
+        }
+        if glyph.text.trim().is_empty() {
+            pending_break |= !glyph.text.is_empty() || glyph.is_space;
+            continue;
+        }
+
+        match (words.last_mut(), previous) {
+            (Some(word), Some(prev))
+                if !pending_break && gap_along_baseline(prev, glyph) <= word_gap * prev.space_width =>
+            {
+                word.push(index)
+            }
+            _ => words.push(vec![index]),
+        }
+        previous = Some(glyph);
+        pending_break = false;
+    }
+    words
+}
+
+/// Left edge of a word: its leftmost glyph origin or advance end
+fn word_left(word: &[&PositionedGlyph]) -> f64 {
+    word.iter().map(|glyph| glyph.x.min(glyph.end_x)).fold(f64::INFINITY, f64::min)
+}
+
+/// Right edge of a word
+fn word_right(word: &[&PositionedGlyph]) -> f64 {
+    word.iter().map(|glyph| glyph.x.max(glyph.end_x)).fold(f64::NEG_INFINITY, f64::max)
+}
+
+/// Build a word from its glyphs
+fn make_word(glyphs: &[&PositionedGlyph], include_glyphs: bool) -> TextWord {
+    let first = glyphs[0];
+    let bbox = glyphs[1..]
+        .iter()
+        .fold(BoundingBox::new(first.bbox), |bbox, glyph| bbox.union(&BoundingBox::new(glyph.bbox)));
+
+    TextWord {
+        text: glyphs.iter().map(|glyph| glyph.text.as_str()).collect(),
+        bbox,
+        font: first.font_name.clone(),
+        font_size: round(first.font_size),
+        glyphs: include_glyphs.then(|| {
+            glyphs
+                .iter()
+                .map(|glyph| TextGlyph { text: glyph.text.clone(), bbox: BoundingBox::new(glyph.bbox) })
+                .collect()
+        }),
+    }
+}
+
+/// Unit vector of a glyph's writing direction
+pub(crate) fn direction(glyph: &PositionedGlyph) -> (f64, f64) {
+    let (dx, dy) = (glyph.end_x - glyph.x, glyph.end_y - glyph.y);
+    let length = dx.hypot(dy);
+    if length > f64::EPSILON { (dx / length, dy / length) } else { (1.0, 0.0) }
+}
+
+/// Distance from the end of `prev` to the start of `next`, measured along `prev`'s baseline
+fn gap_along_baseline(prev: &PositionedGlyph, next: &PositionedGlyph) -> f64 {
+    let (ux, uy) = direction(prev);
+    (next.x - prev.end_x) * ux + (next.y - prev.end_y) * uy
+}
+
+/// Whether `next` leaves the line of `prev`: its baseline moved, or it jumped backwards
+///
+/// Right-to-left text may be drawn in logical order, each glyph to the left of the one
+/// before, so a step back to just before `prev` stays on the line when either glyph is
+/// right-to-left.
+fn starts_new_line(prev: &PositionedGlyph, next: &PositionedGlyph) -> bool {
+    let (ux, uy) = direction(prev);
+    let (dx, dy) = (next.x - prev.end_x, next.y - prev.end_y);
+    let across = (dy * ux - dx * uy).abs();
+    let size = prev.font_size.max(next.font_size);
+    if across > LINE_SHIFT * size {
+        return true;
+    }
+
+    let gap_before = (prev.x - next.end_x) * ux + (prev.y - next.end_y) * uy;
+    let right_to_left = gap_before > -size && (bidi::is_rtl(&prev.text) || bidi::is_rtl(&next.text));
+    gap_along_baseline(prev, next) < -size && !right_to_left
+}
+
+/// Round a coordinate to two decimals, which is well below a glyph's size
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
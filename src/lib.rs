@@ -2,10 +2,42 @@
 //!
 //! A Rust-based MCP Server that provides PDF reading capabilities as a Kiro Power.
 
+mod bidi;
+mod cmap;
+mod content;
+pub mod encryption;
 pub mod error;
+mod encodings;
+mod font_program;
+mod fonts;
+mod furniture;
+mod glyph_list;
+mod html;
+pub mod layout;
+pub mod links;
+mod markdown;
+mod metadata;
+mod normalize;
+pub mod outline;
+pub mod page_labels;
+pub mod page_selection;
 pub mod pdf_reader;
+mod reading_order;
+mod structure;
 pub mod service;
+pub mod tables;
+pub mod xmp;
 
+pub use encryption::{DocumentPermissions, EncryptionInfo};
 pub use error::PdfError;
-pub use pdf_reader::{PdfInfo, PdfReader};
+pub use layout::{BoundingBox, FontRecovery, PageLayout, RecoveredFont, TextGlyph, TextLine, TextWord};
+pub use links::{Link, LinkAction};
+pub use outline::OutlineItem;
+pub use page_labels::PageRef;
+pub use page_selection::PageSelection;
+pub use pdf_reader::{
+    BidiOrder, Normalization, PageError, PageStatus, PageText, PdfInfo, PdfReader, TextLayout, TextOptions, UnicodeForm,
+};
 pub use service::PdfReaderService;
+pub use tables::{Table, TableCell, TableMethod};
+pub use xmp::XmpMetadata;
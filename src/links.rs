@@ -0,0 +1,334 @@
+//! Link annotations and their targets
+//!
+//! A link annotation covers a rectangle of the page, or the quadrilaterals of its
+//! `/QuadPoints` when the link runs over several lines. It leads to a destination of the
+//! same document (`/Dest` or a `GoTo` action), or performs an action: opening a URI,
+//! going to a destination in another PDF file (`GoToR`) or launching an application or
+//! file (`Launch`).
+
+use crate::content::PositionedGlyph;
+use crate::layout::{self, BoundingBox};
+use crate::normalize;
+use crate::outline::DestinationResolver;
+use crate::pdf_reader::TextOptions;
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use serde::{Deserialize, Serialize};
+
+/// A link annotation of a page, with what it leads to and the text under it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Link {
+    /// Physical page number (1-indexed) the link is on
+    pub page: u32,
+    /// The annotation's `/Rect` in PDF user space
+    pub bbox: BoundingBox,
+    /// Text of the page under the link, in reading order; empty when the link covers none
+    pub text: String,
+    pub action: LinkAction,
+}
+
+/// What following a link does
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum LinkAction {
+    /// Open a URI, usually a web address
+    Uri { uri: String },
+    /// Go to a destination of the same document
+    GoTo {
+        /// Target page (1-indexed), if the destination could be resolved
+        #[serde(skip_serializing_if = "Option::is_none")]
+        page: Option<u32>,
+        /// Name of the destination, for named destinations
+        #[serde(skip_serializing_if = "Option::is_none")]
+        destination: Option<String>,
+    },
+    /// Go to a destination in another PDF file
+    GoToR {
+        /// The file, as written in its file specification
+        file: String,
+        /// Target page (1-indexed) in that file, for destinations given by page
+        #[serde(skip_serializing_if = "Option::is_none")]
+        page: Option<u32>,
+        /// Name of the destination, for named destinations
+        #[serde(skip_serializing_if = "Option::is_none")]
+        destination: Option<String>,
+        /// Whether the file opens in a new window, when the link says
+        #[serde(skip_serializing_if = "Option::is_none")]
+        new_window: Option<bool>,
+    },
+    /// Launch an application or open a file
+    Launch {
+        /// The file, as written in its file specification
+        #[serde(skip_serializing_if = "Option::is_none")]
+        file: Option<String>,
+        /// Parameters passed to the application (Windows launch parameters)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        parameters: Option<String>,
+        /// Whether the file opens in a new window, when the link says
+        #[serde(skip_serializing_if = "Option::is_none")]
+        new_window: Option<bool>,
+    },
+    /// Any other action, such as `JavaScript` or `Named`, or none at all
+    Other {
+        /// Type of the action (`/S`), if the link has one
+        #[serde(skip_serializing_if = "Option::is_none")]
+        action: Option<String>,
+    },
+}
+
+/// A link annotation of a page, for links kept in Markdown and HTML
+pub(crate) struct PageLink {
+    /// x0, y0, x1, y1 of the annotation's `/Rect` in user space
+    pub(crate) rect: [f64; 4],
+    /// Bounds of the quadrilaterals of `/QuadPoints`, when the annotation has them
+    pub(crate) quads: Vec<[f64; 4]>,
+    pub(crate) target: LinkTarget,
+}
+
+/// Where a link leads
+#[derive(Clone)]
+pub(crate) enum LinkTarget {
+    /// A URI action, usually a web address
+    Uri(String),
+    /// A page (1-indexed) of the same document, from a destination or `GoTo` action
+    Page(u32),
+}
+
+/// A link annotation as read from the page's `/Annots`
+struct Annotation<'a> {
+    dict: &'a Dictionary,
+    rect: [f64; 4],
+    quads: Vec<[f64; 4]>,
+}
+
+impl PageLink {
+    /// Whether the center of a glyph lies inside the link's area
+    pub(crate) fn covers(&self, glyph: &PositionedGlyph) -> bool {
+        covers(&self.rect, &self.quads, glyph)
+    }
+}
+
+/// Read the link annotations of a page whose targets can be resolved
+///
+/// Links whose action is not a URI or `GoTo` action, or whose destination cannot be
+/// resolved to a page, are left out.
+pub(crate) fn page_links(doc: &Document, page_id: ObjectId, resolver: &DestinationResolver) -> Vec<PageLink> {
+    link_annotations(doc, page_id)
+        .into_iter()
+        .filter_map(|annotation| {
+            let target = match link_action(doc, annotation.dict, resolver) {
+                LinkAction::Uri { uri } => LinkTarget::Uri(uri),
+                LinkAction::GoTo { page: Some(page), .. } => LinkTarget::Page(page),
+                _ => return None,
+            };
+            Some(PageLink { rect: annotation.rect, quads: annotation.quads, target })
+        })
+        .collect()
+}
+
+/// Read every link annotation of a page, with its action and the text under it
+///
+/// `glyphs` are the page's glyphs; links are in the order of the page's `/Annots`.
+pub(crate) fn read_links(
+    doc: &Document,
+    page: u32,
+    page_id: ObjectId,
+    glyphs: &[PositionedGlyph],
+    resolver: &DestinationResolver,
+    options: &TextOptions,
+) -> Vec<Link> {
+    link_annotations(doc, page_id)
+        .into_iter()
+        .map(|annotation| {
+            let mut covered: Vec<PositionedGlyph> = glyphs
+                .iter()
+                .filter(|glyph| covers(&annotation.rect, &annotation.quads, glyph))
+                .cloned()
+                .collect();
+            let text = normalize::normalize_text(layout::reading_text(&mut covered, options), &options.normalization);
+            Link {
+                page,
+                bbox: BoundingBox::new(annotation.rect),
+                text: text.split_whitespace().collect::<Vec<_>>().join(" "),
+                action: link_action(doc, annotation.dict, resolver),
+            }
+        })
+        .collect()
+}
+
+/// The link annotations of a page that have a usable `/Rect`
+fn link_annotations(doc: &Document, page_id: ObjectId) -> Vec<Annotation<'_>> {
+    let Ok(page) = doc.get_dictionary(page_id) else {
+        return Vec::new();
+    };
+    let Some(annotations) = page
+        .get(b"Annots")
+        .ok()
+        .and_then(|annots| doc.dereference(annots).ok())
+        .and_then(|(_, annots)| annots.as_array().ok())
+    else {
+        return Vec::new();
+    };
+
+    annotations
+        .iter()
+        .filter_map(|annotation| {
+            let dict = doc.dereference(annotation).ok()?.1.as_dict().ok()?;
+            if dict.get(b"Subtype").and_then(Object::as_name).ok()? != b"Link" {
+                return None;
+            }
+            let rect = annotation_rect(doc, dict.get(b"Rect").ok()?)?;
+            let quads = dict.get(b"QuadPoints").map(|quads| quad_bounds(doc, quads)).unwrap_or_default();
+            Some(Annotation { dict, rect, quads })
+        })
+        .collect()
+}
+
+/// Whether the center of a glyph lies inside a link's quadrilaterals, or its rectangle
+/// when it has none
+fn covers(rect: &[f64; 4], quads: &[[f64; 4]], glyph: &PositionedGlyph) -> bool {
+    let (x, y) = ((glyph.bbox[0] + glyph.bbox[2]) / 2.0, (glyph.bbox[1] + glyph.bbox[3]) / 2.0);
+    let inside = |area: &[f64; 4]| area[0] <= x && x <= area[2] && area[1] <= y && y <= area[3];
+    if quads.is_empty() { inside(rect) } else { quads.iter().any(inside) }
+}
+
+/// The rectangle of an annotation with its corners in order
+fn annotation_rect(doc: &Document, rect: &Object) -> Option<[f64; 4]> {
+    let values = numbers(doc, rect)?;
+    let [x0, y0, x1, y1] = values[..] else {
+        return None;
+    };
+    Some([x0.min(x1), y0.min(y1), x0.max(x1), y0.max(y1)])
+}
+
+/// Bounds of the quadrilaterals of a `/QuadPoints` array, eight numbers each
+fn quad_bounds(doc: &Document, quads: &Object) -> Vec<[f64; 4]> {
+    let values = numbers(doc, quads).unwrap_or_default();
+    values
+        .chunks_exact(8)
+        .map(|quad| {
+            let (xs, ys) = (quad.iter().step_by(2), quad.iter().skip(1).step_by(2));
+            [
+                xs.clone().copied().fold(f64::INFINITY, f64::min),
+                ys.clone().copied().fold(f64::INFINITY, f64::min),
+                xs.copied().fold(f64::NEG_INFINITY, f64::max),
+                ys.copied().fold(f64::NEG_INFINITY, f64::max),
+            ]
+        })
+        .collect()
+}
+
+/// The numbers of an array
+fn numbers(doc: &Document, array: &Object) -> Option<Vec<f64>> {
+    doc.dereference(array)
+        .ok()?
+        .1
+        .as_array()
+        .ok()?
+        .iter()
+        .map(|value| value.as_float().ok().map(f64::from))
+        .collect()
+}
+
+/// What a link annotation does: its `/Dest`, or else its action (`/A`)
+fn link_action(doc: &Document, annotation: &Dictionary, resolver: &DestinationResolver) -> LinkAction {
+    if let Ok(dest) = annotation.get(b"Dest") {
+        return LinkAction::GoTo { page: resolver.resolve(dest), destination: destination_name(doc, dest) };
+    }
+    let Some(action) = annotation
+        .get(b"A")
+        .ok()
+        .and_then(|action| doc.dereference(action).ok())
+        .and_then(|(_, action)| action.as_dict().ok())
+    else {
+        return LinkAction::Other { action: None };
+    };
+    let Ok(kind) = action.get(b"S").and_then(Object::as_name) else {
+        return LinkAction::Other { action: None };
+    };
+    let new_window = action.get(b"NewWindow").and_then(Object::as_bool).ok();
+
+    match kind {
+        b"URI" => match uri(doc, action) {
+            Some(uri) => LinkAction::Uri { uri },
+            None => LinkAction::Other { action: Some("URI".to_string()) },
+        },
+        b"GoTo" => {
+            let dest = action.get(b"D").ok();
+            LinkAction::GoTo {
+                page: dest.and_then(|dest| resolver.resolve(dest)),
+                destination: dest.and_then(|dest| destination_name(doc, dest)),
+            }
+        }
+        b"GoToR" => {
+            let Some(file) = action.get(b"F").ok().and_then(|file| file_specification(doc, file)) else {
+                return LinkAction::Other { action: Some("GoToR".to_string()) };
+            };
+            let dest = action.get(b"D").ok();
+            LinkAction::GoToR {
+                file,
+                page: dest.and_then(|dest| remote_page(doc, dest)),
+                destination: dest.and_then(|dest| destination_name(doc, dest)),
+                new_window,
+            }
+        }
+        b"Launch" => {
+            // Platform dictionaries name the file under /F as well; Windows adds parameters
+            let windows = action
+                .get(b"Win")
+                .ok()
+                .and_then(|win| doc.dereference(win).ok())
+                .and_then(|(_, win)| win.as_dict().ok());
+            let platform_file = [b"Win".as_slice(), b"Unix", b"Mac"].into_iter().find_map(|key| {
+                let (_, platform) = doc.dereference(action.get(key).ok()?).ok()?;
+                file_specification(doc, platform.as_dict().ok()?.get(b"F").ok()?)
+            });
+            let file = action.get(b"F").ok().and_then(|file| file_specification(doc, file)).or(platform_file);
+            let parameters = windows.and_then(|win| win.get(b"P").ok()).and_then(|parameters| text(doc, parameters));
+            LinkAction::Launch { file, parameters, new_window }
+        }
+        kind => LinkAction::Other { action: Some(String::from_utf8_lossy(kind).into_owned()) },
+    }
+}
+
+/// The URI of a URI action
+fn uri(doc: &Document, action: &Dictionary) -> Option<String> {
+    let (_, uri) = doc.dereference(action.get(b"URI").ok()?).ok()?;
+    let uri = String::from_utf8_lossy(uri.as_str().ok()?).trim().to_string();
+    (!uri.is_empty()).then_some(uri)
+}
+
+/// The name of a named destination
+fn destination_name(doc: &Document, dest: &Object) -> Option<String> {
+    match doc.dereference(dest).ok()?.1 {
+        Object::Name(name) => Some(String::from_utf8_lossy(name).into_owned()),
+        name @ Object::String(..) => text(doc, name),
+        _ => None,
+    }
+}
+
+/// The page (1-indexed) of a destination in another file, given by its 0-indexed number
+fn remote_page(doc: &Document, dest: &Object) -> Option<u32> {
+    match doc.dereference(dest).ok()?.1.as_array().ok()?.first()? {
+        Object::Integer(index) => u32::try_from(*index).ok().map(|index| index + 1),
+        _ => None,
+    }
+}
+
+/// The file named by a file specification: a string, or a dictionary preferring its
+/// Unicode name (`/UF`) over `/F` and the platform entries
+fn file_specification(doc: &Document, specification: &Object) -> Option<String> {
+    let (_, specification) = doc.dereference(specification).ok()?;
+    match specification {
+        Object::Dictionary(dict) => [b"UF".as_slice(), b"F", b"Unix", b"DOS", b"Mac"]
+            .into_iter()
+            .find_map(|key| dict.get(key).ok().and_then(|name| text(doc, name))),
+        name => text(doc, name),
+    }
+}
+
+/// A non-empty text string
+fn text(doc: &Document, object: &Object) -> Option<String> {
+    let (_, object) = doc.dereference(object).ok()?;
+    let text = lopdf::decode_text_string(object).ok()?;
+    (!text.trim().is_empty()).then_some(text)
+}
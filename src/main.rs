@@ -2,9 +2,31 @@
 //!
 //! A Rust-based MCP Server that provides PDF reading capabilities as a Kiro Power.
 
+mod bidi;
+mod cmap;
+mod content;
+mod encryption;
 mod error;
+mod encodings;
+mod font_program;
+mod fonts;
+mod furniture;
+mod glyph_list;
+mod html;
+mod layout;
+mod links;
+mod markdown;
+mod metadata;
+mod normalize;
+mod outline;
+mod page_labels;
+mod page_selection;
 mod pdf_reader;
+mod reading_order;
+mod structure;
 mod service;
+mod tables;
+mod xmp;
 
 pub use error::PdfError;
 pub use pdf_reader::{PdfInfo, PdfReader};
@@ -0,0 +1,556 @@
+//! Markdown: page text as headings, paragraphs, lists and tables
+//!
+//! Tables found on a page are taken out of its text and the rest is read in reading order
+//! (see the `reading_order` module). Lines are then grouped into blocks: a block ends where
+//! the font size or weight changes, where the gap to the next line is wider than the page's
+//! line spacing, where the next line is indented or starts with a list marker, and after a
+//! short line that ends a sentence. Heading levels are assigned over the whole document:
+//! blocks of a few lines set larger than the body text are headings ranked by size, and
+//! short bold blocks at body size rank below them. Tables are placed before the first block
+//! below their top edge.
+//!
+//! The blocks with their roles (see [`Element`]) are shared with the HTML export; Markdown
+//! renders them with tables as pipe tables, and leaves out the links the HTML export keeps.
+
+use crate::bidi;
+use crate::content::{PositionedGlyph, Ruling};
+use crate::layout;
+use crate::links::{LinkTarget, PageLink};
+use crate::normalize;
+use crate::pdf_reader::{BidiOrder, TextOptions};
+use crate::reading_order::{self, Segment};
+use crate::tables::{self, Table};
+use std::ops::Range;
+
+/// Baseline step between lines, as a fraction of the font size, for pages whose line
+/// spacing cannot be measured
+const DEFAULT_LINE_SPACING: f64 = 1.2;
+
+/// Extra space between lines, as a fraction of the font size, that separates paragraphs
+const PARAGRAPH_GAP: f64 = 0.4;
+
+/// Indentation, as a fraction of the font size, that starts a new paragraph, and that
+/// sets list items apart as nested or continues them on the next line
+const INDENT: f64 = 0.8;
+
+/// Distance from the right edge of a paragraph, as a fraction of the font size, at which
+/// a line ending a sentence counts as the paragraph's last line
+const SHORT_LINE: f64 = 3.0;
+
+/// Difference in font size, in points, below which two lines are set in the same size
+const SIZE_TOLERANCE: f64 = 0.5;
+
+/// Font size, relative to the body text, from which a block counts as a heading
+const HEADING_SCALE: f64 = 1.15;
+
+/// Most lines of a heading
+const MAX_HEADING_LINES: usize = 3;
+
+/// Most characters of a heading
+const MAX_HEADING_CHARS: usize = 200;
+
+/// Parts of font names that mark monospaced fonts, whose lines are kept apart from text
+/// set in other fonts
+const MONOSPACED_FONTS: [&str; 5] = ["mono", "courier", "consolas", "cmtt", "sftt"];
+
+/// Characters that mark bulleted list items
+const BULLETS: [char; 12] = ['•', '◦', '▪', '▫', '‣', '⁃', '∙', '·', '○', '●', '■', '□'];
+
+/// Characters that mark bulleted list items only as words of their own, since they also
+/// start numbers, ranges and emphasis
+const DASHES: [&str; 4] = ["-", "–", "—", "*"];
+
+/// A line of a page, with the style of its glyphs
+///
+/// On right-to-left pages positions are mirrored, so that lines always start at `x0`.
+pub(crate) struct Line {
+    text: String,
+    x0: f64,
+    x1: f64,
+    top: f64,
+    baseline: f64,
+    /// Median font size of the line's glyphs
+    font_size: f64,
+    /// Whether all glyphs of the line are set in a bold font
+    bold: bool,
+    /// Whether all glyphs of the line are set in a monospaced font
+    monospaced: bool,
+    /// Parts of the text inside link annotations, one per link
+    links: Vec<Anchor>,
+}
+
+/// A part of a text that a link annotation covers
+pub(crate) struct Anchor {
+    /// Byte range of the part in the text
+    pub(crate) range: Range<usize>,
+    pub(crate) target: LinkTarget,
+}
+
+/// A part of a page, in reading order
+pub(crate) enum Block {
+    /// Lines that form a paragraph, a heading or a list item
+    Text(Vec<Line>),
+    Table(Table),
+}
+
+/// A block of text with the role inferred for it, or a table
+pub(crate) enum Element<'a> {
+    Heading {
+        /// Level from 1, for the largest headings of the document
+        level: usize,
+        text: String,
+        links: Vec<Anchor>,
+    },
+    Paragraph {
+        text: String,
+        links: Vec<Anchor>,
+    },
+    ListItem {
+        /// Nesting depth from 1, for items outside other lists
+        depth: usize,
+        marker: Marker,
+        /// The item's text after its marker
+        text: String,
+        links: Vec<Anchor>,
+    },
+    Table(&'a Table),
+}
+
+/// The kind of marker that starts a list item
+pub(crate) enum Marker {
+    Bullet,
+    /// A number followed by a period or parenthesis, rendered as an ordered list item
+    Number(u32),
+    /// Letters or roman numerals such as "a)" or "(iv)", kept as written
+    Label(String),
+}
+
+/// Split a page into blocks of text and tables, in reading order
+///
+/// Tables are looked for, and `links` matched with the words they cover, on pages of
+/// horizontal text only.
+pub(crate) fn page_blocks(
+    mut glyphs: Vec<PositionedGlyph>,
+    rulings: &[Ruling],
+    links: &[PageLink],
+    page: u32,
+    options: &TextOptions,
+) -> Vec<Block> {
+    let vertical = layout::is_mostly_vertical(&glyphs);
+    let mut tables = Vec::new();
+    if !vertical {
+        // Table detection reorders right-to-left text in place, so it works on a copy
+        tables = tables::find_tables(&mut glyphs.clone(), rulings, page, options);
+        glyphs.retain(|glyph| {
+            let (x, y) = ((glyph.bbox[0] + glyph.bbox[2]) / 2.0, (glyph.bbox[1] + glyph.bbox[3]) / 2.0);
+            !tables.iter().any(|table| {
+                table.bbox.x0 <= x && x <= table.bbox.x1 && table.bbox.y0 <= y && y <= table.bbox.y1
+            })
+        });
+    }
+    let (mut glyphs, links) = if vertical { (layout::turn_vertical(&glyphs), &[][..]) } else { (glyphs, links) };
+
+    let right_to_left = options.bidi == BidiOrder::Logical && bidi::is_mostly_rtl(&glyphs);
+    let segments = reading_order::segments(layout::group_glyphs(&mut glyphs, options));
+    let lines: Vec<Line> = reading_order::reading_lines(segments, right_to_left)
+        .iter()
+        .map(|segments| make_line(segments, links, right_to_left, options))
+        .collect();
+
+    let mut blocks: Vec<Block> = group_blocks(lines).into_iter().map(Block::Text).collect();
+    for table in tables {
+        let (x0, x1) = if right_to_left { (-table.bbox.x1, -table.bbox.x0) } else { (table.bbox.x0, table.bbox.x1) };
+        let position = blocks
+            .iter()
+            .position(|block| match block {
+                Block::Text(lines) => lines[0].top <= table.bbox.y1 && lines[0].x0 < x1 && x0 < lines[0].x1,
+                Block::Table(_) => false,
+            })
+            .unwrap_or(blocks.len());
+        blocks.insert(position, Block::Table(table));
+    }
+    blocks
+}
+
+/// Assign roles to the blocks of the selected pages
+///
+/// Heading levels are ranked over all pages. List items are nested by indentation, and
+/// lists end at the first block that is not a list item and at the end of a page. Blocks
+/// without text are left out.
+pub(crate) fn elements<'a>(pages: &'a [(u32, Vec<Block>)], options: &TextOptions) -> Vec<(u32, Vec<Element<'a>>)> {
+    let texts = || {
+        pages.iter().flat_map(|(_, blocks)| blocks).filter_map(|block| match block {
+            Block::Text(lines) => Some(lines),
+            Block::Table(_) => None,
+        })
+    };
+    let body_size = body_size(texts().flatten());
+
+    // Sizes of the headings set larger than the body text, largest first
+    let mut heading_sizes: Vec<f64> = Vec::new();
+    for lines in texts() {
+        if is_heading(lines) && lines[0].font_size >= body_size * HEADING_SCALE {
+            heading_sizes.push(lines[0].font_size);
+        }
+    }
+    heading_sizes.sort_by(|a, b| b.total_cmp(a));
+    heading_sizes.dedup_by(|a, b| (*b - *a).abs() < SIZE_TOLERANCE);
+    let level = |lines: &[Line], text: &str| {
+        let size = lines[0].font_size;
+        if !is_heading(lines) {
+            return None;
+        }
+        if size >= body_size * HEADING_SCALE {
+            let rank = heading_sizes.iter().position(|&heading| (heading - size).abs() < SIZE_TOLERANCE);
+            return Some(rank.unwrap_or(0) + 1);
+        }
+        let plain_end = !text.ends_with(['.', ',', ';', ':']);
+        (lines.iter().all(|line| line.bold) && size > body_size - SIZE_TOLERANCE && plain_end)
+            .then_some(heading_sizes.len() + 1)
+    };
+
+    let mut result = Vec::new();
+    for (page, blocks) in pages {
+        let mut elements = Vec::new();
+        // Left edges of the list items the current item is nested in; empty outside lists
+        let mut list_indents: Vec<f64> = Vec::new();
+        for block in blocks {
+            let lines = match block {
+                Block::Table(table) => {
+                    list_indents.clear();
+                    elements.push(Element::Table(table));
+                    continue;
+                }
+                Block::Text(lines) => lines,
+            };
+            let (text, placed) = join_lines(lines, options);
+            if text.is_empty() {
+                continue;
+            }
+            let mut links: Vec<Anchor> = lines
+                .iter()
+                .zip(&placed)
+                .flat_map(|(line, placed)| line.links.iter().map(move |anchor| (anchor, placed)))
+                .filter_map(|(anchor, placed)| {
+                    let end = (placed.start + anchor.range.end).min(placed.end);
+                    let range = trim_range(&text, placed.start + anchor.range.start..end)?;
+                    Some(Anchor { range, target: anchor.target.clone() })
+                })
+                .collect();
+            links.sort_by_key(|anchor| anchor.range.start);
+            let (marker, rest) = match (level(lines, &text), list_marker(&text)) {
+                (Some(level), _) => {
+                    list_indents.clear();
+                    elements.push(Element::Heading { level, text, links });
+                    continue;
+                }
+                (None, None) => {
+                    list_indents.clear();
+                    elements.push(Element::Paragraph { text, links });
+                    continue;
+                }
+                (None, Some(item)) => item,
+            };
+            // Links within the marker are dropped with it
+            let marker_length = text.len() - rest.len();
+            if !text.ends_with(rest.as_str()) {
+                links.clear();
+            }
+            let links = links
+                .into_iter()
+                .filter(|anchor| anchor.range.start >= marker_length)
+                .map(|anchor| Anchor {
+                    range: anchor.range.start - marker_length..anchor.range.end - marker_length,
+                    target: anchor.target,
+                })
+                .collect();
+
+            let (x0, tolerance) = (lines[0].x0, INDENT * lines[0].font_size);
+            while let Some(&indent) = list_indents.last()
+                && indent > x0 + tolerance
+            {
+                list_indents.pop();
+            }
+            if list_indents.last().is_none_or(|&indent| indent < x0 - tolerance) {
+                list_indents.push(x0);
+            }
+            elements.push(Element::ListItem { depth: list_indents.len(), marker, text: rest, links });
+        }
+        result.push((*page, elements));
+    }
+    result
+}
+
+/// Render the elements of the selected pages as one Markdown document
+///
+/// With `page_anchors`, every page starts with an HTML anchor named `page-N` after its
+/// physical page number, so that links and citations can point at it.
+pub(crate) fn render(pages: &[(u32, Vec<Element>)], page_anchors: bool) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    for (page, elements) in pages {
+        if page_anchors {
+            parts.push(format!("<a id=\"page-{}\"></a>", page));
+        }
+        let mut in_list = false;
+        for element in elements {
+            let part = match element {
+                Element::Table(table) => table.markdown.trim_end().to_string(),
+                Element::Heading { level, text, .. } => format!("{} {}", "#".repeat((*level).min(6)), text),
+                Element::Paragraph { text, .. } => escape_start(text),
+                Element::ListItem { depth, marker, text, .. } => {
+                    let item = match marker {
+                        Marker::Bullet => format!("- {}", text),
+                        Marker::Number(number) => format!("{}. {}", number, text),
+                        Marker::Label(label) => format!("- {} {}", label, text),
+                    };
+                    let item = format!("{}{}", "    ".repeat(depth - 1), item);
+                    match parts.last_mut() {
+                        Some(last) if in_list => {
+                            last.push('\n');
+                            last.push_str(&item);
+                        }
+                        _ => parts.push(item),
+                    }
+                    in_list = true;
+                    continue;
+                }
+            };
+            in_list = false;
+            parts.push(part);
+        }
+    }
+    parts.join("\n\n")
+}
+
+/// Build a line from the segments it holds
+///
+/// A link covers the glyphs of the line whose centers lie inside its rectangle.
+fn make_line(segments: &[Segment], links: &[PageLink], right_to_left: bool, options: &TextOptions) -> Line {
+    // The words of the line joined by spaces, with the range of each glyph's text in it
+    let mut raw = String::new();
+    let mut glyph_ranges = Vec::new();
+    for (n, word) in segments.iter().flat_map(|segment| &segment.words).enumerate() {
+        if n > 0 {
+            raw.push(' ');
+        }
+        for &glyph in word {
+            let start = raw.len();
+            raw.push_str(&glyph.text);
+            glyph_ranges.push((glyph, start..raw.len()));
+        }
+    }
+    let text = normalize::normalize_text(raw.clone(), &options.normalization);
+    // Offsets into the raw text moved to the normalized one
+    let offset = |raw_offset: usize| {
+        normalize::normalize_text(raw[..raw_offset].to_string(), &options.normalization).len().min(text.len())
+    };
+    let anchors = links
+        .iter()
+        .filter_map(|link| {
+            let covered = || glyph_ranges.iter().filter(|(glyph, _)| link.covers(glyph)).map(|(_, range)| range);
+            let start = covered().map(|range| range.start).min()?;
+            let end = covered().map(|range| range.end).max()?;
+            let range = trim_range(&text, offset(start)..offset(end))?;
+            Some(Anchor { range, target: link.target.clone() })
+        })
+        .collect();
+
+    let glyphs = || segments.iter().flat_map(|segment| segment.words.iter().flatten());
+    let x0 = segments.iter().map(|segment| segment.bbox[0]).fold(f64::INFINITY, f64::min);
+    let x1 = segments.iter().map(|segment| segment.bbox[2]).fold(f64::NEG_INFINITY, f64::max);
+    Line {
+        text,
+        x0: if right_to_left { -x1 } else { x0 },
+        x1: if right_to_left { -x0 } else { x1 },
+        top: segments.iter().map(|segment| segment.bbox[3]).fold(f64::NEG_INFINITY, f64::max),
+        baseline: segments[0].baseline,
+        font_size: layout::median(glyphs().map(|glyph| glyph.font_size)).unwrap_or(segments[0].font_size),
+        bold: glyphs().all(|glyph| glyph.font_name.to_ascii_lowercase().contains("bold")),
+        monospaced: glyphs().all(|glyph| {
+            let font = glyph.font_name.to_ascii_lowercase();
+            MONOSPACED_FONTS.iter().any(|name| font.contains(name))
+        }),
+        links: anchors,
+    }
+}
+
+/// Group the lines of a page, in reading order, into blocks
+fn group_blocks(lines: Vec<Line>) -> Vec<Vec<Line>> {
+    let same_size = |a: &Line, b: &Line| (a.font_size - b.font_size).abs() < SIZE_TOLERANCE;
+    // Baseline step between the lines of a paragraph, as a fraction of the font size
+    let spacing = layout::median(lines.windows(2).filter_map(|pair| {
+        let drop = pair[0].baseline - pair[1].baseline;
+        (same_size(&pair[0], &pair[1]) && drop > 0.0 && drop < 3.0 * pair[0].font_size)
+            .then(|| drop / pair[0].font_size)
+    }))
+    .unwrap_or(DEFAULT_LINE_SPACING);
+
+    let mut blocks: Vec<Vec<Line>> = Vec::new();
+    for line in lines {
+        if let Some(block) = blocks.last_mut()
+            && continues_block(block, &line, spacing)
+        {
+            block.push(line);
+        } else {
+            blocks.push(vec![line]);
+        }
+    }
+    blocks
+}
+
+/// Whether a line continues the block above it
+fn continues_block(block: &[Line], line: &Line, spacing: f64) -> bool {
+    let (first, previous) = (&block[0], &block[block.len() - 1]);
+    let size = previous.font_size.max(line.font_size);
+    let drop = previous.baseline - line.baseline;
+    let right = block.iter().map(|line| line.x1).fold(line.x1, f64::max);
+    let in_list = list_marker(&first.text).is_some();
+
+    let same_style = (previous.font_size - line.font_size).abs() < SIZE_TOLERANCE
+        && previous.bold == line.bold
+        && previous.monospaced == line.monospaced;
+    let close = drop > 0.0 && drop <= (spacing + PARAGRAPH_GAP) * size;
+    let overlapping = line.x0 < previous.x1 && previous.x0 < line.x1;
+    let ends_paragraph = previous.text.ends_with(['.', '!', '?', ':']) && previous.x1 < right - SHORT_LINE * size;
+    let indented = if in_list {
+        // Lines of a list item hang below its text rather than its marker
+        line.x0 <= first.x0 + INDENT * size
+    } else {
+        line.x0 > previous.x0 + INDENT * size
+    };
+    same_style && close && overlapping && !ends_paragraph && !indented && list_marker(&line.text).is_none()
+}
+
+/// The text of a block, its lines joined by spaces, and the range of each line in it
+///
+/// A word hyphenated at the end of a line is joined with its rest without a space; the
+/// hyphen is dropped when dehyphenation is enabled and the rest starts in lowercase. Lines
+/// of Chinese, Japanese or Korean text are joined without a space too.
+fn join_lines(lines: &[Line], options: &TextOptions) -> (String, Vec<Range<usize>>) {
+    let mut text = String::new();
+    let mut placed: Vec<Range<usize>> = Vec::new();
+    for line in lines {
+        if line.text.is_empty() {
+            placed.push(text.len()..text.len());
+            continue;
+        }
+        if reading_order::ends_hyphenated(&text) {
+            if options.normalization.dehyphenate && line.text.starts_with(char::is_lowercase) {
+                text.pop();
+                if let Some(previous) = placed.last_mut() {
+                    previous.end = previous.end.min(text.len());
+                }
+            }
+        } else if !text.is_empty() {
+            let wide = text.ends_with(is_wide) && line.text.starts_with(is_wide);
+            if !wide {
+                text.push(' ');
+            }
+        }
+        let start = text.len();
+        text.push_str(&line.text);
+        placed.push(start..text.len());
+    }
+    (text, placed)
+}
+
+/// A range of a text moved onto character boundaries and trimmed of whitespace, unless
+/// nothing is left of it
+fn trim_range(text: &str, range: Range<usize>) -> Option<Range<usize>> {
+    let (mut start, mut end) = (range.start.min(text.len()), range.end.min(text.len()));
+    while !text.is_char_boundary(start) {
+        start -= 1;
+    }
+    while !text.is_char_boundary(end) {
+        end += 1;
+    }
+    if start >= end {
+        return None;
+    }
+    let part = &text[start..end];
+    let (start, end) = (start + part.len() - part.trim_start().len(), end - (part.len() - part.trim_end().len()));
+    (start < end).then_some(start..end)
+}
+
+/// Whether a character is a CJK ideograph, kana, hangul syllable or full-width form, which
+/// are written without spaces between words
+fn is_wide(c: char) -> bool {
+    matches!(c, '\u{1100}'..='\u{11FF}' | '\u{2E80}'..='\u{A4CF}' | '\u{AC00}'..='\u{D7A3}' | '\u{F900}'..='\u{FAFF}')
+        || matches!(c, '\u{FE30}'..='\u{FE4F}' | '\u{FF00}'..='\u{FF60}' | '\u{FFE0}'..='\u{FFE6}')
+}
+
+/// Font size of most of the characters of the document
+fn body_size<'a>(lines: impl Iterator<Item = &'a Line>) -> f64 {
+    let mut sizes: Vec<(f64, usize)> = Vec::new();
+    for line in lines {
+        let characters = line.text.chars().filter(|c| !c.is_whitespace()).count();
+        match sizes.iter_mut().find(|(size, _)| (size - line.font_size).abs() < SIZE_TOLERANCE) {
+            Some((_, count)) => *count += characters,
+            None => sizes.push((line.font_size, characters)),
+        }
+    }
+    sizes.iter().max_by_key(|(_, count)| *count).map_or(10.0, |(size, _)| *size)
+}
+
+/// Whether a block is short enough to be a heading and reads like one
+///
+/// Entries of a table of contents, with their leader dots, are not headings.
+fn is_heading(lines: &[Line]) -> bool {
+    let characters: usize = lines.iter().map(|line| line.text.chars().count()).sum();
+    lines.len() <= MAX_HEADING_LINES
+        && characters <= MAX_HEADING_CHARS
+        && lines.iter().any(|line| line.text.chars().any(char::is_alphabetic))
+        && !lines.iter().any(|line| line.text.contains(". . .") || line.text.contains("...."))
+        && !matches!(list_marker(&lines[0].text), Some((Marker::Bullet, _)))
+}
+
+/// The list marker a text starts with, and the text after it
+fn list_marker(text: &str) -> Option<(Marker, String)> {
+    let (first, rest) = text.split_once(' ').unwrap_or((text, ""));
+    let rest = rest.trim_start();
+
+    if let Some(bullet) = first.chars().next().filter(|c| BULLETS.contains(c)) {
+        let attached = first[bullet.len_utf8()..].trim_start();
+        let rest = match (attached.is_empty(), rest.is_empty()) {
+            (true, _) => rest.to_string(),
+            (false, true) => attached.to_string(),
+            (false, false) => format!("{} {}", attached, rest),
+        };
+        return (!rest.is_empty()).then_some((Marker::Bullet, rest));
+    }
+    if rest.is_empty() {
+        return None;
+    }
+    if DASHES.contains(&first) {
+        return Some((Marker::Bullet, rest.to_string()));
+    }
+
+    let label = first.strip_prefix('(').and_then(|label| label.strip_suffix(')'));
+    let label = label.or_else(|| first.strip_suffix('.').or_else(|| first.strip_suffix(')')))?;
+    let is_roman = |label: &str| {
+        matches!(label, "i" | "ii" | "iii" | "iv" | "v" | "vi" | "vii" | "viii" | "ix" | "x" | "xi" | "xii")
+    };
+    if (1..=3).contains(&label.len()) && label.bytes().all(|b| b.is_ascii_digit()) {
+        Some((Marker::Number(label.parse().ok()?), rest.to_string()))
+    } else if (label.len() == 1 && label.bytes().all(|b| b.is_ascii_lowercase())) || is_roman(label) {
+        Some((Marker::Label(first.to_string()), rest.to_string()))
+    } else {
+        None
+    }
+}
+
+/// Escape the characters at the start of a paragraph that Markdown would read as a heading,
+/// block quote, list item or thematic break
+fn escape_start(text: &str) -> String {
+    let digits = text.bytes().take_while(u8::is_ascii_digit).count();
+    if digits > 0 && text[digits..].starts_with(['.', ')']) {
+        return format!("{}\\{}", &text[..digits], &text[digits..]);
+    }
+    let mut chars = text.chars();
+    let (first, second) = (chars.next(), chars.next());
+    let repeated_or_spaced = second.is_none_or(|second| second.is_whitespace() || Some(second) == first);
+    if first == Some('>') || (matches!(first, Some('#' | '-' | '+' | '*' | '=' | '_')) && repeated_or_spaced) {
+        return format!("\\{}", text);
+    }
+    text.to_string()
+}
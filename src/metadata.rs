@@ -0,0 +1,261 @@
+//! Document metadata from the Info dictionary and XMP stream
+//!
+//! When both sources define a field, XMP takes precedence: PDF 2.0 deprecates the Info
+//! dictionary and most producers treat XMP as authoritative. The exception is a stale
+//! XMP packet — if the Info `ModDate` is later than the XMP modification date, the
+//! document was last edited by a tool that only updated Info, so Info wins. Fields
+//! missing from the preferred source are always filled from the other one.
+
+use crate::xmp::XmpMetadata;
+use lopdf::{Dictionary, Document, Object};
+use std::collections::BTreeMap;
+
+/// Keys defined by the PDF specification for the Info dictionary
+const STANDARD_INFO_KEYS: [&[u8]; 9] = [
+    b"Title",
+    b"Author",
+    b"Subject",
+    b"Keywords",
+    b"Creator",
+    b"Producer",
+    b"CreationDate",
+    b"ModDate",
+    b"Trapped",
+];
+
+/// Entries of the document's Info dictionary
+#[derive(Debug, Default)]
+pub(crate) struct InfoMetadata {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub subject: Option<String>,
+    pub keywords: Option<String>,
+    pub creator: Option<String>,
+    pub producer: Option<String>,
+    pub creation_date: Option<String>,
+    pub modification_date: Option<String>,
+    pub trapped: Option<String>,
+    pub custom: BTreeMap<String, String>,
+}
+
+/// Extract metadata from the document's Info dictionary
+///
+/// Dates are converted to ISO 8601; values that are not valid PDF dates are kept as written.
+pub(crate) fn read_info_dictionary(doc: &Document) -> InfoMetadata {
+    let Some(info_dict) = info_dictionary(doc) else {
+        return InfoMetadata::default();
+    };
+
+    let date = |key: &[u8]| {
+        get_string_from_dict(info_dict, key).map(|raw| parse_pdf_date(&raw).unwrap_or(raw))
+    };
+
+    let custom = info_dict
+        .iter()
+        .filter(|(key, _)| !STANDARD_INFO_KEYS.contains(&key.as_slice()))
+        .filter_map(|(key, value)| {
+            let value = object_to_string(doc, value)?;
+            Some((String::from_utf8_lossy(key).into_owned(), value))
+        })
+        .collect();
+
+    InfoMetadata {
+        title: get_string_from_dict(info_dict, b"Title"),
+        author: get_string_from_dict(info_dict, b"Author"),
+        subject: get_string_from_dict(info_dict, b"Subject"),
+        keywords: get_string_from_dict(info_dict, b"Keywords"),
+        creator: get_string_from_dict(info_dict, b"Creator"),
+        producer: get_string_from_dict(info_dict, b"Producer"),
+        creation_date: date(b"CreationDate"),
+        modification_date: date(b"ModDate"),
+        trapped: info_dict.get(b"Trapped").ok().and_then(|obj| object_to_string(doc, obj)),
+        custom,
+    }
+}
+
+/// Document metadata: the Info dictionary merged with the XMP stream, when there is one
+pub(crate) fn read_metadata(doc: &Document, xmp: Option<&XmpMetadata>) -> InfoMetadata {
+    match xmp {
+        Some(xmp) => merge_xmp(read_info_dictionary(doc), xmp),
+        None => read_info_dictionary(doc),
+    }
+}
+
+/// Combine Info values with XMP values following the module's precedence rule
+pub(crate) fn merge_xmp(mut info: InfoMetadata, xmp: &XmpMetadata) -> InfoMetadata {
+    // Custom Info entries are reported as they are; XMP keeps its own `custom` map
+    let custom = std::mem::take(&mut info.custom);
+    let join = |values: &[String], separator: &str| (!values.is_empty()).then(|| values.join(separator));
+
+    let from_xmp = InfoMetadata {
+        title: xmp.title.clone(),
+        author: join(&xmp.creators, "; "),
+        subject: xmp.description.clone(),
+        keywords: xmp.keywords.clone().or_else(|| join(&xmp.subjects, ", ")),
+        creator: xmp.creator_tool.clone(),
+        producer: xmp.producer.clone(),
+        creation_date: xmp.create_date.clone(),
+        modification_date: xmp.modify_date.clone(),
+        trapped: xmp.trapped.clone(),
+        custom: BTreeMap::new(),
+    };
+
+    let xmp_date = xmp.modify_date.as_deref().or(xmp.metadata_date.as_deref());
+    let info_is_newer = match (info.modification_date.as_deref().and_then(timestamp), xmp_date.and_then(timestamp)) {
+        (Some(info_date), Some(xmp_date)) => info_date > xmp_date,
+        _ => false,
+    };
+
+    let (preferred, fallback) = if info_is_newer { (info, from_xmp) } else { (from_xmp, info) };
+
+    InfoMetadata {
+        title: preferred.title.or(fallback.title),
+        author: preferred.author.or(fallback.author),
+        subject: preferred.subject.or(fallback.subject),
+        keywords: preferred.keywords.or(fallback.keywords),
+        creator: preferred.creator.or(fallback.creator),
+        producer: preferred.producer.or(fallback.producer),
+        creation_date: preferred.creation_date.or(fallback.creation_date),
+        modification_date: preferred.modification_date.or(fallback.modification_date),
+        trapped: preferred.trapped.or(fallback.trapped),
+        custom,
+    }
+}
+
+/// Seconds since the Unix epoch of an ISO 8601 date (`YYYY[-MM[-DD[THH:MM[:SS[.s]]]]][Z|±HH:MM]`)
+///
+/// Dates without an offset are treated as UTC, which is good enough to order them.
+fn timestamp(date: &str) -> Option<i64> {
+    let (date_part, time_part) = date.split_once('T').unwrap_or((date, ""));
+
+    let mut date_fields = date_part.split('-').map(str::parse::<i64>);
+    let year = date_fields.next()?.ok()?;
+    let month = date_fields.next().unwrap_or(Ok(1)).ok()?;
+    let day = date_fields.next().unwrap_or(Ok(1)).ok()?;
+
+    let offset_start = time_part.find(['Z', '+', '-']).unwrap_or(time_part.len());
+    let (clock, zone) = time_part.split_at(offset_start);
+
+    let mut clock_fields = clock.split(':').filter(|field| !field.is_empty());
+    let hour: i64 = clock_fields.next().unwrap_or("0").parse().ok()?;
+    let minute: i64 = clock_fields.next().unwrap_or("0").parse().ok()?;
+    let second: f64 = clock_fields.next().unwrap_or("0").parse().ok()?;
+
+    let offset_minutes = match zone.split_at_checked(1) {
+        Some((sign @ ("+" | "-"), rest)) => {
+            let (hours, minutes) = rest.split_once(':').unwrap_or((rest, "0"));
+            let minutes = hours.parse::<i64>().ok()? * 60 + minutes.parse::<i64>().ok()?;
+            if sign == "-" { -minutes } else { minutes }
+        }
+        _ => 0,
+    };
+
+    // Days from civil date (proleptic Gregorian calendar)
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    Some(days * 86_400 + hour * 3_600 + minute * 60 + second as i64 - offset_minutes * 60)
+}
+
+/// Locate the Info dictionary referenced from the trailer
+fn info_dictionary(doc: &Document) -> Option<&Dictionary> {
+    let info_ref = doc.trailer.get(b"Info").ok()?;
+
+    match info_ref.as_reference() {
+        Ok(ref_id) => doc.get_dictionary(ref_id).ok(),
+        // Maybe it's a direct dictionary
+        Err(_) => info_ref.as_dict().ok(),
+    }
+}
+
+/// Helper to extract a string value from a dictionary
+fn get_string_from_dict(dict: &Dictionary, key: &[u8]) -> Option<String> {
+    dict.get(key).ok().and_then(|obj| {
+        // decode_text_string takes an Object reference and returns Result<String>
+        lopdf::decode_text_string(obj).ok()
+    })
+}
+
+/// Render a simple Info value (string, name, number or boolean) as text
+fn object_to_string(doc: &Document, obj: &Object) -> Option<String> {
+    let (_, obj) = doc.dereference(obj).ok()?;
+    match obj {
+        Object::String(..) => lopdf::decode_text_string(obj).ok(),
+        Object::Name(name) => Some(String::from_utf8_lossy(name).into_owned()),
+        Object::Integer(i) => Some(i.to_string()),
+        Object::Real(r) => Some(r.to_string()),
+        Object::Boolean(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// Convert a PDF date string (`D:YYYYMMDDHHmmSSOHH'mm'`) to ISO 8601
+///
+/// Omitted fields take their defaults (month and day 01, time 00:00:00). The offset is
+/// only included when the date specifies one, since PDF dates without it are in an
+/// unknown time zone. Returns `None` when the string is not a valid PDF date.
+pub(crate) fn parse_pdf_date(raw: &str) -> Option<String> {
+    let s = raw.trim();
+    let s = s.strip_prefix("D:").unwrap_or(s);
+
+    let digit_count = s.bytes().take_while(u8::is_ascii_digit).count();
+    if !(4..=14).contains(&digit_count) || !digit_count.is_multiple_of(2) {
+        return None;
+    }
+    let (digits, zone) = s.split_at(digit_count);
+
+    let field = |start: usize, default: u32| -> Option<u32> {
+        match digits.get(start..start + 2) {
+            Some(value) => value.parse().ok(),
+            None => Some(default),
+        }
+    };
+
+    let year: u32 = digits[..4].parse().ok()?;
+    let month = field(4, 1)?;
+    let day = field(6, 1)?;
+    let hour = field(8, 0)?;
+    let minute = field(10, 0)?;
+    let second = field(12, 0)?;
+
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+
+    let offset = parse_pdf_date_offset(zone)?;
+
+    Some(format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{}",
+        year, month, day, hour, minute, second, offset
+    ))
+}
+
+/// Convert the time zone part of a PDF date (`Z`, `+HH'mm'`, `-HH'mm`, ...) to ISO 8601
+fn parse_pdf_date_offset(zone: &str) -> Option<String> {
+    let zone = zone.trim();
+    let Some(sign) = zone.chars().next() else {
+        return Some(String::new());
+    };
+
+    // `HH'mm'`, `HH'mm` and `HHmm` all occur in the wild
+    let digits: String = zone[sign.len_utf8()..].chars().filter(|c| *c != '\'').collect();
+    if !digits.bytes().all(|b| b.is_ascii_digit()) || digits.len() > 4 || !digits.len().is_multiple_of(2) {
+        return None;
+    }
+    let hours: u32 = digits.get(..2).map_or(Some(0), |h| h.parse().ok())?;
+    let minutes: u32 = digits.get(2..4).map_or(Some(0), |m| m.parse().ok())?;
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+
+    match sign {
+        // Some producers write `Z00'00'`
+        'Z' => Some("Z".to_string()),
+        '+' | '-' => Some(format!("{}{:02}:{:02}", sign, hours, minutes)),
+        _ => None,
+    }
+}
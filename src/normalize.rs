@@ -0,0 +1,84 @@
+//! Clean-up of extracted page text
+//!
+//! The steps of [`Normalization`] run at three points. Ligature expansion and the removal
+//! of control and private-use characters work glyph by glyph before words are formed, so a
+//! dropped glyph leaves no stray space behind. Dehyphenation needs line geometry and
+//! happens while lines are joined; see the `reading_order` module. Unicode normalization,
+//! which composes characters drawn as separate glyphs, and whitespace collapsing run over
+//! the finished text.
+
+use crate::content::PositionedGlyph;
+use crate::pdf_reader::{Normalization, UnicodeForm};
+use unicode_normalization::UnicodeNormalization;
+
+/// Latin presentation-form ligatures (U+FB00 to U+FB06) and the letters they stand for
+const LIGATURES: [(char, &str); 7] = [
+    ('\u{FB00}', "ff"),
+    ('\u{FB01}', "fi"),
+    ('\u{FB02}', "fl"),
+    ('\u{FB03}', "ffi"),
+    ('\u{FB04}', "ffl"),
+    ('\u{FB05}', "st"),
+    ('\u{FB06}', "st"),
+];
+
+/// Expand ligatures and drop invisible characters in the text of each glyph, as enabled
+pub(crate) fn normalize_glyphs(glyphs: &mut [PositionedGlyph], steps: &Normalization) {
+    for glyph in glyphs {
+        if steps.ligatures && glyph.text.chars().any(|c| ('\u{FB00}'..='\u{FB06}').contains(&c)) {
+            glyph.text = glyph.text.chars().fold(String::new(), |mut expanded, c| {
+                match LIGATURES.iter().find(|(ligature, _)| *ligature == c) {
+                    Some((_, letters)) => expanded.push_str(letters),
+                    None => expanded.push(c),
+                }
+                expanded
+            });
+        }
+        if steps.strip_control {
+            glyph.text.retain(|c| !is_invisible(c));
+        }
+    }
+}
+
+/// Apply the enabled Unicode normalization and whitespace collapsing to the text of a page
+pub(crate) fn normalize_text(text: String, steps: &Normalization) -> String {
+    let mut text = match steps.unicode_form {
+        UnicodeForm::None => text,
+        UnicodeForm::Nfc => text.nfc().collect(),
+        UnicodeForm::Nfkc => text.nfkc().collect(),
+    };
+    if steps.collapse_whitespace {
+        text = collapse_whitespace(&text);
+    }
+    text
+}
+
+/// Whether a character carries no visible text: control characters other than line breaks
+/// and tabs, soft hyphens, zero-width spaces, byte order marks, and private-use code points
+/// (which symbol and subset fonts map their glyphs to)
+fn is_invisible(c: char) -> bool {
+    (c.is_control() && c != '\n' && c != '\t')
+        || matches!(c, '\u{00AD}' | '\u{200B}' | '\u{FEFF}')
+        || ('\u{E000}'..='\u{F8FF}').contains(&c)
+        || c >= '\u{F0000}'
+}
+
+/// Collapse runs of spaces and tabs to one space, trim every line, and keep at most one
+/// blank line between paragraphs
+fn collapse_whitespace(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut blank_lines = 0;
+    for line in text.lines() {
+        let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+        if line.is_empty() {
+            blank_lines += 1;
+            continue;
+        }
+        if !collapsed.is_empty() {
+            collapsed.push_str(if blank_lines > 0 { "\n\n" } else { "\n" });
+        }
+        blank_lines = 0;
+        collapsed.push_str(&line);
+    }
+    collapsed
+}
@@ -0,0 +1,282 @@
+//! Document outline (bookmarks) and destination resolution
+
+use crate::error::PdfError;
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Upper bound on outline depth, guarding against malformed trees
+const MAX_OUTLINE_DEPTH: usize = 64;
+
+/// An entry of the document outline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutlineItem {
+    pub title: String,
+    /// Nesting level, starting at 1 for top-level entries
+    pub level: usize,
+    /// Target page (1-indexed), if the entry's destination could be resolved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<OutlineItem>,
+}
+
+/// Resolves destinations (explicit, named and `GoTo` actions) to page numbers
+pub(crate) struct DestinationResolver<'a> {
+    doc: &'a Document,
+    page_numbers: HashMap<ObjectId, u32>,
+    named: HashMap<Vec<u8>, &'a Object>,
+}
+
+impl<'a> DestinationResolver<'a> {
+    pub(crate) fn new(doc: &'a Document) -> Self {
+        let page_numbers = doc.get_pages().into_iter().map(|(number, id)| (id, number)).collect();
+        Self {
+            doc,
+            page_numbers,
+            named: named_destinations(doc),
+        }
+    }
+
+    /// Page (1-indexed) targeted by an item's `/Dest` entry or `GoTo` action
+    pub(crate) fn resolve_item(&self, dict: &Dictionary) -> Option<u32> {
+        if let Ok(dest) = dict.get(b"Dest") {
+            return self.resolve(dest);
+        }
+
+        let (_, action) = self.doc.dereference(dict.get(b"A").ok()?).ok()?;
+        let action = action.as_dict().ok()?;
+        if action.get(b"S").and_then(Object::as_name).ok()? != b"GoTo" {
+            return None;
+        }
+        self.resolve(action.get(b"D").ok()?)
+    }
+
+    /// Page (1-indexed) targeted by a destination: an array, a name or a string
+    pub(crate) fn resolve(&self, dest: &Object) -> Option<u32> {
+        let (_, dest) = self.doc.dereference(dest).ok()?;
+        match dest {
+            Object::Name(name) | Object::String(name, _) => self.resolve_explicit(self.named.get(name.as_slice())?),
+            _ => self.resolve_explicit(dest),
+        }
+    }
+
+    /// Page targeted by an explicit destination array, or a dictionary wrapping one in `/D`
+    fn resolve_explicit(&self, dest: &Object) -> Option<u32> {
+        let (_, dest) = self.doc.dereference(dest).ok()?;
+        let dest = match dest {
+            Object::Dictionary(dict) => self.doc.dereference(dict.get(b"D").ok()?).ok()?.1,
+            _ => dest,
+        };
+        match dest.as_array().ok()?.first()? {
+            Object::Reference(id) => self.page_numbers.get(id).copied(),
+            // Page index as used for remote destinations (0-indexed)
+            Object::Integer(index) => u32::try_from(*index).ok().map(|i| i + 1),
+            _ => None,
+        }
+    }
+}
+
+/// Read the document outline as a tree
+pub(crate) fn read_outline(doc: &Document) -> Vec<OutlineItem> {
+    let Some(first) = doc
+        .catalog()
+        .ok()
+        .and_then(|catalog| catalog.get(b"Outlines").ok())
+        .and_then(|outlines| doc.dereference(outlines).ok())
+        .and_then(|(_, outlines)| outlines.as_dict().ok())
+        .and_then(|outlines| outlines.get(b"First").ok())
+    else {
+        return Vec::new();
+    };
+
+    let resolver = DestinationResolver::new(doc);
+    let mut visited = HashSet::new();
+    read_siblings(doc, &resolver, first, 1, &mut visited)
+}
+
+/// Follow a `/First` → `/Next` chain of outline items
+fn read_siblings(
+    doc: &Document,
+    resolver: &DestinationResolver,
+    first: &Object,
+    level: usize,
+    visited: &mut HashSet<ObjectId>,
+) -> Vec<OutlineItem> {
+    let mut items = Vec::new();
+    let mut current = first.as_reference().ok();
+
+    while let Some(id) = current {
+        // Malformed files can link items in a cycle
+        if level > MAX_OUTLINE_DEPTH || !visited.insert(id) {
+            break;
+        }
+        let Ok(dict) = doc.get_dictionary(id) else {
+            break;
+        };
+
+        let title = dict
+            .get(b"Title")
+            .ok()
+            .and_then(|title| doc.dereference(title).ok())
+            .and_then(|(_, title)| lopdf::decode_text_string(title).ok())
+            .unwrap_or_default();
+
+        let children = match dict.get(b"First") {
+            Ok(child) => read_siblings(doc, resolver, child, level + 1, visited),
+            Err(_) => Vec::new(),
+        };
+
+        items.push(OutlineItem {
+            title: title.trim().to_string(),
+            level,
+            page: resolver.resolve_item(dict),
+            children,
+        });
+
+        current = dict.get(b"Next").and_then(Object::as_reference).ok();
+    }
+
+    items
+}
+
+/// Collect named destinations from the catalog's `/Dests` dictionary and `/Names` tree
+fn named_destinations(doc: &Document) -> HashMap<Vec<u8>, &Object> {
+    let mut named = HashMap::new();
+    let Ok(catalog) = doc.catalog() else {
+        return named;
+    };
+
+    // PDF 1.1 style: a plain dictionary mapping names to destinations
+    if let Some(dests) = catalog
+        .get(b"Dests")
+        .ok()
+        .and_then(|dests| doc.dereference(dests).ok())
+        .and_then(|(_, dests)| dests.as_dict().ok())
+    {
+        for (name, dest) in dests.iter() {
+            named.insert(name.clone(), dest);
+        }
+    }
+
+    // PDF 1.2+: a name tree under /Names /Dests
+    if let Some(tree) = catalog
+        .get(b"Names")
+        .ok()
+        .and_then(|names| doc.dereference(names).ok())
+        .and_then(|(_, names)| names.as_dict().ok())
+        .and_then(|names| names.get(b"Dests").ok())
+    {
+        let mut visited = HashSet::new();
+        collect_name_tree(doc, tree, &mut named, &mut visited);
+    }
+
+    named
+}
+
+/// Add every leaf entry of a name tree to `named`
+fn collect_name_tree<'a>(
+    doc: &'a Document,
+    node: &'a Object,
+    named: &mut HashMap<Vec<u8>, &'a Object>,
+    visited: &mut HashSet<ObjectId>,
+) {
+    if let Ok(id) = node.as_reference()
+        && !visited.insert(id)
+    {
+        return;
+    }
+    let Some(node) = doc.dereference(node).ok().and_then(|(_, node)| node.as_dict().ok()) else {
+        return;
+    };
+
+    if let Ok(names) = node.get(b"Names").and_then(Object::as_array) {
+        for pair in names.chunks(2) {
+            if let [Object::String(name, _), dest] = pair {
+                named.insert(name.clone(), dest);
+            }
+        }
+    }
+
+    if let Ok(kids) = node.get(b"Kids").and_then(Object::as_array) {
+        for kid in kids {
+            collect_name_tree(doc, kid, named, visited);
+        }
+    }
+}
+
+/// An outline entry with the titles of its ancestors, in document order
+struct FlatEntry<'a> {
+    path: Vec<&'a str>,
+    level: usize,
+    /// The entry's page, or the first page among its descendants
+    page: Option<u32>,
+    /// Number of descendants, which directly follow the entry when flattened
+    descendants: usize,
+}
+
+/// Find the page span (1-indexed, inclusive) of the section named by `query`
+///
+/// `query` is a title or a path of titles separated by `>` (`"Chapter 3 > Installation"`);
+/// a path only has to match the end of an entry's ancestry. Exact matches win over
+/// case-insensitive ones, and earlier entries over later ones. The section runs from the
+/// entry's page up to the page before the next entry at the same or a higher level, or to
+/// the end of the document.
+pub(crate) fn find_section(items: &[OutlineItem], query: &str, page_count: u32) -> Result<(u32, u32), PdfError> {
+    let segments: Vec<&str> = query.split('>').map(str::trim).collect();
+    if segments.iter().any(|segment| segment.is_empty()) {
+        return Err(PdfError::SectionNotFound(query.to_string()));
+    }
+
+    let mut entries = Vec::new();
+    flatten(items, &mut Vec::new(), &mut entries);
+
+    let matches = |entry: &FlatEntry, same: fn(&str, &str) -> bool| {
+        entry.path.len() >= segments.len()
+            && entry.path[entry.path.len() - segments.len()..]
+                .iter()
+                .zip(&segments)
+                .all(|(title, segment)| same(title, segment))
+    };
+    let index = entries
+        .iter()
+        .position(|entry| matches(entry, |a, b| a == b))
+        .or_else(|| entries.iter().position(|entry| matches(entry, |a, b| a.to_lowercase() == b.to_lowercase())))
+        .ok_or_else(|| PdfError::SectionNotFound(query.to_string()))?;
+
+    let entry = &entries[index];
+    let start = entry
+        .page
+        .filter(|page| (1..=page_count).contains(page))
+        .ok_or_else(|| PdfError::SectionNotFound(query.to_string()))?;
+
+    let end = entries[index + 1 + entry.descendants..]
+        .iter()
+        .filter(|next| next.level <= entry.level)
+        .find_map(|next| next.page)
+        .map_or(page_count, |next| if next > start { (next - 1).min(page_count) } else { start });
+
+    Ok((start, end))
+}
+
+/// Append `items` and their descendants to `entries` in document order
+fn flatten<'a>(items: &'a [OutlineItem], path: &mut Vec<&'a str>, entries: &mut Vec<FlatEntry<'a>>) {
+    for item in items {
+        path.push(&item.title);
+        let index = entries.len();
+        entries.push(FlatEntry {
+            path: path.clone(),
+            level: item.level,
+            page: item.page,
+            descendants: 0,
+        });
+        flatten(&item.children, path, entries);
+        path.pop();
+
+        let descendants = entries.len() - index - 1;
+        let first_child_page = entries[index + 1..].iter().find_map(|entry| entry.page);
+        let entry = &mut entries[index];
+        entry.descendants = descendants;
+        entry.page = entry.page.or(first_child_page);
+    }
+}
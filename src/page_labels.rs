@@ -0,0 +1,224 @@
+//! Logical page labels from the catalog's `/PageLabels` number tree
+
+use crate::error::PdfError;
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+/// A page given either by physical number or by its printed label
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum PageRef {
+    /// Physical page number (1-indexed)
+    Number(u32),
+    /// Page label as printed, e.g. "iv" or "A-12"
+    Label(String),
+}
+
+impl PageRef {
+    /// Resolve to a physical page number (1-indexed)
+    ///
+    /// A plain number is always a physical page number, as in page selectors, even when
+    /// some page is labelled with it. Other labels are matched exactly first, then
+    /// case-insensitively.
+    pub(crate) fn resolve(&self, labels: Option<&[String]>, page_count: usize) -> Result<u32, PdfError> {
+        let page = match self {
+            PageRef::Number(page) => *page,
+            PageRef::Label(label) => {
+                let label = label.trim();
+                match (label.parse::<u32>(), labels.and_then(|labels| find_label(labels, label))) {
+                    (Ok(page), _) => page,
+                    (Err(_), Some(page)) => page,
+                    (Err(_), None) => return Err(PdfError::PageLabelNotFound(label.to_string())),
+                }
+            }
+        };
+
+        if page < 1 || page as usize > page_count {
+            return Err(PdfError::PageNotFound(page, page_count));
+        }
+        Ok(page)
+    }
+}
+
+impl From<u32> for PageRef {
+    fn from(page: u32) -> Self {
+        PageRef::Number(page)
+    }
+}
+
+impl From<&str> for PageRef {
+    fn from(label: &str) -> Self {
+        PageRef::Label(label.to_string())
+    }
+}
+
+impl From<String> for PageRef {
+    fn from(label: String) -> Self {
+        PageRef::Label(label)
+    }
+}
+
+/// Physical page (1-indexed) carrying `label`, matched exactly first, then case-insensitively
+pub(crate) fn find_label(labels: &[String], label: &str) -> Option<u32> {
+    let index = labels
+        .iter()
+        .position(|candidate| candidate == label)
+        .or_else(|| labels.iter().position(|candidate| candidate.to_lowercase() == label.to_lowercase()))?;
+    Some(index as u32 + 1)
+}
+
+/// Largest first number (`/St`) of a label range; larger values are clamped
+const MAX_START: i64 = i32::MAX as i64;
+
+/// Largest number written as a roman numeral
+const MAX_ROMAN: i64 = 9999;
+
+/// Largest number written with letters (ten repetitions of `Z`)
+const MAX_ALPHABETIC: i64 = 260;
+
+/// Numbering style of a page label range (`/S`)
+#[derive(Clone, Copy)]
+enum NumberingStyle {
+    Decimal,
+    UpperRoman,
+    LowerRoman,
+    UpperAlpha,
+    LowerAlpha,
+    /// No numeric part; the label is the prefix alone
+    None,
+}
+
+/// A label range starting at some page index
+struct LabelRange {
+    style: NumberingStyle,
+    prefix: String,
+    start: i64,
+}
+
+/// Read the label of every page, in page order
+///
+/// Returns `None` when the document does not define page labels. Pages before the first
+/// range (which a well-formed tree never has) are labelled with their physical number.
+pub(crate) fn read_page_labels(doc: &Document, page_count: usize) -> Option<Vec<String>> {
+    let tree = doc.catalog().ok()?.get(b"PageLabels").ok()?;
+
+    let mut ranges = BTreeMap::new();
+    collect_number_tree(doc, tree, &mut ranges, &mut HashSet::new());
+    if ranges.is_empty() {
+        return None;
+    }
+
+    let labels = (0..page_count)
+        .map(|index| match ranges.range(..=index as i64).next_back() {
+            Some((first_index, range)) => format_label(range, range.start.saturating_add(index as i64 - first_index)),
+            None => (index + 1).to_string(),
+        })
+        .collect();
+    Some(labels)
+}
+
+/// Add every entry of a page label number tree to `ranges`
+fn collect_number_tree(
+    doc: &Document,
+    node: &Object,
+    ranges: &mut BTreeMap<i64, LabelRange>,
+    visited: &mut HashSet<ObjectId>,
+) {
+    if let Ok(id) = node.as_reference()
+        && !visited.insert(id)
+    {
+        return;
+    }
+    let Some(node) = doc.dereference(node).ok().and_then(|(_, node)| node.as_dict().ok()) else {
+        return;
+    };
+
+    if let Ok(nums) = node.get(b"Nums").and_then(Object::as_array) {
+        for pair in nums.chunks(2) {
+            if let [Object::Integer(index), label] = pair
+                && let Some(label) = doc.dereference(label).ok().and_then(|(_, label)| label.as_dict().ok())
+            {
+                ranges.insert(*index, label_range(label));
+            }
+        }
+    }
+
+    if let Ok(kids) = node.get(b"Kids").and_then(Object::as_array) {
+        for kid in kids {
+            collect_number_tree(doc, kid, ranges, visited);
+        }
+    }
+}
+
+/// Decode a page label dictionary
+fn label_range(dict: &Dictionary) -> LabelRange {
+    let style = match dict.get(b"S").and_then(Object::as_name) {
+        Ok(b"D") => NumberingStyle::Decimal,
+        Ok(b"R") => NumberingStyle::UpperRoman,
+        Ok(b"r") => NumberingStyle::LowerRoman,
+        Ok(b"A") => NumberingStyle::UpperAlpha,
+        Ok(b"a") => NumberingStyle::LowerAlpha,
+        _ => NumberingStyle::None,
+    };
+    let prefix = dict
+        .get(b"P")
+        .ok()
+        .and_then(|prefix| lopdf::decode_text_string(prefix).ok())
+        .unwrap_or_default();
+    let start = dict.get(b"St").and_then(Object::as_i64).unwrap_or(1).clamp(1, MAX_START);
+
+    LabelRange { style, prefix, start }
+}
+
+/// Format the label of the page numbered `number` within `range`
+fn format_label(range: &LabelRange, number: i64) -> String {
+    let numeral = match range.style {
+        NumberingStyle::Decimal => number.to_string(),
+        // Numbers too large to write as letters fall back to decimal
+        NumberingStyle::UpperRoman | NumberingStyle::LowerRoman if number > MAX_ROMAN => number.to_string(),
+        NumberingStyle::UpperAlpha | NumberingStyle::LowerAlpha if number > MAX_ALPHABETIC => number.to_string(),
+        NumberingStyle::UpperRoman => roman(number),
+        NumberingStyle::LowerRoman => roman(number).to_lowercase(),
+        NumberingStyle::UpperAlpha => alphabetic(number),
+        NumberingStyle::LowerAlpha => alphabetic(number).to_lowercase(),
+        NumberingStyle::None => String::new(),
+    };
+    format!("{}{}", range.prefix, numeral)
+}
+
+/// Upper-case roman numeral; numbers past 3999 repeat `M`
+fn roman(mut number: i64) -> String {
+    const NUMERALS: [(i64, &str); 13] = [
+        (1000, "M"),
+        (900, "CM"),
+        (500, "D"),
+        (400, "CD"),
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+
+    let mut numeral = String::new();
+    for (value, symbol) in NUMERALS {
+        while number >= value {
+            numeral.push_str(symbol);
+            number -= value;
+        }
+    }
+    numeral
+}
+
+/// Upper-case letter label: A to Z, then AA to ZZ, then AAA and so on
+fn alphabetic(number: i64) -> String {
+    let letter = (b'A' + ((number - 1) % 26) as u8) as char;
+    let repeat = ((number - 1) / 26 + 1) as usize;
+    letter.to_string().repeat(repeat)
+}
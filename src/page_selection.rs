@@ -0,0 +1,130 @@
+//! Page selector syntax shared by the page-oriented tools
+//!
+//! A selector is a comma-separated list of entries, each one of:
+//!
+//! - a physical page number or a page label: `7`, `iv`, `A-12`
+//! - a range: `1-3`, `ii-iv`, or an open range `10-` (to the last page)
+//! - `-N`: the last N pages
+//! - `last`, `odd` or `even`
+//!
+//! Pages are returned in the order given, without duplicates.
+
+use crate::error::PdfError;
+use crate::page_labels::{self, PageRef};
+
+/// Which pages of a document to extract
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PageSelection {
+    /// Every page, in order
+    All,
+    /// A single page
+    Page(PageRef),
+    /// An inclusive range of pages
+    Range(PageRef, PageRef),
+    /// A page selector such as `"1-3,7,10-"`
+    Pages(String),
+    /// The pages of an outline entry, by title or `>`-separated path
+    Section(String),
+}
+
+/// Resolve a page selector to physical page numbers (1-indexed)
+pub(crate) fn select_pages(spec: &str, page_count: usize, labels: Option<&[String]>) -> Result<Vec<u32>, PdfError> {
+    if spec.trim().is_empty() {
+        return Err(invalid(spec, "the selection is empty"));
+    }
+
+    let mut pages = Vec::new();
+    for (position, token) in spec.split(',').enumerate() {
+        let token = token.trim();
+        if token.is_empty() {
+            return Err(invalid(spec, &format!("entry {} is empty", position + 1)));
+        }
+        for page in select_token(token, page_count, labels)? {
+            if !pages.contains(&page) {
+                pages.push(page);
+            }
+        }
+    }
+    Ok(pages)
+}
+
+/// Resolve one comma-separated entry of a selector
+fn select_token(token: &str, page_count: usize, labels: Option<&[String]>) -> Result<Vec<u32>, PdfError> {
+    let last = page_count as u32;
+
+    match token.to_ascii_lowercase().as_str() {
+        "odd" => return Ok((1..=last).step_by(2).collect()),
+        "even" => return Ok((2..=last).step_by(2).collect()),
+        _ => {}
+    }
+
+    // A whole-token match wins, so labels such as "A-12" are not read as ranges
+    let single = resolve_bound(token, page_count, labels);
+    if let Ok(page) = single {
+        return Ok(vec![page]);
+    }
+
+    if let Some(count) = token.strip_prefix('-') {
+        let count: u32 = count
+            .trim()
+            .parse()
+            .map_err(|_| invalid(token, "expected the number of trailing pages after '-'"))?;
+        if count == 0 {
+            return Err(invalid(token, "the number of trailing pages must be at least 1"));
+        }
+        return Ok((last.saturating_sub(count) + 1..=last).collect());
+    }
+
+    if let Some(start) = token.strip_suffix('-') {
+        let start = resolve_bound(start.trim(), page_count, labels).map_err(|reason| invalid(token, &reason))?;
+        return Ok((start..=last).collect());
+    }
+
+    // Try every '-' as the separator; labels may contain dashes themselves
+    let mut first_error = None;
+    for (index, _) in token.match_indices('-') {
+        let start = resolve_bound(token[..index].trim(), page_count, labels);
+        let end = resolve_bound(token[index + 1..].trim(), page_count, labels);
+        match (start, end) {
+            (Ok(start), Ok(end)) if start <= end => return Ok((start..=end).collect()),
+            (Ok(start), Ok(end)) => {
+                return Err(invalid(token, &format!("start page {} is after end page {}", start, end)));
+            }
+            (Err(reason), _) | (_, Err(reason)) => {
+                first_error.get_or_insert(reason);
+            }
+        }
+    }
+
+    let reason = first_error.unwrap_or_else(|| single.unwrap_err());
+    Err(invalid(token, &reason))
+}
+
+/// Resolve a page number, page label or `last`, explaining why it does not resolve
+///
+/// Numbers are always physical page numbers; labels are only looked up for other text.
+fn resolve_bound(bound: &str, page_count: usize, labels: Option<&[String]>) -> Result<u32, String> {
+    if page_count == 0 {
+        return Err("the document has no pages".to_string());
+    }
+    if bound.eq_ignore_ascii_case("last") {
+        return Ok(page_count as u32);
+    }
+
+    match bound.parse::<u32>() {
+        Ok(0) => Err("pages are numbered from 1".to_string()),
+        Ok(page) if page as usize > page_count => {
+            Err(format!("page {} does not exist (document has {} pages)", page, page_count))
+        }
+        Ok(page) => Ok(page),
+        Err(_) if bound.is_empty() => Err("missing page number".to_string()),
+        Err(_) => labels
+            .and_then(|labels| page_labels::find_label(labels, bound))
+            .ok_or_else(|| format!("'{}' is not a page number, page label or keyword", bound)),
+    }
+}
+
+/// Error for a selector entry, with the reason it was rejected
+fn invalid(token: &str, reason: &str) -> PdfError {
+    PdfError::InvalidPageSelection(token.to_string(), reason.to_string())
+}
@@ -1,14 +1,43 @@
 //! PDF reading and parsing module
 
+use crate::content::{self, PositionedGlyph, Ruling};
+use crate::encryption::{self, EncryptionInfo};
 use crate::error::PdfError;
-use lopdf::Document;
+use crate::furniture;
+use crate::html;
+use crate::layout::{self, PageLayout, RecoveredFont};
+use crate::links::{self, Link};
+use crate::markdown;
+use crate::metadata;
+use crate::normalize;
+use crate::outline::{self, DestinationResolver, OutlineItem};
+use crate::page_labels::{self, PageRef};
+use crate::page_selection::{self, PageSelection};
+use crate::structure;
+use crate::tables::{self, Table};
+use crate::xmp::{self, XmpMetadata};
+use lopdf::{Document, Object};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::Path;
 
 /// PDF document metadata and information
+///
+/// The top-level metadata fields combine the Info dictionary and the XMP stream; see the
+/// `metadata` module for which source wins when they disagree.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PdfInfo {
-    pub page_count: usize,
+    /// Number of pages; `None` when the document is locked and its page tree is packed in
+    /// encrypted object streams
+    pub page_count: Option<usize>,
+    /// PDF version from the file header, e.g. "1.7"
+    pub pdf_version: String,
+    /// File size in bytes
+    pub file_size: u64,
+    /// Logical label of every page in page order, when the document defines page labels
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_labels: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -17,6 +46,196 @@ pub struct PdfInfo {
     pub subject: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub creator: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keywords: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub producer: Option<String>,
+    /// Creation date in ISO 8601 (kept as written if it is not a valid PDF date)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub creation_date: Option<String>,
+    /// Modification date in ISO 8601 (kept as written if it is not a valid PDF date)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modification_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trapped: Option<String>,
+    /// Non-standard Info dictionary entries
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub custom: BTreeMap<String, String>,
+    /// Metadata from the XMP stream as written, when the document has one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub xmp: Option<XmpMetadata>,
+    pub encrypted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encryption: Option<EncryptionInfo>,
+    /// The document could not be decrypted because no password was given; only the page
+    /// count, version, encryption settings and tagging are reported
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub locked: bool,
+    /// Whether the document is tagged: a structure tree gives its logical reading order
+    pub tagged: bool,
+}
+
+/// Glyphs and rulings of one page, or why its content could not be read
+type PageContent = Result<(Vec<PositionedGlyph>, Vec<Ruling>), lopdf::Error>;
+
+/// Outcome of extracting the text of one page
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PageStatus {
+    /// Text was extracted
+    Ok,
+    /// The page was read but holds no text, e.g. a scanned image
+    Empty,
+    /// Extraction failed; the reason is in `error`
+    Error,
+}
+
+/// Text of a single page with its extraction status
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageText {
+    /// Physical page number (1-indexed)
+    pub page: u32,
+    /// Page label, when the document defines page labels
+    pub label: Option<String>,
+    pub text: String,
+    pub status: PageStatus,
+    pub error: Option<PageError>,
+    /// Fonts whose text was recovered heuristically and may be inaccurate
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recovered_fonts: Vec<RecoveredFont>,
+    /// Running headers, footers and page numbers removed from `text`, top to bottom
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub furniture: Vec<String>,
+}
+
+/// Why the text of a page could not be extracted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageError {
+    /// Machine-readable error code, as in [`PdfError::code`]
+    pub code: String,
+    pub message: String,
+}
+
+impl From<&PdfError> for PageError {
+    fn from(error: &PdfError) -> Self {
+        PageError { code: error.code().to_string(), message: error.to_string() }
+    }
+}
+
+/// How page text is arranged
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TextLayout {
+    /// Text in reading order, one line of text per line on the page; multi-column pages
+    /// are read one column at a time
+    #[default]
+    Reading,
+    /// A fixed-width character grid that keeps columns, indentation and table alignment,
+    /// like `pdftotext -layout`
+    Preserve,
+    /// The logical reading order of a tagged document's structure tree, one block per line
+    /// labeled with its role (`[H1]`, `[P]`, `[LI]`, ...); pages of untagged documents, and
+    /// pages the tree has no content on, are read as with `reading`
+    Tagged,
+}
+
+/// Order of the characters in lines holding right-to-left text (Hebrew, Arabic, ...)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum BidiOrder {
+    /// The order the text is read and typed in, from the Unicode Bidirectional Algorithm;
+    /// numbers and Latin words inside right-to-left runs keep their own order
+    #[default]
+    Logical,
+    /// The order the glyphs appear on the page, left to right
+    Visual,
+}
+
+/// Unicode normalization form applied to extracted text
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum UnicodeForm {
+    /// Leave the text as decoded
+    #[default]
+    None,
+    /// Canonical composition: accented letters as single code points where possible
+    Nfc,
+    /// Compatibility composition: also folds full-width forms, ligatures, superscripts and
+    /// the like into their plain equivalents
+    Nfkc,
+}
+
+/// Clean-up steps applied to extracted text, each of which can be turned on or off
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct Normalization {
+    /// Expand ligature code points such as "ﬁ" and "ﬂ" into their letters (default on)
+    pub ligatures: bool,
+    /// Join words hyphenated across line breaks ("docu-" / "ment") when the next line
+    /// continues the same column and starts in lowercase; reading layout only (default off)
+    pub dehyphenate: bool,
+    /// Unicode normalization form (default none)
+    pub unicode_form: UnicodeForm,
+    /// Remove control characters, soft hyphens, zero-width spaces and private-use
+    /// characters (default on)
+    pub strip_control: bool,
+    /// Collapse runs of spaces, trim lines and keep at most one blank line in a row; this
+    /// undoes the alignment of the preserve layout (default off)
+    pub collapse_whitespace: bool,
+}
+
+impl Default for Normalization {
+    fn default() -> Self {
+        Normalization {
+            ligatures: true,
+            dehyphenate: false,
+            unicode_form: UnicodeForm::None,
+            strip_control: true,
+            collapse_whitespace: false,
+        }
+    }
+}
+
+/// Options for text extraction
+#[derive(Debug, Clone)]
+pub struct TextOptions {
+    pub layout: TextLayout,
+    /// Smallest gap between two glyphs that separates words, as a fraction of the width of
+    /// a space in the font (a quarter of the font size when the font does not define one)
+    pub word_gap: f64,
+    pub bidi: BidiOrder,
+    pub normalization: Normalization,
+    /// Remove running headers, footers and page numbers: rows of text that recur at the same
+    /// height near the top or bottom edge of the compared pages
+    pub strip_furniture: bool,
+}
+
+impl TextOptions {
+    /// Word gap used unless a caller asks for another one
+    pub const DEFAULT_WORD_GAP: f64 = 0.5;
+
+    /// Reject option values that cannot produce sensible text
+    fn validate(&self) -> Result<(), PdfError> {
+        if !self.word_gap.is_finite() || self.word_gap <= 0.0 {
+            return Err(PdfError::InvalidOption(
+                "word_gap".to_string(),
+                format!("must be a positive number, got {}", self.word_gap),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for TextOptions {
+    fn default() -> Self {
+        TextOptions {
+            layout: TextLayout::default(),
+            word_gap: Self::DEFAULT_WORD_GAP,
+            bidi: BidiOrder::default(),
+            normalization: Normalization::default(),
+            strip_furniture: false,
+        }
+    }
 }
 
 /// PDF Reader for extracting text and metadata from PDF files
@@ -24,198 +243,614 @@ pub struct PdfReader;
 
 impl PdfReader {
     /// Load a PDF document from a file path
-    fn load_document(file_path: &str) -> Result<Document, PdfError> {
+    ///
+    /// Encrypted documents are decrypted with `password`, or with the empty user
+    /// password when none is given (documents with only an owner password open this way).
+    fn load_document(file_path: &str, password: Option<&str>) -> Result<Document, PdfError> {
         let path = Path::new(file_path);
         
         if !path.exists() {
             return Err(PdfError::FileNotFound(file_path.to_string()));
         }
         
-        let doc = Document::load(file_path).map_err(|e| {
-            let err_str = e.to_string();
-            if err_str.contains("encrypted") || err_str.contains("password") {
-                PdfError::EncryptedDocument
-            } else if err_str.contains("invalid") || err_str.contains("Invalid") {
-                PdfError::InvalidFormat(err_str)
-            } else {
-                PdfError::ParseError(err_str)
-            }
-        })?;
+        let bytes = std::fs::read(path)?;
         
-        // Check if document is encrypted and we couldn't decrypt it
-        if doc.is_encrypted() && doc.encryption_state.is_none() {
-            return Err(PdfError::EncryptedDocument);
-        }
+        let doc = Document::load_mem(&bytes).map_err(|e| Self::classify_load_error(e, &bytes))?;
         
+        // lopdf only decrypts on load when the empty user password is accepted
+        let doc = if doc.is_encrypted() && doc.encryption_state.is_none() {
+            encryption::load_encrypted_document(&bytes, password)?
+        } else {
+            doc
+        };
+        
+        Self::check_page_tree(&doc)?;
         Ok(doc)
     }
 
-    /// Extract all text from a PDF file
-    /// Extracts text page-by-page, skipping pages that fail to parse.
-    /// Returns the concatenated text from all successfully parsed pages.
-    pub fn extract_text(file_path: &str) -> Result<String, PdfError> {
-        let doc = Self::load_document(file_path)?;
+    /// Convert a load failure into a `PdfError`
+    ///
+    /// Damage to the end of a file shows up as all kinds of xref and trailer errors, so a
+    /// file without an `%%EOF` marker near its end is reported as truncated instead.
+    fn classify_load_error(err: lopdf::Error, bytes: &[u8]) -> PdfError {
+        let err = PdfError::from(err);
+        let tail = &bytes[bytes.len().saturating_sub(1024)..];
+        let has_eof_marker = tail.windows(5).any(|window| window == b"%%EOF");
         
-        let pages = doc.get_pages();
-        if pages.is_empty() {
-            return Ok(String::new());
+        match err {
+            PdfError::CorruptXref(reason) | PdfError::ParseError(reason) if !has_eof_marker => {
+                PdfError::TruncatedFile(reason)
+            }
+            err => err,
         }
+    }
+
+    /// Make sure the catalog leads to a readable page tree
+    fn check_page_tree(doc: &Document) -> Result<(), PdfError> {
+        let catalog = doc
+            .catalog()
+            .map_err(|e| PdfError::PageTree(format!("document catalog is unreadable: {}", e)))?;
+        let root = catalog
+            .get(b"Pages")
+            .and_then(Object::as_reference)
+            .map_err(|_| PdfError::PageTree("catalog has no /Pages reference".to_string()))?;
+        let root = doc.get_dictionary(root).map_err(|e| match e {
+            lopdf::Error::ObjectNotFound((number, generation)) => PdfError::MissingObject(number, generation),
+            e => PdfError::PageTree(format!("page tree root is unreadable: {}", e)),
+        })?;
         
-        let mut all_text = String::new();
-        let mut skipped_pages = Vec::new();
+        let declared = root.get(b"Count").and_then(Object::as_i64).unwrap_or(0);
+        if declared > 0 && doc.page_iter().next().is_none() {
+            return Err(PdfError::PageTree(format!(
+                "page tree declares {} pages but none could be read",
+                declared
+            )));
+        }
+        Ok(())
+    }
+
+    /// Extract the text of the selected pages as one record per page
+    ///
+    /// Pages that fail to extract are reported with an `Error` status instead of failing
+    /// the whole call. Records are in selection order.
+    pub fn extract_page_texts(
+        file_path: &str,
+        selection: &PageSelection,
+        options: &TextOptions,
+        password: Option<&str>,
+    ) -> Result<Vec<PageText>, PdfError> {
+        Self::read_page_texts(file_path, selection, options, password, false)
+    }
+
+    /// Extract the text of the selected pages, failing with [`PdfError::Page`] on the first
+    /// page that cannot be extracted when `strict` is set
+    pub(crate) fn read_page_texts(
+        file_path: &str,
+        selection: &PageSelection,
+        options: &TextOptions,
+        password: Option<&str>,
+        strict: bool,
+    ) -> Result<Vec<PageText>, PdfError> {
+        options.validate()?;
+        let doc = Self::load_document(file_path, password)?;
+        
+        let page_count = doc.get_pages().len();
+        let labels = page_labels::read_page_labels(&doc, page_count);
+        let pages = Self::select_pages(&doc, selection, page_count, labels.as_deref())?;
         
-        // Extract page-by-page to handle problematic pages gracefully
-        for page_num in pages.keys() {
-            match doc.extract_text(&[*page_num]) {
-                Ok(text) => {
-                    if !all_text.is_empty() && !text.is_empty() {
-                        all_text.push('\n');
+        let (extracted, furniture) = Self::page_contents(&doc, &pages, labels.as_deref(), options);
+        let structure = (options.layout == TextLayout::Tagged).then(|| structure::read_structure(&doc)).flatten();
+
+        pages
+            .into_iter()
+            .zip(extracted)
+            .zip(furniture)
+            .map(|((page, content), furniture)| {
+                let label = labels.as_ref().and_then(|labels| labels.get(page as usize - 1)).cloned();
+                match content {
+                    Ok((mut glyphs, _)) => {
+                        let recovered_fonts = layout::recovered_fonts(&glyphs);
+                        let text = match options.layout {
+                            TextLayout::Reading => layout::reading_text(&mut glyphs, options),
+                            TextLayout::Preserve => layout::preserved_text(&mut glyphs, options),
+                            TextLayout::Tagged => structure
+                                .as_ref()
+                                .and_then(|structure| structure.page_text(page, &glyphs, options))
+                                .unwrap_or_else(|| layout::reading_text(&mut glyphs, options)),
+                        };
+                        let text = normalize::normalize_text(text, &options.normalization);
+                        Ok(PageText {
+                            page,
+                            label,
+                            status: if text.trim().is_empty() { PageStatus::Empty } else { PageStatus::Ok },
+                            text,
+                            error: None,
+                            recovered_fonts,
+                            furniture,
+                        })
+                    }
+                    Err(e) => {
+                        let error = PdfError::from(e);
+                        if strict {
+                            return Err(PdfError::Page(page, Box::new(error)));
+                        }
+                        Ok(PageText {
+                            page,
+                            label,
+                            text: String::new(),
+                            status: PageStatus::Error,
+                            error: Some(PageError::from(&error)),
+                            recovered_fonts: Vec::new(),
+                            furniture,
+                        })
                     }
-                    all_text.push_str(&text);
                 }
-                Err(_) => {
-                    skipped_pages.push(*page_num);
+            })
+            .collect()
+    }
+
+    /// Read the glyphs and rulings of the selected pages
+    ///
+    /// Pages are read one by one so that a problematic page does not fail the others. The
+    /// character steps of the normalization are applied to the glyphs and, when asked,
+    /// furniture is removed; the text of the removed rows is returned for each page.
+    fn page_contents(
+        doc: &Document,
+        pages: &[u32],
+        labels: Option<&[String]>,
+        options: &TextOptions,
+    ) -> (Vec<PageContent>, Vec<Vec<String>>) {
+        let page_ids = doc.get_pages();
+        let mut extracted: Vec<PageContent> = pages
+            .iter()
+            .map(|page| {
+                let page_id = page_ids.get(page).ok_or(lopdf::Error::PageNumberNotFound(*page))?;
+                content::page_content(doc, *page_id).map(|(mut glyphs, rulings)| {
+                    normalize::normalize_glyphs(&mut glyphs, &options.normalization);
+                    (glyphs, rulings)
+                })
+            })
+            .collect();
+        let mut furniture = vec![Vec::new(); pages.len()];
+        if options.strip_furniture {
+            furniture = Self::strip_furniture(doc, pages, &mut extracted, labels, options);
+        }
+        (extracted, furniture)
+    }
+
+    /// Remove running headers, footers and page numbers from the glyphs of the selected pages
+    ///
+    /// Pages are compared with each other; a selection of fewer than three pages is compared
+    /// with its neighbouring pages as well. Returns the text of the rows removed from each page.
+    fn strip_furniture(
+        doc: &Document,
+        pages: &[u32],
+        extracted: &mut [PageContent],
+        labels: Option<&[String]>,
+        options: &TextOptions,
+    ) -> Vec<Vec<String>> {
+        let page_ids = doc.get_pages();
+        let number = |page: u32| match labels.and_then(|labels| labels.get(page as usize - 1)) {
+            Some(label) => label.clone(),
+            None => page.to_string(),
+        };
+
+        let mut neighbours: Vec<u32> = Vec::new();
+        if pages.len() < furniture::MIN_PAGES {
+            for &page in pages {
+                let first = page.saturating_sub(furniture::NEIGHBOUR_PAGES).max(1);
+                for neighbour in first..=page + furniture::NEIGHBOUR_PAGES {
+                    let compared = pages.contains(&neighbour) || neighbours.contains(&neighbour);
+                    if page_ids.contains_key(&neighbour) && !compared {
+                        neighbours.push(neighbour);
+                    }
                 }
             }
         }
-        
-        // Add a note about skipped pages if any
-        if !skipped_pages.is_empty() {
-            all_text.push_str(&format!(
-                "\n\n[Note: {} page(s) could not be extracted due to font encoding issues: {:?}]",
-                skipped_pages.len(),
-                skipped_pages
-            ));
+
+        let mut rows: Vec<Vec<furniture::EdgeRow>> = pages
+            .iter()
+            .zip(extracted.iter())
+            .map(|(&page, content)| match content {
+                Ok((glyphs, _)) => furniture::edge_rows(glyphs, &number(page), options.word_gap),
+                Err(_) => Vec::new(),
+            })
+            .collect();
+        for &page in &neighbours {
+            let glyphs = content::page_glyphs(doc, page_ids[&page]).map(|mut glyphs| {
+                normalize::normalize_glyphs(&mut glyphs, &options.normalization);
+                glyphs
+            });
+            let edge_rows = glyphs.map(|glyphs| furniture::edge_rows(&glyphs, &number(page), options.word_gap));
+            rows.push(edge_rows.unwrap_or_default());
         }
-        
-        Ok(all_text)
+
+        furniture::find(&rows, pages.len())
+            .into_iter()
+            .zip(extracted.iter_mut())
+            .map(|(furniture, content)| match content {
+                Ok((glyphs, _)) => furniture::strip(glyphs, &furniture),
+                Err(_) => Vec::new(),
+            })
+            .collect()
     }
 
-    /// Extract text from a specific page (1-indexed)
-    pub fn extract_page_text(file_path: &str, page: u32) -> Result<String, PdfError> {
-        let doc = Self::load_document(file_path)?;
-        
+    /// Extract all text from a PDF file
+    /// Extracts text page-by-page, skipping pages that fail to parse.
+    /// Returns the concatenated text from all successfully parsed pages.
+    pub fn extract_text(file_path: &str, password: Option<&str>) -> Result<String, PdfError> {
+        let records = Self::extract_page_texts(file_path, &PageSelection::All, &TextOptions::default(), password)?;
+        Ok(join_page_texts(&records))
+    }
+
+    /// Extract text from the pages matched by a page selector
+    ///
+    /// `pages` is a comma-separated list of page numbers, page labels, ranges (`1-3`,
+    /// `10-`), trailing pages (`-5`) and the keywords `last`, `odd` and `even`, for
+    /// example `"1-3,7,10-"`. Pages are extracted in the order given.
+    pub fn extract_pages_text(file_path: &str, pages: &str, password: Option<&str>) -> Result<String, PdfError> {
+        let selection = PageSelection::Pages(pages.to_string());
+        let records = Self::extract_page_texts(file_path, &selection, &TextOptions::default(), password)?;
+        Ok(join_page_texts(&records))
+    }
+
+    /// Extract text from a specific page
+    ///
+    /// `page` is a physical page number (1-indexed) or a page label such as "iv".
+    pub fn extract_page_text(file_path: &str, page: impl Into<PageRef>, password: Option<&str>) -> Result<String, PdfError> {
+        let selection = PageSelection::Page(page.into());
+        let records = Self::read_page_texts(file_path, &selection, &TextOptions::default(), password, true)?;
+        Ok(records.into_iter().next().map(|record| record.text).unwrap_or_default())
+    }
+
+    /// Extract text from a range of pages (inclusive)
+    ///
+    /// Both ends are physical page numbers (1-indexed) or page labels such as "iv".
+    pub fn extract_page_range_text(
+        file_path: &str,
+        start_page: impl Into<PageRef>,
+        end_page: impl Into<PageRef>,
+        password: Option<&str>,
+    ) -> Result<String, PdfError> {
+        let selection = PageSelection::Range(start_page.into(), end_page.into());
+        let records = Self::extract_page_texts(file_path, &selection, &TextOptions::default(), password)?;
+        Ok(join_page_texts(&records))
+    }
+
+    /// Extract text from the pages covered by an outline entry
+    ///
+    /// `section` is a bookmark title or a `>`-separated outline path such as
+    /// `"Chapter 3 > Installation"`. The text runs from the entry's page up to the page
+    /// before the next entry at the same or a higher outline level.
+    pub fn extract_section_text(file_path: &str, section: &str, password: Option<&str>) -> Result<String, PdfError> {
+        let selection = PageSelection::Section(section.to_string());
+        let records = Self::extract_page_texts(file_path, &selection, &TextOptions::default(), password)?;
+        Ok(join_page_texts(&records))
+    }
+
+    /// Extract the words and lines of a page with their bounding boxes
+    ///
+    /// `page` is a physical page number (1-indexed) or a page label such as "iv". Boxes are
+    /// in PDF user space (points, origin at the bottom-left); `include_glyphs` adds the box
+    /// of every glyph to each word. Of the text options, `layout` and `normalization` do
+    /// not apply: words keep the text their glyphs decode to.
+    pub fn extract_page_layout(
+        file_path: &str,
+        page: impl Into<PageRef>,
+        include_glyphs: bool,
+        options: &TextOptions,
+        password: Option<&str>,
+    ) -> Result<PageLayout, PdfError> {
+        options.validate()?;
+        let doc = Self::load_document(file_path, password)?;
+
         let pages = doc.get_pages();
-        let page_count = pages.len();
-        
-        if page < 1 || page as usize > page_count {
-            return Err(PdfError::PageNotFound(page, page_count));
-        }
-        
-        let text = doc.extract_text(&[page]).map_err(|e| {
-            PdfError::ParseError(format!("Failed to extract text from page {}: {}", page, e))
-        })?;
-        
-        Ok(text)
+        let labels = page_labels::read_page_labels(&doc, pages.len());
+        let page = page.into().resolve(labels.as_deref(), pages.len())?;
+        let label = labels.as_ref().and_then(|labels| labels.get(page as usize - 1)).cloned();
+
+        layout::page_layout(&doc, pages[&page], page, label, include_glyphs, options)
+            .map_err(|e| PdfError::Page(page, Box::new(PdfError::from(e))))
     }
 
-    /// Extract text from a range of pages (1-indexed, inclusive)
-    pub fn extract_page_range_text(file_path: &str, start_page: u32, end_page: u32) -> Result<String, PdfError> {
-        let doc = Self::load_document(file_path)?;
-        
+    /// Find the tables of a page
+    ///
+    /// `page` is a physical page number (1-indexed) or a page label such as "iv". Tables are
+    /// found from ruling lines where the page draws them and from the alignment of words
+    /// where it does not; each comes as a cell grid with spans and header rows, as CSV and as
+    /// Markdown. Of the text options, `word_gap`, `bidi` and the character steps of
+    /// `normalization` apply.
+    pub fn extract_tables(
+        file_path: &str,
+        page: impl Into<PageRef>,
+        options: &TextOptions,
+        password: Option<&str>,
+    ) -> Result<Vec<Table>, PdfError> {
+        options.validate()?;
+        let doc = Self::load_document(file_path, password)?;
+
         let pages = doc.get_pages();
-        let page_count = pages.len();
-        
-        if start_page < 1 || start_page as usize > page_count {
-            return Err(PdfError::PageNotFound(start_page, page_count));
-        }
-        
-        if end_page < 1 || end_page as usize > page_count {
-            return Err(PdfError::PageNotFound(end_page, page_count));
+        let labels = page_labels::read_page_labels(&doc, pages.len());
+        let page = page.into().resolve(labels.as_deref(), pages.len())?;
+
+        let (mut glyphs, rulings) =
+            content::page_content(&doc, pages[&page]).map_err(|e| PdfError::Page(page, Box::new(PdfError::from(e))))?;
+        normalize::normalize_glyphs(&mut glyphs, &options.normalization);
+        Ok(tables::find_tables(&mut glyphs, &rulings, page, options))
+    }
+
+    /// Convert the selected pages to Markdown
+    ///
+    /// Headings are inferred from font size and weight relative to the body text, bulleted
+    /// and numbered lists from their markers, and paragraphs are joined across line breaks;
+    /// tables found on the pages become pipe tables. With `page_anchors`, each page starts
+    /// with an `<a id="page-N"></a>` anchor. Of the text options, `layout` does not apply.
+    /// Pages that fail to extract are noted at the end, as with `extract_text`.
+    pub fn extract_markdown(
+        file_path: &str,
+        selection: &PageSelection,
+        options: &TextOptions,
+        page_anchors: bool,
+        password: Option<&str>,
+    ) -> Result<String, PdfError> {
+        options.validate()?;
+        let doc = Self::load_document(file_path, password)?;
+
+        let page_count = doc.get_pages().len();
+        let labels = page_labels::read_page_labels(&doc, page_count);
+        let pages = Self::select_pages(&doc, selection, page_count, labels.as_deref())?;
+
+        let (blocks, skipped_pages) = Self::page_blocks(&doc, &pages, labels.as_deref(), options);
+        let mut text = markdown::render(&markdown::elements(&blocks, options), page_anchors);
+        if !skipped_pages.is_empty() {
+            text.push_str(&skipped_pages_note(&skipped_pages));
         }
-        
-        if start_page > end_page {
-            return Err(PdfError::ParseError(format!(
-                "Invalid page range: start_page ({}) must be <= end_page ({})",
-                start_page, end_page
-            )));
+        Ok(text)
+    }
+
+    /// Export the selected pages as an HTML document
+    ///
+    /// The document has the same headings, paragraphs, lists and tables as `extract_markdown`
+    /// gives, as `<h1>`-`<h6>`, `<p>`, `<ul>`/`<ol>` and `<table>` elements. Each page is a
+    /// `<section id="page-N" data-page="N">` after its physical page number. Words covered by
+    /// link annotations become links: web and mail links to their URI, links within the
+    /// document to the section of their target page when that page is exported. The title
+    /// is the document's, or its file name. Pages that fail to extract are noted at the end.
+    pub fn export_html(
+        file_path: &str,
+        selection: &PageSelection,
+        options: &TextOptions,
+        password: Option<&str>,
+    ) -> Result<String, PdfError> {
+        options.validate()?;
+        let doc = Self::load_document(file_path, password)?;
+
+        let page_count = doc.get_pages().len();
+        let labels = page_labels::read_page_labels(&doc, page_count);
+        let pages = Self::select_pages(&doc, selection, page_count, labels.as_deref())?;
+
+        let (blocks, skipped_pages) = Self::page_blocks(&doc, &pages, labels.as_deref(), options);
+        let xmp = xmp::read_xmp_metadata(&doc);
+        let title = metadata::read_metadata(&doc, xmp.as_ref()).title.filter(|title| !title.trim().is_empty());
+        let title = title.unwrap_or_else(|| {
+            let name = Path::new(file_path).file_name().map(|name| name.to_string_lossy().into_owned());
+            name.unwrap_or_else(|| file_path.to_string())
+        });
+        let mut notes = Vec::new();
+        if !skipped_pages.is_empty() {
+            notes.push(skipped_pages_note(&skipped_pages).trim().to_string());
         }
-        
-        let mut all_text = String::new();
+        Ok(html::render(&title, &markdown::elements(&blocks, options), &notes))
+    }
+
+    /// List the link annotations of the selected pages
+    ///
+    /// Every link is reported with its rectangle, the text under it and what it does: open a
+    /// URI, go to a page of the document (destinations are resolved to page numbers), go to
+    /// a destination in another file, or launch a file. Links are in page order, and in the
+    /// order of each page's annotations. Of the text options, `word_gap`, `bidi` and
+    /// `normalization` apply to the text; links on pages whose content cannot be read come
+    /// without text.
+    pub fn extract_links(
+        file_path: &str,
+        selection: &PageSelection,
+        options: &TextOptions,
+        password: Option<&str>,
+    ) -> Result<Vec<Link>, PdfError> {
+        options.validate()?;
+        let doc = Self::load_document(file_path, password)?;
+
+        let page_ids = doc.get_pages();
+        let labels = page_labels::read_page_labels(&doc, page_ids.len());
+        let pages = Self::select_pages(&doc, selection, page_ids.len(), labels.as_deref())?;
+
+        let resolver = DestinationResolver::new(&doc);
+        let (extracted, _) = Self::page_contents(&doc, &pages, labels.as_deref(), options);
+        let links = pages
+            .iter()
+            .zip(extracted)
+            .flat_map(|(&page, content)| {
+                let glyphs = content.map(|(glyphs, _)| glyphs).unwrap_or_default();
+                let page_id = page_ids.get(&page);
+                page_id.map(|&id| links::read_links(&doc, page, id, &glyphs, &resolver, options)).unwrap_or_default()
+            })
+            .collect();
+        Ok(links)
+    }
+
+    /// Split the selected pages into blocks of text and tables, with their links
+    ///
+    /// Returns the blocks of the pages that could be extracted and the numbers of those that
+    /// could not.
+    fn page_blocks(
+        doc: &Document,
+        pages: &[u32],
+        labels: Option<&[String]>,
+        options: &TextOptions,
+    ) -> (Vec<(u32, Vec<markdown::Block>)>, Vec<u32>) {
+        let page_ids = doc.get_pages();
+        let resolver = DestinationResolver::new(doc);
+        let (extracted, _) = Self::page_contents(doc, pages, labels, options);
+        let mut blocks = Vec::new();
         let mut skipped_pages = Vec::new();
-        
-        for page_num in start_page..=end_page {
-            match doc.extract_text(&[page_num]) {
-                Ok(text) => {
-                    if !all_text.is_empty() && !text.is_empty() {
-                        all_text.push('\n');
-                    }
-                    all_text.push_str(&text);
-                }
-                Err(_) => {
-                    skipped_pages.push(page_num);
+        for (&page, content) in pages.iter().zip(extracted) {
+            match content {
+                Ok((glyphs, rulings)) => {
+                    let links = links::page_links(doc, page_ids[&page], &resolver);
+                    blocks.push((page, markdown::page_blocks(glyphs, &rulings, &links, page, options)));
                 }
+                Err(_) => skipped_pages.push(page),
             }
         }
-        
-        if !skipped_pages.is_empty() {
-            all_text.push_str(&format!(
-                "\n\n[Note: {} page(s) could not be extracted due to font encoding issues: {:?}]",
-                skipped_pages.len(),
-                skipped_pages
-            ));
+        (blocks, skipped_pages)
+    }
+
+    /// Resolve a selection to physical page numbers, validating them against the document
+    fn select_pages(
+        doc: &Document,
+        selection: &PageSelection,
+        page_count: usize,
+        labels: Option<&[String]>,
+    ) -> Result<Vec<u32>, PdfError> {
+        match selection {
+            PageSelection::All => Ok((1..=page_count as u32).collect()),
+            PageSelection::Page(page) => Ok(vec![page.resolve(labels, page_count)?]),
+            PageSelection::Range(start_page, end_page) => {
+                let start_page = start_page.resolve(labels, page_count)?;
+                let end_page = end_page.resolve(labels, page_count)?;
+                
+                if start_page > end_page {
+                    return Err(PdfError::InvalidPageSelection(
+                        format!("{}-{}", start_page, end_page),
+                        format!("start_page ({}) must be <= end_page ({})", start_page, end_page),
+                    ));
+                }
+                Ok((start_page..=end_page).collect())
+            }
+            PageSelection::Pages(spec) => page_selection::select_pages(spec, page_count, labels),
+            PageSelection::Section(section) => {
+                let outline = outline::read_outline(doc);
+                let (start_page, end_page) = outline::find_section(&outline, section, page_count as u32)?;
+                Ok((start_page..=end_page).collect())
+            }
         }
-        
-        Ok(all_text)
     }
 
     /// Get PDF metadata and page count
-    pub fn get_info(file_path: &str) -> Result<PdfInfo, PdfError> {
-        let doc = Self::load_document(file_path)?;
+    ///
+    /// Encrypted documents opened without the right password still report their page count,
+    /// encryption settings and permissions, with `locked` set and no metadata.
+    pub fn get_info(file_path: &str, password: Option<&str>) -> Result<PdfInfo, PdfError> {
+        let doc = match Self::load_document(file_path, password) {
+            Ok(doc) => doc,
+            Err(PdfError::EncryptedDocument) => return Self::get_locked_info(file_path),
+            Err(e) => return Err(e),
+        };
         
         let pages = doc.get_pages();
         let page_count = pages.len();
+        let file_size = std::fs::metadata(file_path)?.len();
         
-        // Try to get the Info dictionary from the trailer
-        let (title, author, subject, creator) = Self::extract_metadata(&doc);
+        let xmp = xmp::read_xmp_metadata(&doc);
+        let info = metadata::read_metadata(&doc, xmp.as_ref());
+        let encryption = encryption::encryption_info(&doc);
         
+        Ok(PdfInfo {
+            page_count: Some(page_count),
+            pdf_version: doc.version.clone(),
+            file_size,
+            page_labels: page_labels::read_page_labels(&doc, page_count),
+            title: info.title,
+            author: info.author,
+            subject: info.subject,
+            creator: info.creator,
+            keywords: info.keywords,
+            producer: info.producer,
+            creation_date: info.creation_date,
+            modification_date: info.modification_date,
+            trapped: info.trapped,
+            custom: info.custom,
+            xmp,
+            encrypted: encryption.is_some(),
+            encryption,
+            locked: false,
+            tagged: structure::is_tagged(&doc),
+        })
+    }
+
+    /// Describe an encrypted document that could not be decrypted
+    ///
+    /// The `/Encrypt` dictionary and the page tree are not encrypted; the metadata strings are.
+    /// Objects packed in object streams are encrypted with the stream, so the page count is
+    /// only known when the page tree root is stored outside of them.
+    fn get_locked_info(file_path: &str) -> Result<PdfInfo, PdfError> {
+        let bytes = std::fs::read(file_path)?;
+        let doc = encryption::load_locked_document(&bytes)?;
+        let page_count = doc
+            .catalog()
+            .and_then(|catalog| catalog.get(b"Pages"))
+            .and_then(|pages| doc.dereference(pages))
+            .and_then(|(_, pages)| pages.as_dict()?.get(b"Count")?.as_i64())
+            .ok()
+            .and_then(|count| usize::try_from(count).ok());
+
         Ok(PdfInfo {
             page_count,
-            title,
-            author,
-            subject,
-            creator,
+            pdf_version: doc.version.clone(),
+            file_size: bytes.len() as u64,
+            page_labels: None,
+            title: None,
+            author: None,
+            subject: None,
+            creator: None,
+            keywords: None,
+            producer: None,
+            creation_date: None,
+            modification_date: None,
+            trapped: None,
+            custom: BTreeMap::new(),
+            xmp: None,
+            encrypted: true,
+            encryption: encryption::encryption_info(&doc),
+            locked: true,
+            tagged: structure::is_tagged(&doc),
         })
     }
+
+    /// Get the document outline (bookmarks) as a tree
+    ///
+    /// Each entry's destination is resolved to a 1-indexed page number where possible.
+    /// Documents without an outline return an empty list.
+    pub fn get_outline(file_path: &str, password: Option<&str>) -> Result<Vec<OutlineItem>, PdfError> {
+        let doc = Self::load_document(file_path, password)?;
+        Ok(outline::read_outline(&doc))
+    }
+}
+
+/// Concatenate page texts, noting pages that could not be extracted
+pub(crate) fn join_page_texts(records: &[PageText]) -> String {
+    let mut all_text = String::new();
+    let mut skipped_pages = Vec::new();
     
-    /// Extract metadata from the document's Info dictionary
-    fn extract_metadata(doc: &Document) -> (Option<String>, Option<String>, Option<String>, Option<String>) {
-        // Get the Info dictionary reference from the trailer
-        let info_ref = match doc.trailer.get(b"Info") {
-            Ok(obj) => obj,
-            Err(_) => return (None, None, None, None),
-        };
-        
-        // Dereference to get the actual Info dictionary
-        let info_dict = match info_ref.as_reference() {
-            Ok(ref_id) => {
-                match doc.get_dictionary(ref_id) {
-                    Ok(dict) => dict,
-                    Err(_) => return (None, None, None, None),
-                }
-            }
-            Err(_) => {
-                // Maybe it's a direct dictionary
-                match info_ref.as_dict() {
-                    Ok(dict) => dict,
-                    Err(_) => return (None, None, None, None),
-                }
-            }
-        };
-        
-        let title = Self::get_string_from_dict(info_dict, b"Title");
-        let author = Self::get_string_from_dict(info_dict, b"Author");
-        let subject = Self::get_string_from_dict(info_dict, b"Subject");
-        let creator = Self::get_string_from_dict(info_dict, b"Creator");
-        
-        (title, author, subject, creator)
+    for record in records {
+        if record.status == PageStatus::Error {
+            skipped_pages.push(record.page);
+            continue;
+        }
+        if !all_text.is_empty() && !record.text.is_empty() {
+            all_text.push('\n');
+        }
+        all_text.push_str(&record.text);
     }
     
-    /// Helper to extract a string value from a dictionary
-    fn get_string_from_dict(dict: &lopdf::Dictionary, key: &[u8]) -> Option<String> {
-        dict.get(key).ok().and_then(|obj| {
-            // decode_text_string takes an Object reference and returns Result<String>
-            lopdf::decode_text_string(obj).ok()
-        })
+    if !skipped_pages.is_empty() {
+        all_text.push_str(&skipped_pages_note(&skipped_pages));
     }
+    
+    all_text
+}
+
+/// The note appended to extracted text when some pages could not be extracted
+fn skipped_pages_note(skipped_pages: &[u32]) -> String {
+    format!("\n\n[Note: {} page(s) could not be extracted: {:?}]", skipped_pages.len(), skipped_pages)
 }
@@ -0,0 +1,262 @@
+//! Reading order of the text on a page
+//!
+//! Lines are split into segments at wide gaps, then the page is cut recursively: at a
+//! column gutter (a vertical strip free of text) where the text on both sides looks like
+//! running prose, otherwise at the widest horizontal gap. Columns are read left to right
+//! and bands top to bottom, so a title block above a two-column body comes first and each
+//! column is read to its end before the next one starts. On right-to-left pages, columns
+//! and segments sharing a line are read from right to left instead.
+
+use crate::content::PositionedGlyph;
+use crate::layout::{median, WordGlyphs};
+
+/// Gap between words, as a fraction of the font size, that splits a line into segments
+/// (table cells, or lines of neighbouring columns drawn as one)
+const SEGMENT_GAP: f64 = 1.0;
+
+/// Narrowest column gutter, as a fraction of the median font size
+const MIN_GUTTER: f64 = 1.0;
+
+/// Fewest segments on each side of a gutter for it to separate columns
+const MIN_COLUMN_SEGMENTS: usize = 2;
+
+/// Fewest words per segment, on average, on each side of a gutter; table cells and form
+/// fields hold fewer, so tables are read row by row instead of column by column
+const MIN_COLUMN_WORDS: f64 = 3.0;
+
+/// Baseline distance, as a fraction of the font size, within which segments share a line
+const SAME_LINE: f64 = 0.5;
+
+/// Baseline drop, as a fraction of the font size, within which a line directly follows the
+/// one above it
+const NEXT_LINE: f64 = 2.0;
+
+/// Consecutive words on one line that are read together
+pub(crate) struct Segment<'a> {
+    pub words: Vec<WordGlyphs<'a>>,
+    /// x0, y0, x1, y1 in user space
+    pub bbox: [f64; 4],
+    pub baseline: f64,
+    pub font_size: f64,
+}
+
+impl<'a> Segment<'a> {
+    fn new(words: Vec<WordGlyphs<'a>>) -> Self {
+        let glyphs = || words.iter().flatten();
+        let bbox = glyphs().fold(
+            [f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY],
+            |[x0, y0, x1, y1], glyph| {
+                [x0.min(glyph.bbox[0]), y0.min(glyph.bbox[1]), x1.max(glyph.bbox[2]), y1.max(glyph.bbox[3])]
+            },
+        );
+        let first = words[0][0];
+        Segment { bbox, baseline: first.y, font_size: first.font_size, words }
+    }
+
+    /// Text of each word of the segment
+    pub fn word_texts(&self) -> Vec<String> {
+        self.words.iter().map(|word| word.iter().map(|g| g.text.as_str()).collect()).collect()
+    }
+
+    /// Whether `next` is the line right below this one, in the same column
+    fn is_followed_by(&self, next: &Segment) -> bool {
+        let drop = self.baseline - next.baseline;
+        drop > 0.0
+            && drop < NEXT_LINE * self.font_size.max(next.font_size)
+            && next.bbox[0] < self.bbox[2]
+            && self.bbox[0] < next.bbox[2]
+    }
+}
+
+/// Split lines of words into segments at gaps wider than [`SEGMENT_GAP`]
+pub(crate) fn segments<'a>(lines: Vec<Vec<WordGlyphs<'a>>>) -> Vec<Segment<'a>> {
+    let mut segments = Vec::new();
+    for line in lines {
+        let mut current: Vec<WordGlyphs> = Vec::new();
+        for word in line {
+            if let Some(previous) = current.last()
+                && horizontal_gap(previous, &word) > SEGMENT_GAP * previous[0].font_size
+            {
+                segments.push(Segment::new(std::mem::take(&mut current)));
+            }
+            current.push(word);
+        }
+        if !current.is_empty() {
+            segments.push(Segment::new(current));
+        }
+    }
+    segments
+}
+
+/// Order segments for reading and group them into the lines they form on the page
+///
+/// A segment continues the line of the one read before it when their baselines are close
+/// and it lies further along in the reading direction.
+pub(crate) fn reading_lines(segments: Vec<Segment>, right_to_left: bool) -> Vec<Vec<Segment>> {
+    let median_size = median(segments.iter().map(|segment| segment.font_size)).unwrap_or(10.0);
+    let mut lines: Vec<Vec<Segment>> = Vec::new();
+    for segment in order(segments, median_size, right_to_left) {
+        if let Some(line) = lines.last_mut()
+            && let Some(previous) = line.last()
+        {
+            let follows = if right_to_left {
+                segment.bbox[2] <= previous.bbox[0]
+            } else {
+                segment.bbox[0] >= previous.bbox[2]
+            };
+            if (previous.baseline - segment.baseline).abs() < SAME_LINE * previous.font_size.max(segment.font_size)
+                && follows
+            {
+                line.push(segment);
+                continue;
+            }
+        }
+        lines.push(vec![segment]);
+    }
+    lines
+}
+
+/// Order segments for reading and join them into text, one line per line on the page
+///
+/// With `dehyphenate`, a word hyphenated at the end of a line is joined with its rest
+/// from the start of the line below ("docu-" and "ment" become "document" on the upper
+/// line), provided that line follows in the same column and starts in lowercase.
+pub(crate) fn reading_text(segments: Vec<Segment>, right_to_left: bool, dehyphenate: bool) -> String {
+    let lines = reading_lines(segments, right_to_left);
+
+    let mut text = String::new();
+    let mut previous: Option<&Segment> = None;
+    for line in &lines {
+        for (position, segment) in line.iter().enumerate() {
+            let mut words = segment.word_texts();
+            if let Some(previous) = previous {
+                let same_line = position > 0;
+                if dehyphenate
+                    && !same_line
+                    && ends_hyphenated(&text)
+                    && words[0].starts_with(char::is_lowercase)
+                    && previous.is_followed_by(segment)
+                {
+                    text.pop();
+                    text.push_str(&words.remove(0));
+                }
+                if !words.is_empty() {
+                    text.push(if same_line { ' ' } else { '\n' });
+                }
+            }
+            text.push_str(&words.join(" "));
+            previous = Some(segment);
+        }
+    }
+    text
+}
+
+/// Whether text ends in a letter followed by a hyphen or soft hyphen
+pub(crate) fn ends_hyphenated(text: &str) -> bool {
+    let mut chars = text.chars().rev();
+    matches!(chars.next(), Some('-' | '\u{00AD}' | '\u{2010}')) && chars.next().is_some_and(char::is_alphabetic)
+}
+
+/// Recursively cut a region into columns and bands and return its segments in reading order
+fn order(mut segments: Vec<Segment>, median_size: f64, right_to_left: bool) -> Vec<Segment> {
+    if segments.len() <= 1 {
+        return segments;
+    }
+
+    if let Some(gutter) = column_gutter(&segments, median_size) {
+        let (left, right): (Vec<_>, Vec<_>) = segments.into_iter().partition(|segment| segment.bbox[2] <= gutter);
+        let (first, second) = if right_to_left { (right, left) } else { (left, right) };
+        let mut ordered = order(first, median_size, right_to_left);
+        ordered.extend(order(second, median_size, right_to_left));
+        return ordered;
+    }
+
+    if let Some(cut) = widest_horizontal_gap(&segments) {
+        let (top, bottom): (Vec<_>, Vec<_>) = segments.into_iter().partition(|segment| segment.bbox[1] >= cut);
+        let mut ordered = order(top, median_size, right_to_left);
+        ordered.extend(order(bottom, median_size, right_to_left));
+        return ordered;
+    }
+
+    // Rows that overlap (e.g. superscripts): top to bottom by baseline, then left to right
+    // (right to left on right-to-left pages)
+    segments.sort_by(|a, b| b.baseline.total_cmp(&a.baseline));
+    let mut rows: Vec<Vec<Segment>> = Vec::new();
+    for segment in segments {
+        match rows.last_mut() {
+            Some(row) if row[0].baseline - segment.baseline < SAME_LINE * row[0].font_size.max(segment.font_size) => {
+                row.push(segment);
+            }
+            _ => rows.push(vec![segment]),
+        }
+    }
+    rows.into_iter()
+        .flat_map(|mut row| {
+            if right_to_left {
+                row.sort_by(|a, b| b.bbox[2].total_cmp(&a.bbox[2]));
+            } else {
+                row.sort_by(|a, b| a.bbox[0].total_cmp(&b.bbox[0]));
+            }
+            row
+        })
+        .collect()
+}
+
+/// The x coordinate of the widest gutter that separates two columns of prose, if any
+fn column_gutter(segments: &[Segment], median_size: f64) -> Option<f64> {
+    let mut spans: Vec<(f64, f64)> = segments.iter().map(|segment| (segment.bbox[0], segment.bbox[2])).collect();
+    spans.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut gutters = Vec::new();
+    let mut reach = spans[0].1;
+    for (x0, x1) in &spans[1..] {
+        if *x0 - reach >= MIN_GUTTER * median_size {
+            gutters.push((reach, *x0));
+        }
+        reach = reach.max(*x1);
+    }
+
+    gutters
+        .into_iter()
+        .filter(|(start, _)| {
+            let (left, right): (Vec<&Segment>, Vec<&Segment>) =
+                segments.iter().partition(|segment| segment.bbox[2] <= *start);
+            is_column(&left) && is_column(&right)
+        })
+        .max_by(|a, b| (a.1 - a.0).total_cmp(&(b.1 - b.0)))
+        .map(|(start, end)| (start + end) / 2.0)
+}
+
+/// Whether the segments on one side of a gutter look like a column of running text
+fn is_column(segments: &[&Segment]) -> bool {
+    let words: usize = segments.iter().map(|segment| segment.words.len()).sum();
+    segments.len() >= MIN_COLUMN_SEGMENTS && words as f64 / segments.len() as f64 >= MIN_COLUMN_WORDS
+}
+
+/// The y coordinate in the middle of the widest horizontal strip free of text, if any
+fn widest_horizontal_gap(segments: &[Segment]) -> Option<f64> {
+    let mut spans: Vec<(f64, f64)> = segments.iter().map(|segment| (segment.bbox[1], segment.bbox[3])).collect();
+    // Top to bottom
+    spans.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let mut widest: Option<(f64, f64)> = None;
+    let mut floor = spans[0].0;
+    for (y0, y1) in &spans[1..] {
+        if *y1 < floor && widest.is_none_or(|(top, bottom)| floor - y1 > top - bottom) {
+            widest = Some((floor, *y1));
+        }
+        floor = floor.min(*y0);
+    }
+    widest.map(|(top, bottom)| (top + bottom) / 2.0)
+}
+
+/// Horizontal distance between two words, whichever side of the first the second is on
+/// (words of right-to-left lines run leftwards)
+fn horizontal_gap(previous: &[&PositionedGlyph], next: &[&PositionedGlyph]) -> f64 {
+    let span = |word: &[&PositionedGlyph]| {
+        word.iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(x0, x1), glyph| (x0.min(glyph.bbox[0]), x1.max(glyph.bbox[2])))
+    };
+    let ((previous_x0, previous_x1), (next_x0, next_x1)) = (span(previous), span(next));
+    (next_x0 - previous_x1).max(previous_x0 - next_x1)
+}
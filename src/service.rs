@@ -1,6 +1,11 @@
 //! MCP Server service implementation for PDF Reader
 
-use crate::pdf_reader::PdfReader;
+use crate::error::PdfError;
+use crate::links::Link;
+use crate::page_labels::PageRef;
+use crate::page_selection::PageSelection;
+use crate::pdf_reader::{self, BidiOrder, Normalization, PageText, PdfReader, TextLayout, TextOptions};
+use crate::tables::Table;
 use rmcp::{
     handler::server::tool::ToolRouter,
     handler::server::wrapper::Parameters,
@@ -9,15 +14,72 @@ use rmcp::{
     ErrorData as McpError,
 };
 use schemars::JsonSchema;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::path::Path;
 use std::sync::Arc;
 
+/// Output format of the text extraction tools
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Page texts concatenated into one string
+    #[default]
+    Text,
+    /// One JSON record per page with its label and extraction status
+    Json,
+}
+
+/// Text content format of the extract_pdf_tables tool
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TableFormat {
+    /// Markdown pipe tables
+    #[default]
+    Markdown,
+    /// Comma-separated values, tables separated by a blank line
+    Csv,
+    /// The tables with their cell grids, as JSON
+    Json,
+}
+
+/// Text options shared by the tools that read page content
+///
+/// Every such tool takes these parameters; its input schema lists the ones that apply to it
+/// (see `text_options_schema`).
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct TextOptionsParams {
+    /// Text arrangement: "reading" (default), "preserve" or "tagged"
+    #[serde(default)]
+    pub layout: TextLayout,
+    /// Smallest gap between glyphs that starts a new word, as a fraction of the font's space width (default 0.5)
+    pub word_gap: Option<f64>,
+    /// Character order in lines of right-to-left text: "logical" (default) or "visual"
+    #[serde(default)]
+    pub bidi: BidiOrder,
+    /// Text clean-up steps, each on or off
+    #[serde(default)]
+    pub normalize: Normalization,
+    /// Remove running headers, footers and page numbers that recur across pages (default false)
+    #[serde(default)]
+    pub strip_furniture: bool,
+}
+
 /// Parameters for the read_pdf tool
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct ReadPdfParams {
     /// Absolute path to the PDF file (relative paths are not supported)
     pub file_path: String,
+    /// Page selector, e.g. "1-3,7,10-", "last", "-5" (last five), "odd" or "even"; page labels are accepted too; numbers are always physical pages; all pages when omitted
+    pub pages: Option<String>,
+    /// Output format of the page texts (default "text")
+    #[serde(default)]
+    pub format: OutputFormat,
+    /// Options for reading the text of the pages
+    #[serde(flatten)]
+    pub text: TextOptionsParams,
+    /// Password for encrypted documents (user or owner password)
+    pub password: Option<String>,
 }
 
 /// Parameters for the read_pdf_page tool
@@ -25,19 +87,37 @@ pub struct ReadPdfParams {
 pub struct ReadPdfPageParams {
     /// Absolute path to the PDF file (relative paths are not supported)
     pub file_path: String,
-    /// Page number (1-indexed)
-    pub page: u32,
+    /// Physical page number (1-indexed) or page label as printed (e.g. "iv", "A-12"); numbers are always physical pages
+    pub page: PageRef,
+    /// Output format of the page texts (default "text")
+    #[serde(default)]
+    pub format: OutputFormat,
+    /// Options for reading the text of the pages
+    #[serde(flatten)]
+    pub text: TextOptionsParams,
+    /// Password for encrypted documents (user or owner password)
+    pub password: Option<String>,
 }
 
-/// Parameters for the read_pdf_pages tool (page range)
+/// Parameters for the read_pdf_pages tool (page selector or range)
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct ReadPdfPagesParams {
     /// Absolute path to the PDF file (relative paths are not supported)
     pub file_path: String,
-    /// Start page number (1-indexed, inclusive)
-    pub start_page: u32,
-    /// End page number (1-indexed, inclusive)
-    pub end_page: u32,
+    /// Page selector, e.g. "1-3,7,10-", "last", "-5" (last five), "odd" or "even"; page labels are accepted too; numbers are always physical pages
+    pub pages: Option<String>,
+    /// Start page: physical page number (1-indexed, inclusive) or page label; used with end_page instead of pages
+    pub start_page: Option<PageRef>,
+    /// End page: physical page number (1-indexed, inclusive) or page label; used with start_page instead of pages
+    pub end_page: Option<PageRef>,
+    /// Output format of the page texts (default "text")
+    #[serde(default)]
+    pub format: OutputFormat,
+    /// Options for reading the text of the pages
+    #[serde(flatten)]
+    pub text: TextOptionsParams,
+    /// Password for encrypted documents (user or owner password)
+    pub password: Option<String>,
 }
 
 /// Parameters for the get_pdf_info tool
@@ -45,28 +125,212 @@ pub struct ReadPdfPagesParams {
 pub struct GetPdfInfoParams {
     /// Absolute path to the PDF file (relative paths are not supported)
     pub file_path: String,
+    /// Password for encrypted documents (user or owner password)
+    pub password: Option<String>,
+}
+
+/// Parameters for the get_pdf_outline tool
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetPdfOutlineParams {
+    /// Absolute path to the PDF file (relative paths are not supported)
+    pub file_path: String,
+    /// Password for encrypted documents (user or owner password)
+    pub password: Option<String>,
+}
+
+/// Parameters for the read_pdf_section tool
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ReadPdfSectionParams {
+    /// Absolute path to the PDF file (relative paths are not supported)
+    pub file_path: String,
+    /// Bookmark title or outline path separated by '>' (e.g. "Chapter 3 > Installation")
+    pub section: String,
+    /// Output format of the page texts (default "text")
+    #[serde(default)]
+    pub format: OutputFormat,
+    /// Options for reading the text of the pages
+    #[serde(flatten)]
+    pub text: TextOptionsParams,
+    /// Password for encrypted documents (user or owner password)
+    pub password: Option<String>,
+}
+
+/// Parameters for the read_pdf_page_layout tool
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ReadPdfPageLayoutParams {
+    /// Absolute path to the PDF file (relative paths are not supported)
+    pub file_path: String,
+    /// Physical page number (1-indexed) or page label as printed (e.g. "iv", "A-12"); numbers are always physical pages
+    pub page: PageRef,
+    /// Include the bounding box of every glyph in each word (default false)
+    #[serde(default)]
+    pub include_glyphs: bool,
+    /// Options for reading the text of the pages
+    #[serde(flatten)]
+    pub text: TextOptionsParams,
+    /// Password for encrypted documents (user or owner password)
+    pub password: Option<String>,
+}
+
+/// Parameters for the extract_pdf_tables tool
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ExtractPdfTablesParams {
+    /// Absolute path to the PDF file (relative paths are not supported)
+    pub file_path: String,
+    /// Physical page number (1-indexed) or page label as printed (e.g. "iv", "A-12"); numbers are always physical pages
+    pub page: PageRef,
+    /// Text content format: "markdown" (default), "csv" or "json"; the structured output always holds all three
+    #[serde(default)]
+    pub format: TableFormat,
+    /// Options for reading the text of the pages
+    #[serde(flatten)]
+    pub text: TextOptionsParams,
+    /// Password for encrypted documents (user or owner password)
+    pub password: Option<String>,
+}
+
+/// Parameters for the pdf_to_markdown tool
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct PdfToMarkdownParams {
+    /// Absolute path to the PDF file (relative paths are not supported)
+    pub file_path: String,
+    /// Page selector, e.g. "1-3,7,10-", "last", "-5" (last five), "odd" or "even"; page labels are accepted too; numbers are always physical pages; all pages when omitted
+    pub pages: Option<String>,
+    /// Start every page with an HTML anchor `<a id="page-N"></a>` (default true)
+    pub page_anchors: Option<bool>,
+    /// Options for reading the text of the pages
+    #[serde(flatten)]
+    pub text: TextOptionsParams,
+    /// Password for encrypted documents (user or owner password)
+    pub password: Option<String>,
+}
+
+/// Parameters for the export_pdf_html tool
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ExportPdfHtmlParams {
+    /// Absolute path to the PDF file (relative paths are not supported)
+    pub file_path: String,
+    /// Absolute path of the HTML file to write; an existing file is overwritten
+    pub output_path: String,
+    /// Page selector, e.g. "1-3,7,10-", "last", "-5" (last five), "odd" or "even"; page labels are accepted too; numbers are always physical pages; all pages when omitted
+    pub pages: Option<String>,
+    /// Options for reading the text of the pages
+    #[serde(flatten)]
+    pub text: TextOptionsParams,
+    /// Password for encrypted documents (user or owner password)
+    pub password: Option<String>,
+}
+
+/// Parameters for the get_pdf_links tool
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetPdfLinksParams {
+    /// Absolute path to the PDF file (relative paths are not supported)
+    pub file_path: String,
+    /// Page selector, e.g. "1-3,7,10-", "last", "-5" (last five), "odd" or "even"; page labels are accepted too; numbers are always physical pages; all pages when omitted
+    pub pages: Option<String>,
+    /// Options for reading the text of the pages
+    #[serde(flatten)]
+    pub text: TextOptionsParams,
+    /// Password for encrypted documents (user or owner password)
+    pub password: Option<String>,
+}
+
+/// Schema of the `format` parameter of the page text tools
+fn output_format_schema() -> serde_json::Value {
+    json!({
+        "type": "string",
+        "enum": ["text", "json"],
+        "description": "Output format: \"text\" (default) concatenates the pages, \"json\" returns one record per page with page, label, text, status and error"
+    })
+}
+
+/// Schema properties of the named text options, to add to a tool's input schema
+fn text_options_schema(options: &[&str]) -> serde_json::Map<String, serde_json::Value> {
+    let schema = json!({
+        "layout": {
+            "type": "string",
+            "enum": ["reading", "preserve", "tagged"],
+            "description": "Text arrangement: \"reading\" (default) for plain lines in reading order, \"preserve\" for a fixed-width grid that keeps columns and table alignment, \"tagged\" for the logical order of a tagged document with a role label per line ([H1], [P], [LI], ...), falling back to \"reading\" when the document is untagged"
+        },
+        "word_gap": {
+            "type": "number",
+            "exclusiveMinimum": 0,
+            "description": "Smallest gap between glyphs that starts a new word, as a fraction of the font's space width (default 0.5); lower it when words run together, raise it when words break apart"
+        },
+        "bidi": {
+            "type": "string",
+            "enum": ["logical", "visual"],
+            "description": "Character order in lines of right-to-left text (Hebrew, Arabic): \"logical\" (default) for reading order with embedded numbers and Latin words intact, \"visual\" for the left-to-right order on the page"
+        },
+        "normalize": {
+            "type": "object",
+            "description": "Text clean-up steps, each on or off; omitted steps keep their defaults",
+            "properties": {
+                "ligatures": {
+                    "type": "boolean",
+                    "description": "Expand ligature characters such as \"ﬁ\" and \"ﬂ\" into their letters (default true)"
+                },
+                "dehyphenate": {
+                    "type": "boolean",
+                    "description": "Join words hyphenated across line breaks (\"docu-\" + \"ment\") when the next line continues the same column in lowercase; reading layout only (default false)"
+                },
+                "unicode_form": {
+                    "type": "string",
+                    "enum": ["none", "nfc", "nfkc"],
+                    "description": "Unicode normalization: \"none\" (default), \"nfc\" (composed accents) or \"nfkc\" (also folds full-width and compatibility forms)"
+                },
+                "strip_control": {
+                    "type": "boolean",
+                    "description": "Remove control characters, soft hyphens, zero-width spaces and private-use characters (default true)"
+                },
+                "collapse_whitespace": {
+                    "type": "boolean",
+                    "description": "Collapse runs of spaces, trim lines and keep at most one blank line in a row; undoes the preserve layout's alignment (default false)"
+                }
+            },
+            "additionalProperties": false
+        },
+        "strip_furniture": {
+            "type": "boolean",
+            "description": "Remove running headers, footers and page numbers that recur across pages; the page text tools list the removed lines per page in their structured output (default false)"
+        }
+    });
+    let mut properties = schema.as_object().unwrap().clone();
+    properties.retain(|name, _| options.contains(&name.as_str()));
+    properties
 }
 
 /// Create a custom schema for read_pdf without $schema field
 fn read_pdf_schema() -> Arc<serde_json::Map<String, serde_json::Value>> {
-    let schema = json!({
+    let mut schema = json!({
         "type": "object",
         "description": "Parameters for the read_pdf tool",
         "properties": {
             "file_path": {
                 "type": "string",
                 "description": "Absolute path to the PDF file (relative paths are not supported)"
+            },
+            "pages": {
+                "type": "string",
+                "description": "Page selector, e.g. \"1-3,7,10-\", \"last\", \"-5\" (last five), \"odd\" or \"even\"; page labels are accepted too; numbers are always physical pages; all pages when omitted"
+            },
+            "format": output_format_schema(),
+            "password": {
+                "type": "string",
+                "description": "Password for encrypted documents (user or owner password)"
             }
         },
         "required": ["file_path"],
         "title": "ReadPdfParams"
     });
+    let options = text_options_schema(&["layout", "word_gap", "bidi", "normalize", "strip_furniture"]);
+    schema["properties"].as_object_mut().unwrap().extend(options);
     Arc::new(schema.as_object().unwrap().clone())
 }
 
 /// Create a custom schema for read_pdf_page without $schema field
 fn read_pdf_page_schema() -> Arc<serde_json::Map<String, serde_json::Value>> {
-    let schema = json!({
+    let mut schema = json!({
         "type": "object",
         "description": "Parameters for the read_pdf_page tool",
         "properties": {
@@ -75,21 +339,27 @@ fn read_pdf_page_schema() -> Arc<serde_json::Map<String, serde_json::Value>> {
                 "description": "Absolute path to the PDF file (relative paths are not supported)"
             },
             "page": {
-                "type": "integer",
-                "description": "Page number (1-indexed)",
-                "minimum": 0,
-                "format": "uint32"
+                "type": ["integer", "string"],
+                "description": "Physical page number (1-indexed) or page label as printed (e.g. \"iv\", \"A-12\"); numbers are always physical pages",
+                "minimum": 0
+            },
+            "format": output_format_schema(),
+            "password": {
+                "type": "string",
+                "description": "Password for encrypted documents (user or owner password)"
             }
         },
         "required": ["file_path", "page"],
         "title": "ReadPdfPageParams"
     });
+    let options = text_options_schema(&["layout", "word_gap", "bidi", "normalize", "strip_furniture"]);
+    schema["properties"].as_object_mut().unwrap().extend(options);
     Arc::new(schema.as_object().unwrap().clone())
 }
 
 /// Create a custom schema for read_pdf_pages (page range) without $schema field
 fn read_pdf_pages_schema() -> Arc<serde_json::Map<String, serde_json::Value>> {
-    let schema = json!({
+    let mut schema = json!({
         "type": "object",
         "description": "Parameters for the read_pdf_pages tool",
         "properties": {
@@ -97,22 +367,31 @@ fn read_pdf_pages_schema() -> Arc<serde_json::Map<String, serde_json::Value>> {
                 "type": "string",
                 "description": "Absolute path to the PDF file (relative paths are not supported)"
             },
+            "pages": {
+                "type": "string",
+                "description": "Page selector, e.g. \"1-3,7,10-\", \"last\", \"-5\" (last five), \"odd\" or \"even\"; page labels are accepted too; numbers are always physical pages"
+            },
             "start_page": {
-                "type": "integer",
-                "description": "Start page number (1-indexed, inclusive)",
-                "minimum": 1,
-                "format": "uint32"
+                "type": ["integer", "string"],
+                "description": "Start page: physical page number (1-indexed, inclusive) or page label; used with end_page instead of pages",
+                "minimum": 1
             },
             "end_page": {
-                "type": "integer",
-                "description": "End page number (1-indexed, inclusive)",
-                "minimum": 1,
-                "format": "uint32"
+                "type": ["integer", "string"],
+                "description": "End page: physical page number (1-indexed, inclusive) or page label; used with start_page instead of pages",
+                "minimum": 1
+            },
+            "format": output_format_schema(),
+            "password": {
+                "type": "string",
+                "description": "Password for encrypted documents (user or owner password)"
             }
         },
-        "required": ["file_path", "start_page", "end_page"],
+        "required": ["file_path"],
         "title": "ReadPdfPagesParams"
     });
+    let options = text_options_schema(&["layout", "word_gap", "bidi", "normalize", "strip_furniture"]);
+    schema["properties"].as_object_mut().unwrap().extend(options);
     Arc::new(schema.as_object().unwrap().clone())
 }
 
@@ -125,6 +404,10 @@ fn get_pdf_info_schema() -> Arc<serde_json::Map<String, serde_json::Value>> {
             "file_path": {
                 "type": "string",
                 "description": "Absolute path to the PDF file (relative paths are not supported)"
+            },
+            "password": {
+                "type": "string",
+                "description": "Password for encrypted documents (user or owner password)"
             }
         },
         "required": ["file_path"],
@@ -133,6 +416,540 @@ fn get_pdf_info_schema() -> Arc<serde_json::Map<String, serde_json::Value>> {
     Arc::new(schema.as_object().unwrap().clone())
 }
 
+/// Create a custom schema for get_pdf_outline without $schema field
+fn get_pdf_outline_schema() -> Arc<serde_json::Map<String, serde_json::Value>> {
+    let schema = json!({
+        "type": "object",
+        "description": "Parameters for the get_pdf_outline tool",
+        "properties": {
+            "file_path": {
+                "type": "string",
+                "description": "Absolute path to the PDF file (relative paths are not supported)"
+            },
+            "password": {
+                "type": "string",
+                "description": "Password for encrypted documents (user or owner password)"
+            }
+        },
+        "required": ["file_path"],
+        "title": "GetPdfOutlineParams"
+    });
+    Arc::new(schema.as_object().unwrap().clone())
+}
+
+/// Create a custom schema for read_pdf_section without $schema field
+fn read_pdf_section_schema() -> Arc<serde_json::Map<String, serde_json::Value>> {
+    let mut schema = json!({
+        "type": "object",
+        "description": "Parameters for the read_pdf_section tool",
+        "properties": {
+            "file_path": {
+                "type": "string",
+                "description": "Absolute path to the PDF file (relative paths are not supported)"
+            },
+            "section": {
+                "type": "string",
+                "description": "Bookmark title or outline path separated by '>' (e.g. \"Chapter 3 > Installation\")"
+            },
+            "format": output_format_schema(),
+            "password": {
+                "type": "string",
+                "description": "Password for encrypted documents (user or owner password)"
+            }
+        },
+        "required": ["file_path", "section"],
+        "title": "ReadPdfSectionParams"
+    });
+    let options = text_options_schema(&["layout", "word_gap", "bidi", "normalize", "strip_furniture"]);
+    schema["properties"].as_object_mut().unwrap().extend(options);
+    Arc::new(schema.as_object().unwrap().clone())
+}
+
+/// Create a custom schema for read_pdf_page_layout without $schema field
+fn read_pdf_page_layout_schema() -> Arc<serde_json::Map<String, serde_json::Value>> {
+    let mut schema = json!({
+        "type": "object",
+        "description": "Parameters for the read_pdf_page_layout tool",
+        "properties": {
+            "file_path": {
+                "type": "string",
+                "description": "Absolute path to the PDF file (relative paths are not supported)"
+            },
+            "page": {
+                "type": ["integer", "string"],
+                "description": "Physical page number (1-indexed) or page label as printed (e.g. \"iv\", \"A-12\"); numbers are always physical pages",
+                "minimum": 0
+            },
+            "include_glyphs": {
+                "type": "boolean",
+                "description": "Include the bounding box of every glyph in each word (default false)"
+            },
+            "password": {
+                "type": "string",
+                "description": "Password for encrypted documents (user or owner password)"
+            }
+        },
+        "required": ["file_path", "page"],
+        "title": "ReadPdfPageLayoutParams"
+    });
+    let options = text_options_schema(&["word_gap", "bidi"]);
+    schema["properties"].as_object_mut().unwrap().extend(options);
+    Arc::new(schema.as_object().unwrap().clone())
+}
+
+/// Create a custom schema for extract_pdf_tables without $schema field
+fn extract_pdf_tables_schema() -> Arc<serde_json::Map<String, serde_json::Value>> {
+    let mut schema = json!({
+        "type": "object",
+        "description": "Parameters for the extract_pdf_tables tool",
+        "properties": {
+            "file_path": {
+                "type": "string",
+                "description": "Absolute path to the PDF file (relative paths are not supported)"
+            },
+            "page": {
+                "type": ["integer", "string"],
+                "description": "Physical page number (1-indexed) or page label as printed (e.g. \"iv\", \"A-12\"); numbers are always physical pages",
+                "minimum": 0
+            },
+            "format": {
+                "type": "string",
+                "enum": ["markdown", "csv", "json"],
+                "description": "Text content format: \"markdown\" (default), \"csv\" or \"json\"; the structured output always holds all three"
+            },
+            "password": {
+                "type": "string",
+                "description": "Password for encrypted documents (user or owner password)"
+            }
+        },
+        "required": ["file_path", "page"],
+        "title": "ExtractPdfTablesParams"
+    });
+    let options = text_options_schema(&["word_gap"]);
+    schema["properties"].as_object_mut().unwrap().extend(options);
+    Arc::new(schema.as_object().unwrap().clone())
+}
+
+/// Create a custom schema for pdf_to_markdown without $schema field
+fn pdf_to_markdown_schema() -> Arc<serde_json::Map<String, serde_json::Value>> {
+    let mut schema = json!({
+        "type": "object",
+        "description": "Parameters for the pdf_to_markdown tool",
+        "properties": {
+            "file_path": {
+                "type": "string",
+                "description": "Absolute path to the PDF file (relative paths are not supported)"
+            },
+            "pages": {
+                "type": "string",
+                "description": "Page selector, e.g. \"1-3,7,10-\", \"last\", \"-5\" (last five), \"odd\" or \"even\"; page labels are accepted too; numbers are always physical pages; all pages when omitted"
+            },
+            "page_anchors": {
+                "type": "boolean",
+                "description": "Start every page with an HTML anchor <a id=\"page-N\"></a> (default true)"
+            },
+            "password": {
+                "type": "string",
+                "description": "Password for encrypted documents (user or owner password)"
+            }
+        },
+        "required": ["file_path"],
+        "title": "PdfToMarkdownParams"
+    });
+    let options = text_options_schema(&["word_gap", "bidi", "normalize", "strip_furniture"]);
+    schema["properties"].as_object_mut().unwrap().extend(options);
+    Arc::new(schema.as_object().unwrap().clone())
+}
+
+/// Create a custom schema for export_pdf_html without $schema field
+fn export_pdf_html_schema() -> Arc<serde_json::Map<String, serde_json::Value>> {
+    let mut schema = json!({
+        "type": "object",
+        "description": "Parameters for the export_pdf_html tool",
+        "properties": {
+            "file_path": {
+                "type": "string",
+                "description": "Absolute path to the PDF file (relative paths are not supported)"
+            },
+            "output_path": {
+                "type": "string",
+                "description": "Absolute path of the HTML file to write; an existing file is overwritten"
+            },
+            "pages": {
+                "type": "string",
+                "description": "Page selector, e.g. \"1-3,7,10-\", \"last\", \"-5\" (last five), \"odd\" or \"even\"; page labels are accepted too; numbers are always physical pages; all pages when omitted"
+            },
+            "password": {
+                "type": "string",
+                "description": "Password for encrypted documents (user or owner password)"
+            }
+        },
+        "required": ["file_path", "output_path"],
+        "title": "ExportPdfHtmlParams"
+    });
+    let options = text_options_schema(&["word_gap", "bidi", "normalize", "strip_furniture"]);
+    schema["properties"].as_object_mut().unwrap().extend(options);
+    Arc::new(schema.as_object().unwrap().clone())
+}
+
+/// Create a custom schema for get_pdf_links without $schema field
+fn get_pdf_links_schema() -> Arc<serde_json::Map<String, serde_json::Value>> {
+    let mut schema = json!({
+        "type": "object",
+        "description": "Parameters for the get_pdf_links tool",
+        "properties": {
+            "file_path": {
+                "type": "string",
+                "description": "Absolute path to the PDF file (relative paths are not supported)"
+            },
+            "pages": {
+                "type": "string",
+                "description": "Page selector, e.g. \"1-3,7,10-\", \"last\", \"-5\" (last five), \"odd\" or \"even\"; page labels are accepted too; numbers are always physical pages; all pages when omitted"
+            },
+            "password": {
+                "type": "string",
+                "description": "Password for encrypted documents (user or owner password)"
+            }
+        },
+        "required": ["file_path"],
+        "title": "GetPdfLinksParams"
+    });
+    let options = text_options_schema(&["word_gap"]);
+    schema["properties"].as_object_mut().unwrap().extend(options);
+    Arc::new(schema.as_object().unwrap().clone())
+}
+
+/// Create the output schema of get_pdf_links
+fn links_output_schema() -> Arc<serde_json::Map<String, serde_json::Value>> {
+    let schema = json!({
+        "type": "object",
+        "description": "Link annotations of the selected pages, in page order",
+        "properties": {
+            "links": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "page": {
+                            "type": "integer",
+                            "description": "Physical page number (1-indexed) the link is on",
+                            "minimum": 1
+                        },
+                        "bbox": {
+                            "type": "object",
+                            "description": "The link's rectangle in PDF user space (points, origin at the bottom-left)",
+                            "properties": {
+                                "x0": { "type": "number" },
+                                "y0": { "type": "number" },
+                                "x1": { "type": "number" },
+                                "y1": { "type": "number" }
+                            },
+                            "required": ["x0", "y0", "x1", "y1"]
+                        },
+                        "text": {
+                            "type": "string",
+                            "description": "Text under the link in reading order; empty when the link covers no text"
+                        },
+                        "action": {
+                            "type": "object",
+                            "description": "What following the link does",
+                            "properties": {
+                                "type": {
+                                    "type": "string",
+                                    "enum": ["uri", "goto", "gotor", "launch", "other"],
+                                    "description": "\"uri\" opens a web address, \"goto\" a page of this document, \"gotor\" a destination in another PDF file, \"launch\" an application or file; \"other\" covers JavaScript, named and other actions, and links without one"
+                                },
+                                "uri": {
+                                    "type": "string",
+                                    "description": "Target of a \"uri\" link"
+                                },
+                                "page": {
+                                    "type": "integer",
+                                    "description": "Target page (1-indexed) of a \"goto\" link, or of a \"gotor\" link in the other file, when known",
+                                    "minimum": 1
+                                },
+                                "destination": {
+                                    "type": "string",
+                                    "description": "Name of the destination, for named destinations"
+                                },
+                                "file": {
+                                    "type": "string",
+                                    "description": "File of a \"gotor\" or \"launch\" link, as written in the document"
+                                },
+                                "parameters": {
+                                    "type": "string",
+                                    "description": "Parameters a \"launch\" link passes to the application"
+                                },
+                                "new_window": {
+                                    "type": "boolean",
+                                    "description": "Whether the file opens in a new window, when the link says"
+                                },
+                                "action": {
+                                    "type": "string",
+                                    "description": "PDF action type of an \"other\" link, e.g. \"JavaScript\" or \"Named\""
+                                }
+                            },
+                            "required": ["type"]
+                        }
+                    },
+                    "required": ["page", "bbox", "text", "action"]
+                }
+            }
+        },
+        "required": ["links"],
+        "title": "PdfLinks"
+    });
+    Arc::new(schema.as_object().unwrap().clone())
+}
+
+/// Create the output schema of extract_pdf_tables
+fn tables_output_schema() -> Arc<serde_json::Map<String, serde_json::Value>> {
+    let bbox = json!({
+        "type": "object",
+        "description": "Box in PDF user space (points, origin at the bottom-left)",
+        "properties": {
+            "x0": { "type": "number" },
+            "y0": { "type": "number" },
+            "x1": { "type": "number" },
+            "y1": { "type": "number" }
+        },
+        "required": ["x0", "y0", "x1", "y1"]
+    });
+    let schema = json!({
+        "type": "object",
+        "description": "Tables found on the page, top to bottom",
+        "properties": {
+            "tables": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "page": {
+                            "type": "integer",
+                            "description": "Physical page number (1-indexed)",
+                            "minimum": 1
+                        },
+                        "bbox": bbox,
+                        "method": {
+                            "type": "string",
+                            "enum": ["ruling_lines", "text_alignment"],
+                            "description": "\"ruling_lines\" when the table was found from lines drawn on the page, \"text_alignment\" when from words lining up in columns"
+                        },
+                        "rows": {
+                            "type": "integer",
+                            "minimum": 1
+                        },
+                        "columns": {
+                            "type": "integer",
+                            "minimum": 1
+                        },
+                        "header_rows": {
+                            "type": "integer",
+                            "description": "Number of leading rows that are column headings",
+                            "minimum": 0
+                        },
+                        "cells": {
+                            "type": "array",
+                            "description": "Cells row by row; merged cells span several rows or columns, and the cells cover the grid exactly once",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "row": { "type": "integer", "minimum": 0 },
+                                    "column": { "type": "integer", "minimum": 0 },
+                                    "row_span": { "type": "integer", "minimum": 1 },
+                                    "column_span": { "type": "integer", "minimum": 1 },
+                                    "text": { "type": "string" },
+                                    "bbox": bbox
+                                },
+                                "required": ["row", "column", "row_span", "column_span", "text", "bbox"]
+                            }
+                        },
+                        "csv": {
+                            "type": "string",
+                            "description": "The table as comma-separated values; merged cells fill their first position"
+                        },
+                        "markdown": {
+                            "type": "string",
+                            "description": "The table as a Markdown pipe table with the header rows as its heading"
+                        }
+                    },
+                    "required": ["page", "bbox", "method", "rows", "columns", "header_rows", "cells", "csv", "markdown"]
+                }
+            }
+        },
+        "required": ["tables"],
+        "title": "PageTables"
+    });
+    Arc::new(schema.as_object().unwrap().clone())
+}
+
+/// Create the output schema shared by the text extraction tools
+fn page_texts_output_schema() -> Arc<serde_json::Map<String, serde_json::Value>> {
+    let schema = json!({
+        "type": "object",
+        "description": "Extracted text, one record per page in selection order",
+        "properties": {
+            "pages": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "page": {
+                            "type": "integer",
+                            "description": "Physical page number (1-indexed)",
+                            "minimum": 1
+                        },
+                        "label": {
+                            "type": ["string", "null"],
+                            "description": "Page label, when the document defines page labels"
+                        },
+                        "text": {
+                            "type": "string",
+                            "description": "Extracted text (empty when extraction failed)"
+                        },
+                        "status": {
+                            "type": "string",
+                            "enum": ["ok", "empty", "error"],
+                            "description": "\"ok\" when text was extracted, \"empty\" when the page holds no text, \"error\" when extraction failed"
+                        },
+                        "error": {
+                            "type": ["object", "null"],
+                            "description": "Why extraction failed, for pages with status \"error\"",
+                            "properties": {
+                                "code": {
+                                    "type": "string",
+                                    "description": "Machine-readable error code, e.g. \"missing_object\""
+                                },
+                                "message": {
+                                    "type": "string",
+                                    "description": "Human-readable reason"
+                                }
+                            },
+                            "required": ["code", "message"]
+                        },
+                        "recovered_fonts": {
+                            "type": "array",
+                            "description": "Fonts whose text was recovered heuristically and may be inaccurate; omitted when there are none",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "font": {
+                                        "type": "string",
+                                        "description": "Base font name (the resource name for unnamed Type3 fonts)"
+                                    },
+                                    "method": {
+                                        "type": "string",
+                                        "enum": ["font_program", "type3_glyph_names"],
+                                        "description": "\"font_program\" when text came from the embedded font program's encoding, cmap or glyph names, \"type3_glyph_names\" when it came from Type3 glyph procedure names"
+                                    }
+                                },
+                                "required": ["font", "method"]
+                            }
+                        },
+                        "furniture": {
+                            "type": "array",
+                            "description": "Running headers, footers and page numbers removed from the text by strip_furniture, top to bottom; omitted when there are none",
+                            "items": {
+                                "type": "string"
+                            }
+                        }
+                    },
+                    "required": ["page", "label", "text", "status", "error"]
+                }
+            }
+        },
+        "required": ["pages"],
+        "title": "PageTexts"
+    });
+    Arc::new(schema.as_object().unwrap().clone())
+}
+
+/// Text options from tool parameters, with defaults for those left out
+fn text_options(params: &TextOptionsParams) -> TextOptions {
+    TextOptions {
+        layout: params.layout,
+        word_gap: params.word_gap.unwrap_or(TextOptions::DEFAULT_WORD_GAP),
+        bidi: params.bidi,
+        normalization: params.normalize,
+        strip_furniture: params.strip_furniture,
+    }
+}
+
+/// Structured content of the text extraction tools
+#[derive(Serialize)]
+struct PageTexts<'a> {
+    pages: &'a [PageText],
+}
+
+/// Build the result of a text extraction tool
+///
+/// The page records are always returned as structured content; `format` selects whether
+/// the text content holds the concatenated text or the same records as JSON.
+fn page_texts_result(records: Vec<PageText>, format: OutputFormat) -> Result<CallToolResult, McpError> {
+    let output = PageTexts { pages: &records };
+    let serialization_error = |e: serde_json::Error| {
+        McpError::internal_error(format!("JSON serialization failed: {}", e), None)
+    };
+    
+    let text = match format {
+        OutputFormat::Text => pdf_reader::join_page_texts(&records),
+        OutputFormat::Json => serde_json::to_string_pretty(&output).map_err(serialization_error)?,
+    };
+    Ok(CallToolResult {
+        content: vec![Content::text(text)],
+        structured_content: Some(serde_json::to_value(&output).map_err(serialization_error)?),
+        is_error: Some(false),
+        meta: None,
+    })
+}
+
+/// Structured content of the extract_pdf_tables tool
+#[derive(Serialize)]
+struct PageTables<'a> {
+    tables: &'a [Table],
+}
+
+/// Structured content of the get_pdf_links tool
+#[derive(Serialize)]
+struct PdfLinks<'a> {
+    links: &'a [Link],
+}
+
+/// Build the result of the extract_pdf_tables tool
+fn tables_result(tables: Vec<Table>, format: TableFormat) -> Result<CallToolResult, McpError> {
+    let output = PageTables { tables: &tables };
+    let serialization_error = |e: serde_json::Error| {
+        McpError::internal_error(format!("JSON serialization failed: {}", e), None)
+    };
+
+    let text = match format {
+        _ if tables.is_empty() && format != TableFormat::Json => "No tables found on this page".to_string(),
+        TableFormat::Markdown => tables
+            .iter()
+            .enumerate()
+            .map(|(index, table)| {
+                format!(
+                    "Table {} (page {}, {} rows x {} columns)\n\n{}",
+                    index + 1,
+                    table.page,
+                    table.rows,
+                    table.columns,
+                    table.markdown
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        TableFormat::Csv => tables.iter().map(|table| table.csv.as_str()).collect::<Vec<_>>().join("\n"),
+        TableFormat::Json => serde_json::to_string_pretty(&output).map_err(serialization_error)?,
+    };
+    Ok(CallToolResult {
+        content: vec![Content::text(text)],
+        structured_content: Some(serde_json::to_value(&output).map_err(serialization_error)?),
+        is_error: Some(false),
+        meta: None,
+    })
+}
+
 /// PDF Reader MCP Service that exposes PDF reading tools
 #[derive(Clone)]
 pub struct PdfReaderService {
@@ -149,52 +966,214 @@ impl PdfReaderService {
     }
 
     /// Extract all text content from a PDF file
-    #[tool(description = "Extract all text content from a PDF file", input_schema = read_pdf_schema())]
+    #[tool(description = "Extract all text content from a PDF file, optionally limited to a page selection", input_schema = read_pdf_schema(), output_schema = page_texts_output_schema())]
     async fn read_pdf(
         &self,
         params: Parameters<ReadPdfParams>,
     ) -> Result<CallToolResult, McpError> {
-        let text = PdfReader::extract_text(&params.0.file_path).map_err(McpError::from)?;
-        Ok(CallToolResult::success(vec![Content::text(text)]))
+        let params = params.0;
+        let selection = match params.pages {
+            Some(pages) => PageSelection::Pages(pages),
+            None => PageSelection::All,
+        };
+        let options = text_options(&params.text);
+        let records = PdfReader::extract_page_texts(&params.file_path, &selection, &options, params.password.as_deref())
+            .map_err(McpError::from)?;
+        page_texts_result(records, params.format)
     }
 
     /// Extract text content from a specific page of a PDF file
-    #[tool(description = "Extract text content from a specific page of a PDF file", input_schema = read_pdf_page_schema())]
+    #[tool(description = "Extract text content from a specific page of a PDF file", input_schema = read_pdf_page_schema(), output_schema = page_texts_output_schema())]
     async fn read_pdf_page(
         &self,
         params: Parameters<ReadPdfPageParams>,
     ) -> Result<CallToolResult, McpError> {
-        let text = PdfReader::extract_page_text(&params.0.file_path, params.0.page)
-            .map_err(McpError::from)?;
-        Ok(CallToolResult::success(vec![Content::text(text)]))
+        let params = params.0;
+        let selection = PageSelection::Page(params.page);
+        let options = text_options(&params.text);
+        // A single failed page is an error unless the caller asked for per-page status
+        let strict = params.format == OutputFormat::Text;
+        let records =
+            PdfReader::read_page_texts(&params.file_path, &selection, &options, params.password.as_deref(), strict)
+                .map_err(McpError::from)?;
+        page_texts_result(records, params.format)
     }
 
-    /// Extract text content from a range of pages in a PDF file
-    #[tool(description = "Extract text content from a range of pages in a PDF file (inclusive). Ideal for distributed parsing workflows.", input_schema = read_pdf_pages_schema())]
+    /// Extract text content from selected pages of a PDF file
+    #[tool(description = "Extract text content from selected pages of a PDF file, given as a page selector (e.g. \"1-3,7,10-\", \"last\", \"-5\", \"odd\") or an inclusive start_page/end_page range. Ideal for distributed parsing workflows.", input_schema = read_pdf_pages_schema(), output_schema = page_texts_output_schema())]
     async fn read_pdf_pages(
         &self,
         params: Parameters<ReadPdfPagesParams>,
     ) -> Result<CallToolResult, McpError> {
-        let text = PdfReader::extract_page_range_text(
-            &params.0.file_path,
-            params.0.start_page,
-            params.0.end_page,
-        )
-        .map_err(McpError::from)?;
-        Ok(CallToolResult::success(vec![Content::text(text)]))
+        let params = params.0;
+        let selection = match (params.pages, params.start_page, params.end_page) {
+            (Some(pages), None, None) => PageSelection::Pages(pages),
+            (None, Some(start_page), Some(end_page)) => PageSelection::Range(start_page, end_page),
+            _ => {
+                return Err(McpError::invalid_params(
+                    "Provide either 'pages' or both 'start_page' and 'end_page'",
+                    None,
+                ));
+            }
+        };
+        let options = text_options(&params.text);
+        let records = PdfReader::extract_page_texts(&params.file_path, &selection, &options, params.password.as_deref())
+            .map_err(McpError::from)?;
+        page_texts_result(records, params.format)
     }
 
     /// Get PDF document metadata and page count
-    #[tool(description = "Get PDF document metadata and page count", input_schema = get_pdf_info_schema())]
+    #[tool(description = "Get PDF document metadata, page count, encryption settings and permissions", input_schema = get_pdf_info_schema())]
     async fn get_pdf_info(
         &self,
         params: Parameters<GetPdfInfoParams>,
     ) -> Result<CallToolResult, McpError> {
-        let info = PdfReader::get_info(&params.0.file_path).map_err(McpError::from)?;
+        let info = PdfReader::get_info(&params.0.file_path, params.0.password.as_deref()).map_err(McpError::from)?;
         let json = serde_json::to_string_pretty(&info)
             .map_err(|e| McpError::internal_error(format!("JSON serialization failed: {}", e), None))?;
         Ok(CallToolResult::success(vec![Content::text(json)]))
     }
+
+    /// Get the document outline (bookmarks) with target pages
+    #[tool(description = "Get the document outline (table of contents / bookmarks) as a nested tree with titles, levels and 1-indexed target pages", input_schema = get_pdf_outline_schema())]
+    async fn get_pdf_outline(
+        &self,
+        params: Parameters<GetPdfOutlineParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let outline = PdfReader::get_outline(&params.0.file_path, params.0.password.as_deref())
+            .map_err(McpError::from)?;
+        let json = serde_json::to_string_pretty(&outline)
+            .map_err(|e| McpError::internal_error(format!("JSON serialization failed: {}", e), None))?;
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    /// Extract text content of a section identified by its outline entry
+    #[tool(description = "Extract text content of a document section by bookmark title or outline path (e.g. \"Chapter 3 > Installation\"). Returns the pages from the entry's target page up to the page before the next sibling entry.", input_schema = read_pdf_section_schema(), output_schema = page_texts_output_schema())]
+    async fn read_pdf_section(
+        &self,
+        params: Parameters<ReadPdfSectionParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let params = params.0;
+        let selection = PageSelection::Section(params.section);
+        let options = text_options(&params.text);
+        let records = PdfReader::extract_page_texts(&params.file_path, &selection, &options, params.password.as_deref())
+            .map_err(McpError::from)?;
+        page_texts_result(records, params.format)
+    }
+
+    /// Extract the words and lines of a page with their bounding boxes
+    #[tool(description = "Get the positioned text of a page: lines and words with bounding boxes in PDF user space (points, origin at the bottom-left), font names and sizes, and optionally per-glyph boxes. Use it to locate or cite regions of a page.", input_schema = read_pdf_page_layout_schema())]
+    async fn read_pdf_page_layout(
+        &self,
+        params: Parameters<ReadPdfPageLayoutParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let params = params.0;
+        let layout = PdfReader::extract_page_layout(
+            &params.file_path,
+            params.page,
+            params.include_glyphs,
+            &text_options(&params.text),
+            params.password.as_deref(),
+        )
+        .map_err(McpError::from)?;
+        let json = serde_json::to_string_pretty(&layout)
+            .map_err(|e| McpError::internal_error(format!("JSON serialization failed: {}", e), None))?;
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    /// Find the tables of a page
+    #[tool(description = "Find the tables on a page, from ruling lines where the page draws them and from text alignment where it does not. Returns each table as a JSON cell grid (merged cells with row and column spans, header rows, page and bounding boxes), as CSV and as Markdown.", input_schema = extract_pdf_tables_schema(), output_schema = tables_output_schema())]
+    async fn extract_pdf_tables(
+        &self,
+        params: Parameters<ExtractPdfTablesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let params = params.0;
+        let tables = PdfReader::extract_tables(
+            &params.file_path,
+            params.page,
+            &text_options(&params.text),
+            params.password.as_deref(),
+        )
+        .map_err(McpError::from)?;
+        tables_result(tables, params.format)
+    }
+
+    /// Convert a PDF file to Markdown
+    #[tool(description = "Convert a PDF file, or a page selection, to Markdown: headings inferred from font size and weight, bulleted and numbered lists, paragraphs joined across line breaks, tables as pipe tables, and an anchor at the start of every page (<a id=\"page-N\"></a>).", input_schema = pdf_to_markdown_schema())]
+    async fn pdf_to_markdown(
+        &self,
+        params: Parameters<PdfToMarkdownParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let params = params.0;
+        let selection = match params.pages {
+            Some(pages) => PageSelection::Pages(pages),
+            None => PageSelection::All,
+        };
+        let options = text_options(&params.text);
+        let markdown = PdfReader::extract_markdown(
+            &params.file_path,
+            &selection,
+            &options,
+            params.page_anchors.unwrap_or(true),
+            params.password.as_deref(),
+        )
+        .map_err(McpError::from)?;
+        Ok(CallToolResult::success(vec![Content::text(markdown)]))
+    }
+
+    /// Export a PDF file as an HTML document
+    #[tool(description = "Export a PDF file, or a page selection, as a semantic HTML document written to output_path: headings, paragraphs, lists, tables and links, with every page in a <section id=\"page-N\" data-page=\"N\"> so citations can link to #page-N.", input_schema = export_pdf_html_schema())]
+    async fn export_pdf_html(
+        &self,
+        params: Parameters<ExportPdfHtmlParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let params = params.0;
+        if !Path::new(&params.output_path).is_absolute() {
+            return Err(McpError::invalid_params(
+                format!("output_path must be an absolute path: {}", params.output_path),
+                None,
+            ));
+        }
+        let selection = match params.pages {
+            Some(pages) => PageSelection::Pages(pages),
+            None => PageSelection::All,
+        };
+        let options = text_options(&params.text);
+        let html = PdfReader::export_html(&params.file_path, &selection, &options, params.password.as_deref())
+            .map_err(McpError::from)?;
+        std::fs::write(&params.output_path, &html).map_err(|e| McpError::from(PdfError::from(e)))?;
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Wrote {} bytes of HTML to {}",
+            html.len(),
+            params.output_path
+        ))]))
+    }
+
+    /// List the link annotations of a PDF file
+    #[tool(description = "List the link annotations of a PDF file, or of a page selection: web and mail links (URI), links to pages of the document (GoTo, resolved to page numbers), links to other PDF files (GoToR) and launch links, each with its page, rectangle and the text under it.", input_schema = get_pdf_links_schema(), output_schema = links_output_schema())]
+    async fn get_pdf_links(
+        &self,
+        params: Parameters<GetPdfLinksParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let params = params.0;
+        let selection = match params.pages {
+            Some(pages) => PageSelection::Pages(pages),
+            None => PageSelection::All,
+        };
+        let options = text_options(&params.text);
+        let links = PdfReader::extract_links(&params.file_path, &selection, &options, params.password.as_deref())
+            .map_err(McpError::from)?;
+        let output = PdfLinks { links: &links };
+        let serialization_error = |e: serde_json::Error| {
+            McpError::internal_error(format!("JSON serialization failed: {}", e), None)
+        };
+        Ok(CallToolResult {
+            content: vec![Content::text(serde_json::to_string_pretty(&output).map_err(serialization_error)?)],
+            structured_content: Some(serde_json::to_value(&output).map_err(serialization_error)?),
+            is_error: Some(false),
+            meta: None,
+        })
+    }
 }
 
 #[tool_handler]
@@ -215,8 +1194,16 @@ impl rmcp::ServerHandler for PdfReaderService {
             instructions: Some(
                 "PDF Reader MCP Server provides tools for extracting text and metadata from PDF files. \
                 Use 'read_pdf' to extract all text, 'read_pdf_page' to extract text from a specific page, \
-                'read_pdf_pages' to extract text from a range of pages (ideal for distributed parsing), \
-                or 'get_pdf_info' to get document metadata and page count.".to_string()
+                'read_pdf_pages' to extract text from a page selection such as '1-3,7,10-' (ideal for distributed parsing), \
+                'get_pdf_info' to get document metadata and page count, \
+                'get_pdf_outline' to get the table of contents with target pages, \
+                'read_pdf_section' to extract a section by bookmark title or outline path, \
+                'read_pdf_page_layout' to get the words and lines of a page with their bounding boxes, \
+                'extract_pdf_tables' to get the tables of a page as CSV, Markdown and cell grids, \
+                'pdf_to_markdown' to convert a document to Markdown with headings, lists and tables, \
+                'export_pdf_html' to write a document as HTML with an anchor for every page, \
+                or 'get_pdf_links' to list the links of a document with their targets and anchor text. \
+                Pass 'password' to any tool to open password-protected documents.".to_string()
             ),
         }
     }
@@ -0,0 +1,368 @@
+//! Tagged PDF: the structure tree and text in its logical order
+//!
+//! The `/StructTreeRoot` of a tagged document holds structure elements with roles such as
+//! `H1`, `P`, `L` or `Table`, in logical reading order. Elements point at their content by
+//! marked-content identifier (MCID) on a page, and may replace it with `/ActualText` or
+//! describe a figure with `/Alt`. Custom roles are mapped to standard ones through the
+//! `/RoleMap`. Content of form XObjects referenced with `/Stm`, and annotations, are not
+//! followed.
+//!
+//! The tree is flattened into blocks, each read as one line labeled with its role: block
+//! level elements start a block, inline elements such as spans and links add to the block
+//! around them. A list item keeps its label and body on one line, and a table row its
+//! cells, separated by `|`; lists and tables nested in them start blocks of their own.
+
+use crate::content::PositionedGlyph;
+use crate::layout;
+use crate::pdf_reader::TextOptions;
+use crate::reading_order;
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use std::collections::{HashMap, HashSet};
+
+/// Upper bound on the depth of the structure tree, guarding against malformed trees
+const MAX_STRUCTURE_DEPTH: usize = 256;
+
+/// Upper bound on the steps of a role mapping, guarding against cycles
+const MAX_ROLE_MAPPINGS: usize = 8;
+
+/// Standard structure types of PDF 1.7 and 2.0, at which role mapping stops
+const STANDARD_ROLES: [&str; 55] = [
+    "Document", "DocumentFragment", "Part", "Art", "Sect", "Div", "Aside", "BlockQuote", "Caption", "TOC", "TOCI",
+    "Index", "NonStruct", "Private", "Title", "P", "H", "H1", "H2", "H3", "H4", "H5", "H6", "L", "LI", "Lbl",
+    "LBody", "Table", "TR", "TH", "TD", "THead", "TBody", "TFoot", "Span", "Quote", "Note", "FENote", "Reference",
+    "BibEntry", "Code", "Link", "Annot", "Ruby", "RB", "RT", "RP", "Warichu", "WT", "WP", "Figure", "Formula",
+    "Form", "Em", "Strong",
+];
+
+/// Roles whose elements add to the block of their parent rather than starting their own
+const INLINE_ROLES: [&str; 20] = [
+    "Span", "Quote", "Note", "FENote", "Reference", "BibEntry", "Code", "Link", "Annot", "Ruby", "RB", "RT", "RP",
+    "Warichu", "WT", "WP", "Lbl", "LBody", "Em", "Strong",
+];
+
+/// Roles whose elements keep all their content on one line
+const ROW_ROLES: [&str; 2] = ["LI", "TR"];
+
+/// Roles that start blocks of their own even inside a list item or table row
+const NESTED_ROLES: [&str; 2] = ["L", "Table"];
+
+/// Roles whose `/Alt` text stands in for their content
+const DESCRIBED_ROLES: [&str; 3] = ["Figure", "Formula", "Form"];
+
+/// A part of a block's text
+enum Piece {
+    /// The glyphs of a marked-content sequence on a page
+    Content { page: u32, mcid: u32 },
+    /// Replacement or alternate text of an element, on the element's page
+    Text { page: Option<u32>, text: String },
+    /// The start of the next cell of a table row
+    Cell,
+}
+
+/// Content of a structure element read as one line
+struct Block {
+    role: String,
+    pieces: Vec<Piece>,
+}
+
+/// The structure tree of a tagged document, flattened into blocks in logical order
+pub(crate) struct StructureTree {
+    blocks: Vec<Block>,
+    /// Indices of the blocks with content on each page
+    pages: HashMap<u32, Vec<usize>>,
+}
+
+/// Reads structure elements into blocks
+struct TreeReader<'a> {
+    doc: &'a Document,
+    role_map: Option<&'a Dictionary>,
+    page_numbers: HashMap<ObjectId, u32>,
+    visited: HashSet<ObjectId>,
+    blocks: Vec<Block>,
+}
+
+/// Whether a document is tagged: it has a structure tree with at least one element
+pub(crate) fn is_tagged(doc: &Document) -> bool {
+    structure_root(doc).is_some_and(|root| root.has(b"K"))
+}
+
+/// Read the structure tree of a tagged document
+///
+/// Returns `None` for untagged documents and for trees that point at no content.
+pub(crate) fn read_structure(doc: &Document) -> Option<StructureTree> {
+    let root = structure_root(doc)?;
+    let role_map = root
+        .get(b"RoleMap")
+        .ok()
+        .and_then(|map| doc.dereference(map).ok())
+        .and_then(|(_, map)| map.as_dict().ok());
+    let mut reader = TreeReader {
+        doc,
+        role_map,
+        page_numbers: doc.get_pages().into_iter().map(|(number, id)| (id, number)).collect(),
+        visited: HashSet::new(),
+        blocks: Vec::new(),
+    };
+    let mut block = Block { role: "Document".to_string(), pieces: Vec::new() };
+    reader.read_kids(root.get(b"K").ok()?, None, &mut block, false, 0);
+    reader.finish(block);
+
+    let mut pages: HashMap<u32, Vec<usize>> = HashMap::new();
+    for (index, block) in reader.blocks.iter().enumerate() {
+        let mut block_pages: Vec<u32> = block
+            .pieces
+            .iter()
+            .filter_map(|piece| match piece {
+                Piece::Content { page, .. } | Piece::Text { page: Some(page), .. } => Some(*page),
+                _ => None,
+            })
+            .collect();
+        block_pages.dedup();
+        for page in block_pages {
+            let indices = pages.entry(page).or_default();
+            if indices.last() != Some(&index) {
+                indices.push(index);
+            }
+        }
+    }
+    (!pages.is_empty()).then_some(StructureTree { blocks: reader.blocks, pages })
+}
+
+/// The `/StructTreeRoot` dictionary of the catalog
+fn structure_root(doc: &Document) -> Option<&Dictionary> {
+    let root = doc.catalog().ok()?.get(b"StructTreeRoot").ok()?;
+    doc.dereference(root).ok()?.1.as_dict().ok()
+}
+
+impl StructureTree {
+    /// The text of the blocks with content on a page, one line each labeled with its role
+    /// (`[H1] Introduction`)
+    ///
+    /// `glyphs` are the page's glyphs in content order. Returns `None` when no block has
+    /// content on the page.
+    pub(crate) fn page_text(&self, page: u32, glyphs: &[PositionedGlyph], options: &TextOptions) -> Option<String> {
+        let indices = self.pages.get(&page)?;
+        let mut sequences: HashMap<u32, Vec<&PositionedGlyph>> = HashMap::new();
+        for glyph in glyphs {
+            if let Some(mcid) = glyph.mcid {
+                sequences.entry(mcid).or_default().push(glyph);
+            }
+        }
+
+        let lines: Vec<String> = indices
+            .iter()
+            .filter_map(|&index| {
+                let block = &self.blocks[index];
+                let text = block_text(block, page, &sequences, options);
+                (!text.is_empty()).then(|| format!("[{}] {}", block.role, text))
+            })
+            .collect();
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    }
+}
+
+impl<'a> TreeReader<'a> {
+    /// Read the kids (`/K`) of an element into `block`
+    ///
+    /// `page` is the page content is on unless a kid says otherwise; `in_row` holds for the
+    /// descendants of list items and table rows.
+    fn read_kids(&mut self, kids: &'a Object, page: Option<u32>, block: &mut Block, in_row: bool, depth: usize) {
+        let Some(kids) = self.resolve(kids) else {
+            return;
+        };
+        match kids {
+            Object::Array(items) => {
+                for item in items {
+                    self.read_kids(item, page, block, in_row, depth);
+                }
+            }
+            Object::Integer(mcid) => {
+                if let (Some(page), Ok(mcid)) = (page, u32::try_from(*mcid)) {
+                    block.pieces.push(Piece::Content { page, mcid });
+                }
+            }
+            Object::Dictionary(dict) => match dict.get(b"Type").and_then(Object::as_name).ok() {
+                Some(b"MCR") => {
+                    let page = self.page(dict).or(page);
+                    let mcid = dict.get(b"MCID").and_then(Object::as_i64).ok();
+                    let mcid = mcid.and_then(|mcid| u32::try_from(mcid).ok());
+                    if let (Some(page), Some(mcid), false) = (page, mcid, dict.has(b"Stm")) {
+                        block.pieces.push(Piece::Content { page, mcid });
+                    }
+                }
+                Some(b"OBJR") => {}
+                _ => self.read_element(dict, page, block, in_row, depth + 1),
+            },
+            _ => {}
+        }
+    }
+
+    /// Read a structure element: into `block` when it is inline, as blocks of its own
+    /// otherwise
+    fn read_element(&mut self, dict: &'a Dictionary, page: Option<u32>, block: &mut Block, in_row: bool, depth: usize) {
+        if depth > MAX_STRUCTURE_DEPTH {
+            return;
+        }
+        let Some(role) = self.role(dict) else {
+            return;
+        };
+        if role == "Artifact" {
+            return;
+        }
+        let page = self.page(dict).or(page);
+        let text = |key: &[u8]| dict.get(key).ok().and_then(|text| lopdf::decode_text_string(text).ok());
+        let replacement = text(b"ActualText")
+            .or_else(|| text(b"Alt").filter(|_| DESCRIBED_ROLES.contains(&role.as_str())))
+            .filter(|text| !text.trim().is_empty());
+
+        let inline = INLINE_ROLES.contains(&role.as_str()) || (in_row && !NESTED_ROLES.contains(&role.as_str()));
+        if inline {
+            if in_row && matches!(role.as_str(), "TD" | "TH") && !block.pieces.is_empty() {
+                block.pieces.push(Piece::Cell);
+            }
+            match replacement {
+                Some(text) => block.pieces.push(Piece::Text { page, text }),
+                None => {
+                    if let Ok(kids) = dict.get(b"K") {
+                        self.read_kids(kids, page, block, in_row, depth);
+                    }
+                }
+            }
+            return;
+        }
+
+        // The parent's content so far comes first; what follows continues it in a new block
+        let parent = std::mem::replace(block, Block { role: block.role.clone(), pieces: Vec::new() });
+        self.finish(parent);
+        let mut own = Block { role: role.clone(), pieces: Vec::new() };
+        match replacement {
+            Some(text) => own.pieces.push(Piece::Text { page, text }),
+            None => {
+                if let Ok(kids) = dict.get(b"K") {
+                    self.read_kids(kids, page, &mut own, ROW_ROLES.contains(&role.as_str()), depth);
+                }
+            }
+        }
+        self.finish(own);
+    }
+
+    /// Add a block unless it is empty, placing text without a page on the page of the
+    /// block's content
+    fn finish(&mut self, mut block: Block) {
+        let first_page = block.pieces.iter().find_map(|piece| match piece {
+            Piece::Content { page, .. } | Piece::Text { page: Some(page), .. } => Some(*page),
+            _ => None,
+        });
+        let Some(first_page) = first_page else {
+            return;
+        };
+        for piece in &mut block.pieces {
+            if let Piece::Text { page: page @ None, .. } = piece {
+                *page = Some(first_page);
+            }
+        }
+        self.blocks.push(block);
+    }
+
+    /// Dereference an object, once per object of the tree
+    fn resolve(&mut self, object: &'a Object) -> Option<&'a Object> {
+        let (id, object) = self.doc.dereference(object).ok()?;
+        match id {
+            Some(id) if !self.visited.insert(id) => None,
+            _ => Some(object),
+        }
+    }
+
+    /// The page (1-indexed) named by an element's or content reference's `/Pg`
+    fn page(&self, dict: &Dictionary) -> Option<u32> {
+        let id = dict.get(b"Pg").and_then(Object::as_reference).ok()?;
+        self.page_numbers.get(&id).copied()
+    }
+
+    /// The role of an element, mapped to a standard role where the role map allows
+    fn role(&self, dict: &Dictionary) -> Option<String> {
+        let mut role = dict.get(b"S").and_then(Object::as_name).ok()?;
+        for _ in 0..MAX_ROLE_MAPPINGS {
+            if STANDARD_ROLES.iter().any(|standard| standard.as_bytes() == role) {
+                break;
+            }
+            match self.role_map.and_then(|map| map.get(role).and_then(Object::as_name).ok()) {
+                Some(mapped) => role = mapped,
+                None => break,
+            }
+        }
+        Some(String::from_utf8_lossy(role).into_owned())
+    }
+}
+
+/// The text of a block's pieces on a page
+///
+/// Glyphs are taken in content order; a space goes where the gap to the previous glyph is
+/// wider than `word_gap` times the font's space width, and where a new line starts. A word
+/// hyphenated at a line break is joined with its rest, without the hyphen when
+/// dehyphenation is enabled and the rest starts in lowercase.
+fn block_text(
+    block: &Block,
+    page: u32,
+    sequences: &HashMap<u32, Vec<&PositionedGlyph>>,
+    options: &TextOptions,
+) -> String {
+    let mut text = String::new();
+    let mut previous: Option<&PositionedGlyph> = None;
+    let separate = |text: &mut String| {
+        if !text.is_empty() && !text.ends_with(char::is_whitespace) {
+            text.push(' ');
+        }
+    };
+    for piece in &block.pieces {
+        match piece {
+            Piece::Cell => {
+                text.truncate(text.trim_end().len());
+                text.push_str(" | ");
+                previous = None;
+            }
+            Piece::Text { page: Some(on), text: replacement } if *on == page => {
+                separate(&mut text);
+                text.push_str(replacement.trim());
+                previous = None;
+            }
+            Piece::Content { page: on, mcid } if *on == page => {
+                for &glyph in sequences.get(mcid).into_iter().flatten() {
+                    // Glyphs whose text a replacement took still place the next one
+                    if glyph.text.is_empty() {
+                        previous = Some(glyph);
+                        continue;
+                    }
+                    if glyph.text.trim().is_empty() {
+                        separate(&mut text);
+                        previous = Some(glyph);
+                        continue;
+                    }
+                    match previous {
+                        Some(previous) => {
+                            let (ux, uy) = layout::direction(previous);
+                            let (dx, dy) = (glyph.x - previous.end_x, glyph.y - previous.end_y);
+                            let (along, across) = (dx * ux + dy * uy, (dy * ux - dx * uy).abs());
+                            let size = previous.font_size.max(glyph.font_size);
+                            let new_line = across > layout::LINE_SHIFT * size || along < -size;
+                            if new_line && reading_order::ends_hyphenated(&text) {
+                                if options.normalization.dehyphenate && glyph.text.starts_with(char::is_lowercase) {
+                                    text.pop();
+                                }
+                            } else if new_line || along > options.word_gap * previous.space_width {
+                                separate(&mut text);
+                            }
+                        }
+                        None => separate(&mut text),
+                    }
+                    text.push_str(&glyph.text);
+                    previous = Some(glyph);
+                }
+            }
+            _ => {}
+        }
+    }
+    let text = text.trim();
+    // Rows of empty cells leave only separators
+    if text.chars().all(|c| c == '|' || c.is_whitespace()) { String::new() } else { text.to_string() }
+}
@@ -0,0 +1,697 @@
+//! Tables: cell grids found from ruling lines or from the alignment of text
+//!
+//! Ruled tables come first. Horizontal and vertical rulings that meet form a grid whose
+//! lines are the column and row boundaries; where the line between two neighbouring grid
+//! cells is missing, they are merged into one spanning cell. Text outside ruled tables is
+//! then split into rows by baseline and into cells at wide gaps, and runs of rows whose
+//! cells line up in two or more columns become tables as well. Leading rows are taken for
+//! header rows when they span columns, hold no numbers above a body that does, are bold
+//! above a body that is not, or leave the first column empty above rows that fill it.
+
+use crate::content::{PositionedGlyph, Ruling};
+use crate::layout::{self, BoundingBox};
+use crate::pdf_reader::TextOptions;
+use serde::{Deserialize, Serialize};
+
+/// Distance, in points, within which rulings meet or lie on one line
+const SNAP: f64 = 2.0;
+
+/// Gap between words, as a fraction of the font size, that separates the cells of a row in
+/// tables without rulings
+const CELL_GAP: f64 = 1.0;
+
+/// Largest step between the baselines of neighbouring rows of a table without rulings, as a
+/// fraction of the smaller font size, so that a title above a table stays out of it
+const MAX_ROW_STEP: f64 = 2.5;
+
+/// Fewest rows of two or more cells that make a table without rulings
+const MIN_ALIGNED_ROWS: usize = 3;
+
+/// Most consecutive rows of a single cell, such as section labels, inside a table without
+/// rulings
+const MAX_LABEL_ROWS: usize = 2;
+
+/// Median number of words per cell above which aligned rows are columns of prose instead
+const MAX_CELL_WORDS: usize = 4;
+
+/// How a table was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TableMethod {
+    /// From horizontal and vertical lines drawn on the page
+    RulingLines,
+    /// From words lining up in columns
+    TextAlignment,
+}
+
+/// A cell of a table; merged cells span several rows or columns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableCell {
+    /// Row of the cell's top-left corner (0-indexed)
+    pub row: usize,
+    /// Column of the cell's top-left corner (0-indexed)
+    pub column: usize,
+    pub row_span: usize,
+    pub column_span: usize,
+    /// Words of the cell, line by line, joined by single spaces
+    pub text: String,
+    pub bbox: BoundingBox,
+}
+
+/// A table found on a page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Table {
+    /// Physical page number (1-indexed)
+    pub page: u32,
+    pub bbox: BoundingBox,
+    pub method: TableMethod,
+    pub rows: usize,
+    pub columns: usize,
+    /// Number of leading rows that are column headings
+    pub header_rows: usize,
+    /// Cells in order of their top-left corners, row by row; together they cover the grid
+    /// exactly once
+    pub cells: Vec<TableCell>,
+    /// The table as comma-separated values; merged cells fill their first position
+    pub csv: String,
+    /// The table as a Markdown pipe table, header rows combined into its heading
+    pub markdown: String,
+}
+
+/// A word of the page with what table building needs to know about it
+struct Word {
+    text: String,
+    bbox: [f64; 4],
+    baseline: f64,
+    font_size: f64,
+    bold: bool,
+}
+
+impl Word {
+    fn center(&self) -> (f64, f64) {
+        ((self.bbox[0] + self.bbox[2]) / 2.0, (self.bbox[1] + self.bbox[3]) / 2.0)
+    }
+}
+
+/// A cell while its table is being built: grid position, spans and the indices of its words
+struct DraftCell {
+    row: usize,
+    column: usize,
+    row_span: usize,
+    column_span: usize,
+    words: Vec<usize>,
+}
+
+/// A table while it is being built
+struct Grid {
+    method: TableMethod,
+    /// Left edge of every column, then the right edge of the last
+    xs: Vec<f64>,
+    /// Top edge of every row, then the bottom edge of the last
+    ys: Vec<f64>,
+    cells: Vec<DraftCell>,
+}
+
+/// A ruling merged with the rulings that continue it: its position across the line, and
+/// where it starts and ends along it
+#[derive(Debug, Clone, Copy)]
+struct Line {
+    position: f64,
+    start: f64,
+    end: f64,
+}
+
+/// A row of words without rulings, split into cells at wide gaps
+struct TextRow {
+    baseline: f64,
+    font_size: f64,
+    /// Indices of the words of each cell, left to right
+    cells: Vec<Vec<usize>>,
+}
+
+/// Find the tables of a page
+pub(crate) fn find_tables(
+    glyphs: &mut [PositionedGlyph],
+    rulings: &[Ruling],
+    page: u32,
+    options: &TextOptions,
+) -> Vec<Table> {
+    let words = page_words(glyphs, options);
+
+    let mut grids = ruled_grids(rulings, &words);
+    let free: Vec<usize> = (0..words.len())
+        .filter(|&index| {
+            let (x, y) = words[index].center();
+            !grids.iter().any(|grid| {
+                let (x0, x1) = (grid.xs[0], grid.xs[grid.xs.len() - 1]);
+                let (y1, y0) = (grid.ys[0], grid.ys[grid.ys.len() - 1]);
+                x0 - SNAP <= x && x <= x1 + SNAP && y0 - SNAP <= y && y <= y1 + SNAP
+            })
+        })
+        .collect();
+    grids.extend(aligned_grids(&words, &free));
+    grids.sort_by(|a, b| b.ys[0].total_cmp(&a.ys[0]).then(a.xs[0].total_cmp(&b.xs[0])));
+
+    grids.into_iter().map(|grid| finish_table(grid, &words, page)).collect()
+}
+
+/// The words of a page, with their boxes
+fn page_words(glyphs: &mut [PositionedGlyph], options: &TextOptions) -> Vec<Word> {
+    layout::group_glyphs(glyphs, options)
+        .into_iter()
+        .flatten()
+        .map(|word| {
+            let bbox = word.iter().fold(word[0].bbox, |[x0, y0, x1, y1], glyph| {
+                [x0.min(glyph.bbox[0]), y0.min(glyph.bbox[1]), x1.max(glyph.bbox[2]), y1.max(glyph.bbox[3])]
+            });
+            Word {
+                text: word.iter().map(|glyph| glyph.text.as_str()).collect(),
+                bbox,
+                baseline: word[0].y,
+                font_size: word[0].font_size,
+                bold: word[0].font_name.to_ascii_lowercase().contains("bold"),
+            }
+        })
+        .collect()
+}
+
+/// Tables outlined by rulings
+///
+/// Rulings that meet are grouped; a group with at least two horizontal and two vertical
+/// lines, framing two or more rows and columns that hold some text, is a table.
+fn ruled_grids(rulings: &[Ruling], words: &[Word]) -> Vec<Grid> {
+    let horizontal = merge_lines(
+        rulings
+            .iter()
+            .filter(|ruling| ruling.is_horizontal())
+            .map(|ruling| Line { position: (ruling.y0 + ruling.y1) / 2.0, start: ruling.x0, end: ruling.x1 })
+            .collect(),
+    );
+    let vertical = merge_lines(
+        rulings
+            .iter()
+            .filter(|ruling| ruling.is_vertical())
+            .map(|ruling| Line { position: (ruling.x0 + ruling.x1) / 2.0, start: ruling.y0, end: ruling.y1 })
+            .collect(),
+    );
+
+    // Union-find over all lines, horizontal ones first
+    let mut parent: Vec<usize> = (0..horizontal.len() + vertical.len()).collect();
+    for (h, across) in horizontal.iter().enumerate() {
+        for (v, down) in vertical.iter().enumerate() {
+            if covers(across, down.position) && covers(down, across.position) {
+                union(&mut parent, h, horizontal.len() + v);
+            }
+        }
+    }
+
+    let mut groups: Vec<(usize, Vec<Line>, Vec<Line>)> = Vec::new();
+    for index in 0..parent.len() {
+        let root = find(&mut parent, index);
+        let position = match groups.iter().position(|(group, _, _)| *group == root) {
+            Some(position) => position,
+            None => {
+                groups.push((root, Vec::new(), Vec::new()));
+                groups.len() - 1
+            }
+        };
+        match index.checked_sub(horizontal.len()) {
+            None => groups[position].1.push(horizontal[index]),
+            Some(v) => groups[position].2.push(vertical[v]),
+        }
+    }
+
+    groups
+        .into_iter()
+        .filter(|(_, horizontal, vertical)| horizontal.len() >= 2 && vertical.len() >= 2)
+        .filter_map(|(_, horizontal, vertical)| ruled_grid(&horizontal, &vertical, words))
+        .collect()
+}
+
+/// The grid of one group of rulings, or `None` when it is not a table
+fn ruled_grid(horizontal: &[Line], vertical: &[Line], words: &[Word]) -> Option<Grid> {
+    let left = horizontal.iter().map(|line| line.start).chain(vertical.iter().map(|line| line.position));
+    let right = horizontal.iter().map(|line| line.end).chain(vertical.iter().map(|line| line.position));
+    let bottom = vertical.iter().map(|line| line.start).chain(horizontal.iter().map(|line| line.position));
+    let top = vertical.iter().map(|line| line.end).chain(horizontal.iter().map(|line| line.position));
+    let (x0, x1) = (left.fold(f64::INFINITY, f64::min), right.fold(f64::NEG_INFINITY, f64::max));
+    let (y0, y1) = (bottom.fold(f64::INFINITY, f64::min), top.fold(f64::NEG_INFINITY, f64::max));
+
+    // Grid lines, with the outer edges added where the frame is open
+    let xs = cluster(vertical.iter().map(|line| line.position).chain([x0, x1]).collect());
+    let mut ys = cluster(horizontal.iter().map(|line| line.position).chain([y0, y1]).collect());
+    ys.reverse();
+    if xs.len() < 3 || ys.len() < 3 {
+        return None;
+    }
+    let (rows, columns) = (ys.len() - 1, xs.len() - 1);
+
+    // Neighbouring grid cells without a ruling between them belong to one merged cell
+    let mut parent: Vec<usize> = (0..rows * columns).collect();
+    for row in 0..rows {
+        for column in 0..columns {
+            let (middle_x, middle_y) = ((xs[column] + xs[column + 1]) / 2.0, (ys[row] + ys[row + 1]) / 2.0);
+            let divided = |lines: &[Line], position: f64, along: f64| {
+                lines.iter().any(|line| (line.position - position).abs() <= SNAP && covers(line, along))
+            };
+            if column + 1 < columns && !divided(vertical, xs[column + 1], middle_y) {
+                union(&mut parent, row * columns + column, row * columns + column + 1);
+            }
+            if row + 1 < rows && !divided(horizontal, ys[row + 1], middle_x) {
+                union(&mut parent, row * columns + column, (row + 1) * columns + column);
+            }
+        }
+    }
+
+    let mut owner: Vec<Option<usize>> = vec![None; rows * columns];
+    let mut cells: Vec<DraftCell> = Vec::new();
+    for position in 0..rows * columns {
+        if owner[position].is_some() {
+            continue;
+        }
+        let root = find(&mut parent, position);
+        let members: Vec<usize> = (0..rows * columns).filter(|&other| find(&mut parent, other) == root).collect();
+        let (row, column) = (position / columns, position % columns);
+        let last_row = members.iter().map(|member| member / columns).max().unwrap_or(row);
+        let last_column = members.iter().map(|member| member % columns).max().unwrap_or(column).max(column);
+        let column = members.iter().map(|member| member % columns).min().unwrap_or(column).min(column);
+        for covered_row in row..=last_row {
+            for covered_column in column..=last_column {
+                owner[covered_row * columns + covered_column].get_or_insert(cells.len());
+            }
+        }
+        cells.push(DraftCell {
+            row,
+            column,
+            row_span: last_row - row + 1,
+            column_span: last_column - column + 1,
+            words: Vec::new(),
+        });
+    }
+
+    for (index, word) in words.iter().enumerate() {
+        let (x, y) = word.center();
+        let column = (0..columns).find(|&column| xs[column] <= x && x < xs[column + 1]);
+        let row = (0..rows).find(|&row| ys[row + 1] <= y && y < ys[row]);
+        if let (Some(row), Some(column)) = (row, column)
+            && let Some(cell) = owner[row * columns + column]
+        {
+            cells[cell].words.push(index);
+        }
+    }
+    if cells.iter().all(|cell| cell.words.is_empty()) {
+        return None;
+    }
+    cells.sort_by_key(|cell| (cell.row, cell.column));
+    Some(Grid { method: TableMethod::RulingLines, xs, ys, cells })
+}
+
+/// Tables made of words that line up in columns, among the words not in a ruled table
+fn aligned_grids(words: &[Word], free: &[usize]) -> Vec<Grid> {
+    let rows = text_rows(words, free);
+
+    let mut grids = Vec::new();
+    let mut run: Vec<&TextRow> = Vec::new();
+    let mut label_rows = 0;
+    for row in &rows {
+        let continues = run.last().is_some_and(|previous| {
+            previous.baseline - row.baseline <= MAX_ROW_STEP * previous.font_size.min(row.font_size)
+        });
+        if !continues {
+            grids.extend(aligned_grid(std::mem::take(&mut run), words));
+            label_rows = 0;
+        }
+        if row.cells.len() >= 2 {
+            run.push(row);
+            label_rows = 0;
+        } else if !run.is_empty() && label_rows < MAX_LABEL_ROWS {
+            run.push(row);
+            label_rows += 1;
+        } else {
+            grids.extend(aligned_grid(std::mem::take(&mut run), words));
+            label_rows = 0;
+        }
+    }
+    grids.extend(aligned_grid(run, words));
+    grids
+}
+
+/// Rows of words by baseline, top to bottom, each split into cells at wide gaps
+fn text_rows(words: &[Word], free: &[usize]) -> Vec<TextRow> {
+    let mut order = free.to_vec();
+    order.sort_by(|&a, &b| words[b].baseline.total_cmp(&words[a].baseline));
+
+    let mut rows: Vec<(f64, f64, Vec<usize>)> = Vec::new();
+    for index in order {
+        let word = &words[index];
+        match rows.last_mut() {
+            Some((baseline, size, row))
+                if *baseline - word.baseline < layout::LINE_SHIFT * size.max(word.font_size) =>
+            {
+                *size = size.max(word.font_size);
+                row.push(index);
+            }
+            _ => rows.push((word.baseline, word.font_size, vec![index])),
+        }
+    }
+
+    rows.into_iter()
+        .map(|(baseline, font_size, mut row)| {
+            row.sort_by(|&a, &b| words[a].bbox[0].total_cmp(&words[b].bbox[0]));
+            let mut cells: Vec<Vec<usize>> = Vec::new();
+            for index in row {
+                let word = &words[index];
+                match cells.last_mut() {
+                    Some(cell) if word.bbox[0] - words[cell[cell.len() - 1]].bbox[2] < CELL_GAP * font_size => {
+                        cell.push(index)
+                    }
+                    _ => cells.push(vec![index]),
+                }
+            }
+            TextRow { baseline, font_size, cells }
+        })
+        .collect()
+}
+
+/// The grid of a run of rows, or `None` when the rows do not make a table
+///
+/// Columns are taken from the rows with the most common number of cells, so that headings
+/// spanning several columns do not hide the gaps between them; cells of other rows are
+/// placed in the columns they overlap.
+fn aligned_grid(mut run: Vec<&TextRow>, words: &[Word]) -> Option<Grid> {
+    while run.last().is_some_and(|row| row.cells.len() < 2) {
+        run.pop();
+    }
+    let aligned: Vec<&TextRow> = run.iter().copied().filter(|row| row.cells.len() >= 2).collect();
+    if aligned.len() < MIN_ALIGNED_ROWS {
+        return None;
+    }
+
+    let cell_words = layout::median(aligned.iter().flat_map(|row| row.cells.iter().map(|cell| cell.len() as f64)));
+    if cell_words.is_some_and(|median| median > MAX_CELL_WORDS as f64) {
+        return None;
+    }
+
+    let extent = |cell: &[usize]| {
+        let x0 = cell.iter().map(|&index| words[index].bbox[0]).fold(f64::INFINITY, f64::min);
+        let x1 = cell.iter().map(|&index| words[index].bbox[2]).fold(f64::NEG_INFINITY, f64::max);
+        (x0, x1)
+    };
+
+    // The most common cell count, the larger on a tie
+    let mut counts: Vec<usize> = aligned.iter().map(|row| row.cells.len()).collect();
+    counts.sort_unstable();
+    let typical = counts
+        .chunk_by(|a, b| a == b)
+        .max_by_key(|chunk| (chunk.len(), chunk[0]))
+        .map(|chunk| chunk[0])
+        .unwrap_or(2);
+
+    let mut spans: Vec<(f64, f64)> = aligned
+        .iter()
+        .filter(|row| row.cells.len() == typical)
+        .flat_map(|row| row.cells.iter().map(|cell| extent(cell)))
+        .collect();
+    spans.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut columns: Vec<(f64, f64)> = Vec::new();
+    for (x0, x1) in spans {
+        match columns.last_mut() {
+            Some(column) if x0 <= column.1 => column.1 = column.1.max(x1),
+            _ => columns.push((x0, x1)),
+        }
+    }
+    if columns.len() < 2 {
+        return None;
+    }
+
+    let mut cells: Vec<DraftCell> = Vec::new();
+    for (row_index, row) in run.iter().enumerate() {
+        let first_cell = cells.len();
+        for cell in &row.cells {
+            let (x0, x1) = extent(cell);
+            let overlapped: Vec<usize> = (0..columns.len())
+                .filter(|&column| x0 < columns[column].1 && columns[column].0 < x1)
+                .collect();
+            let (first, last) = match (overlapped.first(), overlapped.last()) {
+                (Some(&first), Some(&last)) => (first, last),
+                _ => {
+                    // In a gap between columns: the nearest column
+                    let middle = (x0 + x1) / 2.0;
+                    let distance = |column: &(f64, f64)| (column.0 - middle).abs().min((column.1 - middle).abs());
+                    let nearest = (0..columns.len())
+                        .min_by(|&a, &b| distance(&columns[a]).total_cmp(&distance(&columns[b])))
+                        .expect("a table has columns");
+                    (nearest, nearest)
+                }
+            };
+            // Cells that land in a column already taken in this row join its cell
+            match cells[first_cell..].iter_mut().find(|draft| draft.column + draft.column_span > first) {
+                Some(draft) => {
+                    draft.words.extend(cell);
+                    draft.column_span = draft.column_span.max(last + 1 - draft.column);
+                }
+                None => cells.push(DraftCell {
+                    row: row_index,
+                    column: first,
+                    row_span: 1,
+                    column_span: last - first + 1,
+                    words: cell.clone(),
+                }),
+            }
+        }
+    }
+
+    // Column edges halfway between neighbouring columns, row edges halfway between rows
+    let left = run.iter().flat_map(|row| row.cells.iter().map(|cell| extent(cell).0)).fold(columns[0].0, f64::min);
+    let right = run
+        .iter()
+        .flat_map(|row| row.cells.iter().map(|cell| extent(cell).1))
+        .fold(columns[columns.len() - 1].1, f64::max);
+    let mut xs = vec![left];
+    xs.extend(columns.windows(2).map(|pair| (pair[0].1 + pair[1].0) / 2.0));
+    xs.push(right);
+
+    let vertical_extent = |row: &TextRow| {
+        let words = row.cells.iter().flatten().map(|&index| &words[index]);
+        words.fold((f64::INFINITY, f64::NEG_INFINITY), |(y0, y1), word| (y0.min(word.bbox[1]), y1.max(word.bbox[3])))
+    };
+    let mut ys = vec![vertical_extent(run[0]).1];
+    ys.extend(run.windows(2).map(|pair| (vertical_extent(pair[0]).0 + vertical_extent(pair[1]).1) / 2.0));
+    ys.push(vertical_extent(run[run.len() - 1]).0);
+
+    // Positions no cell covers are empty cells
+    for row in 0..run.len() {
+        for column in 0..columns.len() {
+            let covered = cells.iter().any(|cell| {
+                cell.row == row && cell.column <= column && column < cell.column + cell.column_span
+            });
+            if !covered {
+                cells.push(DraftCell { row, column, row_span: 1, column_span: 1, words: Vec::new() });
+            }
+        }
+    }
+    cells.sort_by_key(|cell| (cell.row, cell.column));
+    Some(Grid { method: TableMethod::TextAlignment, xs, ys, cells })
+}
+
+/// Turn a grid into a table: cell texts and boxes, header rows, CSV and Markdown
+fn finish_table(grid: Grid, words: &[Word], page: u32) -> Table {
+    let (rows, columns) = (grid.ys.len() - 1, grid.xs.len() - 1);
+    let bold: Vec<Option<bool>> = grid
+        .cells
+        .iter()
+        .map(|cell| (!cell.words.is_empty()).then(|| cell.words.iter().all(|&index| words[index].bold)))
+        .collect();
+    let cells: Vec<TableCell> = grid
+        .cells
+        .iter()
+        .map(|cell| TableCell {
+            row: cell.row,
+            column: cell.column,
+            row_span: cell.row_span,
+            column_span: cell.column_span,
+            text: cell_text(&cell.words, words),
+            bbox: BoundingBox::new([
+                grid.xs[cell.column],
+                grid.ys[cell.row + cell.row_span],
+                grid.xs[cell.column + cell.column_span],
+                grid.ys[cell.row],
+            ]),
+        })
+        .collect();
+
+    let header_rows = header_rows(&cells, &bold, rows);
+    let mut grid_text = vec![vec![String::new(); columns]; rows];
+    for cell in &cells {
+        grid_text[cell.row][cell.column] = cell.text.clone();
+    }
+    // Without header rows the first row is the heading; headings spanning several columns
+    // head each of them
+    let heading_rows = header_rows.max(1);
+    let mut heading = grid_text[..heading_rows].to_vec();
+    for cell in cells.iter().filter(|cell| cell.row < header_rows) {
+        heading[cell.row][cell.column..cell.column + cell.column_span].fill(cell.text.clone());
+    }
+
+    Table {
+        page,
+        bbox: BoundingBox::new([grid.xs[0], grid.ys[rows], grid.xs[columns], grid.ys[0]]),
+        method: grid.method,
+        rows,
+        columns,
+        header_rows,
+        csv: to_csv(&grid_text),
+        markdown: to_markdown(&heading, &grid_text[heading_rows..]),
+        cells,
+    }
+}
+
+/// Text of a cell: its words line by line, top to bottom and left to right
+fn cell_text(indices: &[usize], words: &[Word]) -> String {
+    let mut order = indices.to_vec();
+    order.sort_by(|&a, &b| words[b].baseline.total_cmp(&words[a].baseline));
+    let mut lines: Vec<(f64, f64, Vec<usize>)> = Vec::new();
+    for index in order {
+        let word = &words[index];
+        match lines.last_mut() {
+            Some((baseline, size, line))
+                if *baseline - word.baseline < layout::LINE_SHIFT * size.max(word.font_size) =>
+            {
+                line.push(index)
+            }
+            _ => lines.push((word.baseline, word.font_size, vec![index])),
+        }
+    }
+    lines
+        .into_iter()
+        .flat_map(|(_, _, mut line)| {
+            line.sort_by(|&a, &b| words[a].bbox[0].total_cmp(&words[b].bbox[0]));
+            line
+        })
+        .map(|index| words[index].text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Number of leading rows that are column headings; at least one body row always remains
+fn header_rows(cells: &[TableCell], bold: &[Option<bool>], rows: usize) -> usize {
+    let filled = |row: usize| cells.iter().zip(bold).filter(move |(cell, _)| cell.row == row && !cell.text.is_empty());
+    let body_has_numbers =
+        |from: usize| cells.iter().any(|cell| cell.row >= from && cell.column > 0 && is_number(&cell.text));
+    let body_is_bold = |from: usize| {
+        cells.iter().zip(bold).filter(|(cell, _)| cell.row >= from).all(|(_, bold)| bold.unwrap_or(true))
+    };
+    let first_column_filled =
+        |from: usize| cells.iter().any(|cell| cell.row >= from && cell.column == 0 && !cell.text.is_empty());
+
+    let mut headers = 0;
+    while headers + 1 < rows && filled(headers).next().is_some() {
+        let spans_columns = filled(headers).any(|(cell, _)| cell.column_span > 1);
+        let no_numbers = filled(headers).all(|(cell, _)| !is_number(&cell.text)) && body_has_numbers(headers + 1);
+        let bold_heading = filled(headers).all(|(_, bold)| *bold == Some(true)) && !body_is_bold(headers + 1);
+        let open_corner = !filled(headers).any(|(cell, _)| cell.column == 0) && first_column_filled(headers + 1);
+        if !(spans_columns || no_numbers || bold_heading || open_corner) {
+            break;
+        }
+        headers += 1;
+    }
+    headers
+}
+
+/// Whether a cell holds a number or an amount, such as "1,204", "(84.50)", "$ 12" or "7%"
+fn is_number(text: &str) -> bool {
+    text.chars().any(|c| c.is_ascii_digit())
+        && text.chars().all(|c| c.is_ascii_digit() || " ,.%$€£¥()+-\u{2212}\u{2013}".contains(c))
+}
+
+/// The table as CSV, quoting fields that hold commas, quotes or line breaks
+fn to_csv(grid: &[Vec<String>]) -> String {
+    let field = |text: &String| {
+        if text.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", text.replace('"', "\"\""))
+        } else {
+            text.clone()
+        }
+    };
+    grid.iter().map(|row| row.iter().map(field).collect::<Vec<_>>().join(",") + "\n").collect()
+}
+
+/// The table as a Markdown pipe table
+///
+/// The heading rows are combined column by column into the one heading Markdown allows.
+/// Columns holding mostly numbers are right-aligned.
+fn to_markdown(heading: &[Vec<String>], body: &[Vec<String>]) -> String {
+    let line = |cells: &[String]| {
+        let cells: Vec<String> = cells.iter().map(|cell| cell.replace('|', "\\|").replace('\n', " ")).collect();
+        format!("| {} |\n", cells.join(" | "))
+    };
+
+    let columns = heading[0].len();
+    let combined: Vec<String> = (0..columns)
+        .map(|column| {
+            let mut parts: Vec<&str> =
+                heading.iter().map(|row| row[column].as_str()).filter(|text| !text.is_empty()).collect();
+            parts.dedup();
+            parts.join(" ")
+        })
+        .collect();
+    let alignment: Vec<String> = (0..columns)
+        .map(|column| {
+            let cells: Vec<&str> =
+                body.iter().map(|row| row[column].as_str()).filter(|text| !text.is_empty()).collect();
+            let numbers = cells.iter().filter(|text| is_number(text)).count();
+            if numbers > 0 && numbers * 2 >= cells.len() { "---:".to_string() } else { "---".to_string() }
+        })
+        .collect();
+
+    let mut markdown = line(&combined);
+    markdown.push_str(&line(&alignment));
+    for row in body {
+        markdown.push_str(&line(row));
+    }
+    markdown
+}
+
+/// Merge lines that lie on one line and overlap or touch
+fn merge_lines(mut lines: Vec<Line>) -> Vec<Line> {
+    lines.sort_by(|a, b| a.position.total_cmp(&b.position).then(a.start.total_cmp(&b.start)));
+    let mut merged: Vec<Line> = Vec::new();
+    for line in lines {
+        match merged.iter_mut().rev().find(|other| (other.position - line.position).abs() <= SNAP) {
+            Some(other) if line.start <= other.end + SNAP => other.end = other.end.max(line.end),
+            _ => merged.push(line),
+        }
+    }
+    merged
+}
+
+/// Whether a line reaches a position along its length
+fn covers(line: &Line, along: f64) -> bool {
+    line.start - SNAP <= along && along <= line.end + SNAP
+}
+
+/// Sorted positions, with positions closer than `SNAP` to the previous one dropped
+fn cluster(mut positions: Vec<f64>) -> Vec<f64> {
+    positions.sort_by(f64::total_cmp);
+    let mut clustered: Vec<f64> = Vec::new();
+    for position in positions {
+        if clustered.last().is_none_or(|last| position - last > SNAP) {
+            clustered.push(position);
+        }
+    }
+    clustered
+}
+
+fn find(parent: &mut [usize], index: usize) -> usize {
+    let mut root = index;
+    while parent[root] != root {
+        root = parent[root];
+    }
+    parent[index] = root;
+    root
+}
+
+fn union(parent: &mut [usize], a: usize, b: usize) {
+    let (a, b) = (find(parent, a), find(parent, b));
+    parent[a.max(b)] = a.min(b);
+}
@@ -0,0 +1,429 @@
+//! XMP metadata stream parsing
+//!
+//! Only the subset of XML used by XMP packets is supported: elements, attributes,
+//! namespaces, character data, CDATA sections and the predefined/numeric entities.
+
+use lopdf::Document;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+const NS_RDF: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+const NS_XML: &str = "http://www.w3.org/XML/1998/namespace";
+const NS_DC: &str = "http://purl.org/dc/elements/1.1/";
+const NS_XMP: &str = "http://ns.adobe.com/xap/1.0/";
+const NS_PDF: &str = "http://ns.adobe.com/pdf/1.3/";
+const NS_PDFAID: &str = "http://www.aiim.org/pdfa/ns/id/";
+const NS_XMP_MM: &str = "http://ns.adobe.com/xap/1.0/mm/";
+
+/// Metadata read from the catalog's `/Metadata` XMP stream
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct XmpMetadata {
+    /// `dc:title` (the `x-default` alternative when several languages are present)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// `dc:creator`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub creators: Vec<String>,
+    /// `dc:description`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// `dc:subject` keywords
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subjects: Vec<String>,
+    /// `xmp:CreateDate`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub create_date: Option<String>,
+    /// `xmp:ModifyDate`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modify_date: Option<String>,
+    /// `xmp:MetadataDate`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata_date: Option<String>,
+    /// `xmp:CreatorTool`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub creator_tool: Option<String>,
+    /// `pdf:Producer`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub producer: Option<String>,
+    /// `pdf:Keywords`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keywords: Option<String>,
+    /// `pdf:Trapped`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trapped: Option<String>,
+    /// PDF/A part claimed by `pdfaid:part`, e.g. "2" for PDF/A-2
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pdfa_part: Option<String>,
+    /// PDF/A conformance level claimed by `pdfaid:conformance`, e.g. "B"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pdfa_conformance: Option<String>,
+    /// Properties from other namespaces, keyed as `prefix:Name`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub custom: BTreeMap<String, String>,
+}
+
+/// Read and parse the document's XMP metadata stream, if it has one
+pub(crate) fn read_xmp_metadata(doc: &Document) -> Option<XmpMetadata> {
+    let catalog = doc.catalog().ok()?;
+    let (_, metadata) = doc.dereference(catalog.get(b"Metadata").ok()?).ok()?;
+    let stream = metadata.as_stream().ok()?;
+    let content = stream
+        .decompressed_content()
+        .unwrap_or_else(|_| stream.content.clone());
+
+    parse_xmp(&String::from_utf8_lossy(&content))
+}
+
+/// Parse an XMP packet
+fn parse_xmp(packet: &str) -> Option<XmpMetadata> {
+    let root = parse_xml(packet)?;
+    let mut xmp = XmpMetadata::default();
+
+    let mut descriptions = Vec::new();
+    root.find_all(NS_RDF, "Description", &mut descriptions);
+
+    for description in descriptions {
+        // Simple properties may be written as attributes of rdf:Description
+        for attr in &description.attributes {
+            if attr.namespace != NS_RDF && !attr.namespace.is_empty() && attr.namespace != NS_XML {
+                apply_property(&mut xmp, &attr.namespace, &attr.prefix, &attr.local, vec![attr.value.clone()]);
+            }
+        }
+        for property in description.elements() {
+            let values = property_values(property);
+            if !values.is_empty() {
+                apply_property(&mut xmp, &property.namespace, &property.prefix, &property.local, values);
+            }
+        }
+    }
+
+    Some(xmp)
+}
+
+/// Store one property value in the matching `XmpMetadata` field
+fn apply_property(xmp: &mut XmpMetadata, namespace: &str, prefix: &str, local: &str, values: Vec<String>) {
+    let first = values.first().cloned();
+    match (namespace, local) {
+        (NS_DC, "title") => xmp.title = first,
+        (NS_DC, "creator") => xmp.creators = values,
+        (NS_DC, "description") => xmp.description = first,
+        (NS_DC, "subject") => xmp.subjects = values,
+        (NS_XMP, "CreateDate") => xmp.create_date = first,
+        (NS_XMP, "ModifyDate") => xmp.modify_date = first,
+        (NS_XMP, "MetadataDate") => xmp.metadata_date = first,
+        (NS_XMP, "CreatorTool") => xmp.creator_tool = first,
+        (NS_PDF, "Producer") => xmp.producer = first,
+        (NS_PDF, "Keywords") => xmp.keywords = first,
+        (NS_PDF, "Trapped") => xmp.trapped = first,
+        (NS_PDFAID, "part") => xmp.pdfa_part = first,
+        (NS_PDFAID, "conformance") => xmp.pdfa_conformance = first,
+        // Housekeeping data such as document IDs and edit history
+        (NS_DC | NS_XMP | NS_PDF | NS_PDFAID | NS_XMP_MM, _) => {}
+        _ => {
+            xmp.custom.insert(format!("{}:{}", prefix, local), values.join("; "));
+        }
+    }
+}
+
+/// Values of a property element: the items of an rdf:Alt/Bag/Seq, or its text
+fn property_values(property: &Element) -> Vec<String> {
+    if let Some(container) = property
+        .elements()
+        .find(|child| child.namespace == NS_RDF && matches!(child.local.as_str(), "Alt" | "Bag" | "Seq"))
+    {
+        let items: Vec<&Element> = container
+            .elements()
+            .filter(|item| item.namespace == NS_RDF && item.local == "li")
+            .collect();
+
+        // Put the x-default alternative first
+        let default = items.iter().position(|item| item.attribute(NS_XML, "lang") == Some("x-default"));
+        let mut values: Vec<String> = items.iter().map(|item| item.text().trim().to_string()).collect();
+        if let Some(index) = default {
+            let value = values.remove(index);
+            values.insert(0, value);
+        }
+        return values.into_iter().filter(|value| !value.is_empty()).collect();
+    }
+
+    // Structured values (nested resources) are not simple properties
+    if property.elements().next().is_some() {
+        return Vec::new();
+    }
+
+    let text = property.text().trim().to_string();
+    if !text.is_empty() {
+        return vec![text];
+    }
+    property
+        .attribute(NS_RDF, "resource")
+        .map(|resource| vec![resource.to_string()])
+        .unwrap_or_default()
+}
+
+/// A namespace-resolved XML attribute
+#[derive(Debug)]
+struct Attribute {
+    namespace: String,
+    prefix: String,
+    local: String,
+    value: String,
+}
+
+/// A namespace-resolved XML element
+#[derive(Debug, Default)]
+struct Element {
+    namespace: String,
+    prefix: String,
+    local: String,
+    attributes: Vec<Attribute>,
+    children: Vec<Node>,
+}
+
+#[derive(Debug)]
+enum Node {
+    Element(Element),
+    Text(String),
+}
+
+impl Element {
+    fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|node| match node {
+            Node::Element(element) => Some(element),
+            Node::Text(_) => None,
+        })
+    }
+
+    fn attribute(&self, namespace: &str, local: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|attr| attr.namespace == namespace && attr.local == local)
+            .map(|attr| attr.value.as_str())
+    }
+
+    /// Concatenated character data of this element and its descendants
+    fn text(&self) -> String {
+        let mut text = String::new();
+        for node in &self.children {
+            match node {
+                Node::Text(t) => text.push_str(t),
+                Node::Element(element) => text.push_str(&element.text()),
+            }
+        }
+        text
+    }
+
+    fn find_all<'a>(&'a self, namespace: &str, local: &str, found: &mut Vec<&'a Element>) {
+        if self.namespace == namespace && self.local == local {
+            found.push(self);
+        }
+        for child in self.elements() {
+            child.find_all(namespace, local, found);
+        }
+    }
+}
+
+/// Parse an XML document into an element tree, returning a synthetic root element
+fn parse_xml(input: &str) -> Option<Element> {
+    // Open elements; the namespace scope each one introduced is kept in `scopes`
+    let mut stack = vec![OpenElement::default()];
+    let mut scopes: Vec<HashMap<String, String>> = vec![HashMap::from([("xml".to_string(), NS_XML.to_string())])];
+    let mut rest = input;
+
+    while !rest.is_empty() {
+        let Some(lt) = rest.find('<') else {
+            push_text(&mut stack, &decode_entities(rest));
+            break;
+        };
+        if lt > 0 {
+            push_text(&mut stack, &decode_entities(&rest[..lt]));
+        }
+        rest = &rest[lt..];
+
+        if let Some(after) = rest.strip_prefix("<!--") {
+            rest = after.find("-->").map_or("", |end| &after[end + 3..]);
+        } else if let Some(after) = rest.strip_prefix("<![CDATA[") {
+            let end = after.find("]]>").unwrap_or(after.len());
+            push_text(&mut stack, &after[..end]);
+            rest = after.get(end + 3..).unwrap_or("");
+        } else if rest.starts_with("<?") || rest.starts_with("<!") {
+            rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+        } else if let Some(after) = rest.strip_prefix("</") {
+            let end = after.find('>')?;
+            rest = &after[end + 1..];
+            if stack.len() > 1 {
+                let element = stack.pop()?.resolve(&scopes);
+                scopes.pop();
+                stack.last_mut()?.children.push(Node::Element(element));
+            }
+        } else {
+            let end = find_tag_end(rest)?;
+            let tag = &rest[1..end];
+            rest = &rest[end + 1..];
+
+            let self_closing = tag.ends_with('/');
+            let tag = tag.trim_end_matches('/');
+            let (name, attributes) = parse_tag(tag)?;
+
+            let mut scope = HashMap::new();
+            for (key, value) in &attributes {
+                if key == "xmlns" {
+                    scope.insert(String::new(), value.clone());
+                } else if let Some(prefix) = key.strip_prefix("xmlns:") {
+                    scope.insert(prefix.to_string(), value.clone());
+                }
+            }
+            scopes.push(scope);
+
+            if self_closing {
+                let element = OpenElement { name, attributes, children: Vec::new() }.resolve(&scopes);
+                scopes.pop();
+                stack.last_mut()?.children.push(Node::Element(element));
+            } else {
+                stack.push(OpenElement { name, attributes, children: Vec::new() });
+            }
+        }
+    }
+
+    // Close anything left open by a truncated packet
+    while stack.len() > 1 {
+        let element = stack.pop()?.resolve(&scopes);
+        scopes.pop();
+        stack.last_mut()?.children.push(Node::Element(element));
+    }
+
+    Some(Element {
+        children: stack.pop()?.children,
+        ..Element::default()
+    })
+}
+
+fn push_text(stack: &mut [OpenElement], text: &str) {
+    if let Some(open) = stack.last_mut() {
+        open.children.push(Node::Text(text.to_string()));
+    }
+}
+
+/// Find the `>` closing a start tag, skipping over quoted attribute values
+fn find_tag_end(input: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in input.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if c == q => quote = None,
+            (None, '>') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Split a start tag into its name and raw attributes
+fn parse_tag(tag: &str) -> Option<(String, Vec<(String, String)>)> {
+    let tag = tag.trim();
+    let name_end = tag.find(|c: char| c.is_whitespace()).unwrap_or(tag.len());
+    let name = tag[..name_end].to_string();
+
+    let mut attributes = Vec::new();
+    let mut rest = tag[name_end..].trim_start();
+    while !rest.is_empty() {
+        let eq = rest.find('=')?;
+        let key = rest[..eq].trim().to_string();
+        let after = rest[eq + 1..].trim_start();
+        let quote = after.chars().next()?;
+        if quote != '"' && quote != '\'' {
+            return None;
+        }
+        let close = after[1..].find(quote)?;
+        attributes.push((key, decode_entities(&after[1..close + 1])));
+        rest = after[close + 2..].trim_start();
+    }
+
+    Some((name, attributes))
+}
+
+/// An element whose end tag has not been seen yet, with unresolved names
+#[derive(Debug, Default)]
+struct OpenElement {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Node>,
+}
+
+impl OpenElement {
+    /// Resolve element and attribute prefixes against the namespaces in scope
+    fn resolve(self, scopes: &[HashMap<String, String>]) -> Element {
+        let lookup = |prefix: &str| {
+            scopes
+                .iter()
+                .rev()
+                .find_map(|scope| scope.get(prefix))
+                .cloned()
+                .unwrap_or_default()
+        };
+        let split = |qualified: &str| match qualified.split_once(':') {
+            Some((prefix, local)) => (prefix.to_string(), local.to_string()),
+            None => (String::new(), qualified.to_string()),
+        };
+
+        let (prefix, local) = split(&self.name);
+        let attributes = self
+            .attributes
+            .into_iter()
+            .filter(|(key, _)| key != "xmlns" && !key.starts_with("xmlns:"))
+            .map(|(key, value)| {
+                let (prefix, local) = split(&key);
+                // Unprefixed attributes are in no namespace
+                let namespace = if prefix.is_empty() { String::new() } else { lookup(&prefix) };
+                Attribute { namespace, prefix, local, value }
+            })
+            .collect();
+
+        Element {
+            namespace: lookup(&prefix),
+            prefix,
+            local,
+            attributes,
+            children: self.children,
+        }
+    }
+}
+
+/// Replace the predefined XML entities and numeric character references
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let Some(semi) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..semi];
+        let replacement = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(|dec| dec.parse()))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        match replacement {
+            Some(c) => {
+                decoded.push(c);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
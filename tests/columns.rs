@@ -0,0 +1,26 @@
+//! Multi-column pages read one column at a time
+
+mod common;
+
+use common::page_texts;
+use pdf_reader_mcp_server::TextOptions;
+
+#[test]
+fn reads_each_column_top_to_bottom() {
+    let text = &page_texts("columns.pdf", &TextOptions::default())[0];
+    let position = |needle: &str| text.find(needle).unwrap_or_else(|| panic!("{needle} missing from {text}"));
+
+    // The body is drawn row by row across both columns
+    let order = ["1 Introduction", "columns line by line.", "2 Method", "and read each column in turn.", "3 Results"];
+    assert!(order.windows(2).all(|pair| position(pair[0]) < position(pair[1])), "{text}");
+    assert!(text.contains("easy to read. Extracting their text\nin the order it is drawn mixes the"), "{text}");
+    assert!(text.contains("4 Conclusion\nReading order follows the layout"), "{text}");
+}
+
+#[test]
+fn keeps_the_spanning_title_block_in_front_and_the_footer_last() {
+    let text = &page_texts("columns.pdf", &TextOptions::default())[0];
+    assert!(text.starts_with("Reading Order in Two Columns\nA. Author and B. Author\nAbstract:"), "{text}");
+    assert!(text.contains("page number in the footer.\n1 Introduction"), "{text}");
+    assert!(text.ends_with("in which the text was drawn.\n1"), "{text}");
+}
@@ -0,0 +1,15 @@
+//! Helpers shared by the integration tests
+
+use pdf_reader_mcp_server::{PageSelection, PdfReader, TextOptions};
+
+/// Absolute path of a file in `tests/fixtures`
+pub fn fixture(name: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+/// Text of every page of a fixture with the given options, one page after the other
+#[allow(dead_code)]
+pub fn page_texts(name: &str, options: &TextOptions) -> Vec<String> {
+    let records = PdfReader::extract_page_texts(&fixture(name), &PageSelection::All, options, None).unwrap();
+    records.into_iter().map(|record| record.text).collect()
+}
@@ -0,0 +1,102 @@
+//! Encrypted documents: passwords, errors and encryption settings
+
+mod common;
+
+use common::fixture;
+use pdf_reader_mcp_server::{PdfError, PdfReader};
+
+#[test]
+fn reports_encryption_settings_without_the_password() {
+    let info = PdfReader::get_info(&fixture("encrypted-aes256.pdf"), None).unwrap();
+    assert!(info.encrypted);
+    assert!(info.locked);
+    assert_eq!(info.page_count, Some(1));
+    assert_eq!(info.title, None);
+
+    let encryption = info.encryption.expect("encryption settings are read from /Encrypt");
+    assert_eq!(encryption.security_handler, "Standard");
+    assert_eq!((encryption.version, encryption.revision), (5, 6));
+    assert_eq!((encryption.cipher.as_str(), encryption.key_length), ("AES-256", 256));
+    assert!(encryption.permissions.print);
+}
+
+#[test]
+fn info_with_a_wrong_password_is_rejected() {
+    let error = PdfReader::get_info(&fixture("encrypted-rc4.pdf"), Some("wrong")).unwrap_err();
+    assert!(matches!(error, PdfError::IncorrectPassword), "{error}");
+}
+
+#[test]
+fn page_count_of_a_locked_document_with_object_streams_is_unknown() {
+    let info = PdfReader::get_info(&fixture("encrypted-object-streams.pdf"), None).unwrap();
+    assert!(info.locked);
+    assert_eq!(info.page_count, None);
+    let encryption = info.encryption.expect("the /Encrypt dictionary is never packed in object streams");
+    assert_eq!((encryption.cipher.as_str(), encryption.key_length), ("RC4", 128));
+
+    let info = PdfReader::get_info(&fixture("encrypted-object-streams.pdf"), Some("secret")).unwrap();
+    assert_eq!(info.page_count, Some(1));
+    assert_eq!(info.title.as_deref(), Some("Simple Test PDF"));
+}
+
+#[test]
+fn unlocked_document_reports_the_same_settings() {
+    let info = PdfReader::get_info(&fixture("encrypted-aes128.pdf"), Some("secret")).unwrap();
+    assert!(info.encrypted);
+    assert!(!info.locked);
+    assert_eq!(info.title.as_deref(), Some("Simple Test PDF"));
+    let encryption = info.encryption.unwrap();
+    assert_eq!((encryption.version, encryption.revision), (4, 4));
+    assert_eq!((encryption.cipher.as_str(), encryption.key_length), ("AES-128", 128));
+
+    let locked = PdfReader::get_info(&fixture("encrypted-aes128.pdf"), None).unwrap();
+    assert!(locked.locked);
+    assert_eq!(serde_json::to_value(locked.encryption).unwrap(), serde_json::to_value(&encryption).unwrap());
+}
+
+#[test]
+fn opens_every_cipher_with_the_user_password() {
+    for name in ["encrypted-rc4.pdf", "encrypted-aes128.pdf", "encrypted-aes256.pdf"] {
+        let text = PdfReader::extract_text(&fixture(name), Some("secret")).unwrap();
+        assert!(text.contains("Hello, this is a simple test PDF."), "{name}: {text}");
+    }
+}
+
+#[test]
+fn opens_every_cipher_with_the_owner_password() {
+    for name in ["encrypted-rc4.pdf", "encrypted-aes128.pdf", "encrypted-aes256.pdf"] {
+        let text = PdfReader::extract_text(&fixture(name), Some("owner")).unwrap();
+        assert!(text.contains("This PDF contains known text content for testing."), "{name}: {text}");
+    }
+}
+
+#[test]
+fn wrong_password_is_rejected() {
+    for name in ["encrypted-rc4.pdf", "encrypted-aes128.pdf", "encrypted-aes256.pdf"] {
+        let error = PdfReader::extract_text(&fixture(name), Some("wrong")).unwrap_err();
+        assert!(matches!(error, PdfError::IncorrectPassword), "{name}: {error}");
+        assert_eq!(error.code(), "incorrect_password");
+    }
+}
+
+#[test]
+fn missing_password_is_reported() {
+    for name in ["encrypted-rc4.pdf", "encrypted-aes128.pdf", "encrypted-aes256.pdf"] {
+        let error = PdfReader::extract_page_text(&fixture(name), 1, None).unwrap_err();
+        assert!(matches!(error, PdfError::EncryptedDocument), "{name}: {error}");
+        assert_eq!(error.code(), "encrypted_document");
+    }
+}
+
+#[test]
+fn owner_password_only_document_opens_without_a_password() {
+    let text = PdfReader::extract_text(&fixture("owner-password-only.pdf"), None).unwrap();
+    assert!(text.contains("Hello, this is a simple test PDF."), "{text}");
+
+    let info = PdfReader::get_info(&fixture("owner-password-only.pdf"), None).unwrap();
+    assert!(info.encrypted && !info.locked);
+    assert_eq!(info.title.as_deref(), Some("Simple Test PDF"));
+    let permissions = info.encryption.unwrap().permissions;
+    assert!(permissions.print);
+    assert!(!permissions.copy && !permissions.modify && !permissions.annotate);
+}
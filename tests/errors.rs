@@ -0,0 +1,105 @@
+//! Typed errors and their machine-readable codes
+
+mod common;
+
+use common::fixture;
+use pdf_reader_mcp_server::{PageSelection, PdfError, PdfReader, TextOptions};
+use rmcp::model::ErrorData;
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+/// Write bytes to a temporary file, kept until the handle is dropped
+fn temporary_file(bytes: &[u8]) -> NamedTempFile {
+    let mut file = NamedTempFile::new().unwrap();
+    file.write_all(bytes).unwrap();
+    file
+}
+
+/// The error of reading a damaged copy of simple.pdf
+fn damaged_copy_error(damage: impl Fn(Vec<u8>) -> Vec<u8>) -> PdfError {
+    let file = temporary_file(&damage(std::fs::read(fixture("simple.pdf")).unwrap()));
+    PdfReader::get_info(file.path().to_str().unwrap(), None).unwrap_err()
+}
+
+#[test]
+fn missing_file_is_reported() {
+    let error = PdfReader::get_info(&fixture("does-not-exist.pdf"), None).unwrap_err();
+    assert!(matches!(error, PdfError::FileNotFound(_)));
+    assert_eq!(error.code(), "file_not_found");
+}
+
+#[test]
+fn file_that_is_not_a_pdf_is_reported() {
+    let error = damaged_copy_error(|_| b"just some text, not a PDF".to_vec());
+    assert_eq!(error.code(), "invalid_format", "{error}");
+}
+
+#[test]
+fn truncated_file_is_reported() {
+    let error = damaged_copy_error(|bytes| bytes[..bytes.len() / 2].to_vec());
+    assert!(matches!(error, PdfError::TruncatedFile(_)), "{error}");
+    assert_eq!(error.code(), "truncated_file");
+}
+
+#[test]
+fn damaged_cross_reference_table_is_reported() {
+    let error = damaged_copy_error(|bytes| String::from_utf8_lossy(&bytes).replace("xref", "xxxx").into_bytes());
+    assert!(matches!(error, PdfError::CorruptXref(_)), "{error}");
+
+    let data = ErrorData::from(error).data.unwrap();
+    assert_eq!(data["code"], "corrupt_xref");
+    assert!(data["details"]["reason"].is_string());
+}
+
+#[test]
+fn page_past_the_end_is_reported() {
+    let error = PdfReader::extract_page_text(&fixture("multi-page.pdf"), 99, None).unwrap_err();
+    assert!(matches!(error, PdfError::PageNotFound(99, _)));
+}
+
+#[test]
+fn reversed_page_range_is_an_invalid_selection() {
+    let selection = PageSelection::Range(3.into(), 1.into());
+    let options = TextOptions::default();
+    let error = PdfReader::extract_page_texts(&fixture("multi-page.pdf"), &selection, &options, None).unwrap_err();
+    assert!(matches!(&error, PdfError::InvalidPageSelection(token, _) if token == "3-1"), "{error}");
+
+    let data = ErrorData::from(error).data.unwrap();
+    assert_eq!(data["code"], "invalid_page_selection");
+    assert_eq!(data["details"]["token"], "3-1");
+}
+
+#[test]
+fn page_errors_keep_the_code_of_their_cause_and_name_the_page() {
+    let cause = PdfError::MissingObject(12, 0);
+    let error = PdfError::Page(3, Box::new(cause));
+    assert_eq!(error.code(), "missing_object");
+    assert_eq!(error.details(), serde_json::json!({ "object": 12, "generation": 0, "page": 3 }));
+    assert_eq!(error.to_string(), "Failed to read page 3: Object 12 0 R is missing from the document");
+}
+
+#[test]
+fn library_errors_map_to_specific_codes() {
+    use lopdf::Error as E;
+
+    let codes = [
+        (E::Unimplemented("decompression algorithms"), "unsupported_feature"),
+        (E::Unimplemented("inline image colorspaces"), "unsupported_feature"),
+        (E::IndirectObject { offset: 1234 }, "damaged_object"),
+        (E::ObjectIdMismatch, "corrupt_xref"),
+        (E::ReferenceCycle((5, 0)), "reference_cycle"),
+        (E::ObjectNotFound((7, 0)), "missing_object"),
+        (E::PageNumberNotFound(4), "invalid_page_tree"),
+        (E::MissingXrefEntry, "corrupt_xref"),
+    ];
+    for (error, code) in codes {
+        let description = error.to_string();
+        assert_eq!(PdfError::from(error).code(), code, "{description}");
+    }
+
+    let details = |error| PdfError::from(error).details();
+    assert_eq!(details(E::IndirectObject { offset: 1234 }), serde_json::json!({ "offset": 1234 }));
+    assert_eq!(details(E::ReferenceCycle((5, 0))), serde_json::json!({ "object": 5, "generation": 0 }));
+    let feature = details(E::Unimplemented("decompression algorithms"));
+    assert_eq!(feature, serde_json::json!({ "feature": "decompression algorithms" }));
+}
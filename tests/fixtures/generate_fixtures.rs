@@ -4,8 +4,13 @@
 //! Run with: cargo test --test generate_fixtures -- --ignored
 
 use lopdf::{Document, Object, Dictionary, Stream, StringFormat};
+use lopdf::{EncryptionState, EncryptionVersion, ObjectStream, Permissions, SaveOptions};
 use lopdf::content::{Content, Operation};
+use lopdf::encryption::crypt_filters::{Aes128CryptFilter, Aes256CryptFilter, CryptFilter};
+use lopdf::encryption::encrypt_object;
+use std::collections::BTreeMap;
 use std::path::Path;
+use std::sync::Arc;
 
 const FIXTURES_DIR: &str = "tests/fixtures";
 
@@ -62,10 +67,8 @@ fn create_simple_pdf() -> Document {
     ]));
     
     // Update page to reference parent
-    if let Ok(page) = doc.get_object_mut(page_id) {
-        if let Object::Dictionary(dict) = page {
-            dict.set("Parent", Object::Reference(pages_id));
-        }
+    if let Ok(Object::Dictionary(dict)) = doc.get_object_mut(page_id) {
+        dict.set("Parent", Object::Reference(pages_id));
     }
     
     // Create catalog
@@ -80,6 +83,12 @@ fn create_simple_pdf() -> Document {
         ("Author", Object::String(b"Test Author".to_vec(), StringFormat::Literal)),
         ("Subject", Object::String(b"Testing PDF Reader".to_vec(), StringFormat::Literal)),
         ("Creator", Object::String(b"PDF Reader Test Suite".to_vec(), StringFormat::Literal)),
+        ("Keywords", Object::String(b"pdf, testing, fixtures".to_vec(), StringFormat::Literal)),
+        ("Producer", Object::String(b"lopdf".to_vec(), StringFormat::Literal)),
+        ("CreationDate", Object::String(b"D:20240115093000+01'00'".to_vec(), StringFormat::Literal)),
+        ("ModDate", Object::String(b"D:20240301170500Z".to_vec(), StringFormat::Literal)),
+        ("Trapped", Object::Name(b"False".to_vec())),
+        ("Department", Object::String(b"Quality Assurance".to_vec(), StringFormat::Literal)),
     ]));
     
     // Set trailer
@@ -153,10 +162,8 @@ fn create_multi_page_pdf() -> Document {
     
     // Update pages to reference parent
     for page_id in &page_ids {
-        if let Ok(page) = doc.get_object_mut(*page_id) {
-            if let Object::Dictionary(dict) = page {
-                dict.set("Parent", Object::Reference(pages_id));
-            }
+        if let Ok(Object::Dictionary(dict)) = doc.get_object_mut(*page_id) {
+            dict.set("Parent", Object::Reference(pages_id));
         }
     }
     
@@ -181,6 +188,1517 @@ fn create_multi_page_pdf() -> Document {
     doc
 }
 
+/// Create a six-page manual with a nested outline
+///
+/// The outline exercises every destination form: explicit `/Dest` arrays, `GoTo`
+/// actions, names from the `/Names` tree and names from the catalog's `/Dests`.
+fn create_outline_pdf() -> Document {
+    let mut doc = Document::with_version("1.5");
+    
+    let font_id = doc.add_object(Dictionary::from_iter(vec![
+        ("Type", Object::Name(b"Font".to_vec())),
+        ("Subtype", Object::Name(b"Type1".to_vec())),
+        ("BaseFont", Object::Name(b"Helvetica".to_vec())),
+    ]));
+    
+    let resources_id = doc.add_object(Dictionary::from_iter(vec![
+        ("Font", Dictionary::from_iter(vec![
+            ("F1", Object::Reference(font_id)),
+        ]).into()),
+    ]));
+    
+    let page_texts = [
+        "Chapter 1: Getting Started",
+        "Overview of the product and its components.",
+        "Chapter 2: Setup",
+        "Installation steps for all supported platforms.",
+        "Configuration options and defaults.",
+        "Appendix: Troubleshooting",
+    ];
+    
+    let mut page_ids = Vec::new();
+    for text in page_texts {
+        let content = Content {
+            operations: vec![
+                Operation::new("BT", vec![]),
+                Operation::new("Tf", vec![Object::Name(b"F1".to_vec()), Object::Integer(12)]),
+                Operation::new("Td", vec![Object::Integer(100), Object::Integer(700)]),
+                Operation::new("Tj", vec![Object::String(text.as_bytes().to_vec(), StringFormat::Literal)]),
+                Operation::new("ET", vec![]),
+            ],
+        };
+        let content_id = doc.add_object(Stream::new(Dictionary::new(), content.encode().unwrap()));
+        page_ids.push(doc.add_object(Dictionary::from_iter(vec![
+            ("Type", Object::Name(b"Page".to_vec())),
+            ("MediaBox", vec![0.into(), 0.into(), 612.into(), 792.into()].into()),
+            ("Resources", Object::Reference(resources_id)),
+            ("Contents", Object::Reference(content_id)),
+        ])));
+    }
+    
+    let pages_id = doc.add_object(Dictionary::from_iter(vec![
+        ("Type", Object::Name(b"Pages".to_vec())),
+        ("Kids", page_ids.iter().map(|id| Object::Reference(*id)).collect::<Vec<_>>().into()),
+        ("Count", Object::Integer(page_ids.len() as i64)),
+    ]));
+    for page_id in &page_ids {
+        if let Ok(Object::Dictionary(dict)) = doc.get_object_mut(*page_id) {
+            dict.set("Parent", Object::Reference(pages_id));
+        }
+    }
+    
+    let fit = |page: usize| Object::Array(vec![Object::Reference(page_ids[page]), Object::Name(b"Fit".to_vec())]);
+    let title = |text: &str| Object::String(text.as_bytes().to_vec(), StringFormat::Literal);
+    
+    // Allocate ids up front so items can point at their siblings and parents
+    let outlines_id = doc.new_object_id();
+    let chapter1_id = doc.new_object_id();
+    let overview_id = doc.new_object_id();
+    let chapter2_id = doc.new_object_id();
+    let installation_id = doc.new_object_id();
+    let configuration_id = doc.new_object_id();
+    let appendix_id = doc.new_object_id();
+    
+    let goto_action = doc.add_object(Dictionary::from_iter(vec![
+        ("S", Object::Name(b"GoTo".to_vec())),
+        ("D", fit(1)),
+    ]));
+    
+    let items = vec![
+        (chapter1_id, Dictionary::from_iter(vec![
+            ("Title", title("Chapter 1 Getting Started")),
+            ("Parent", Object::Reference(outlines_id)),
+            ("Next", Object::Reference(chapter2_id)),
+            ("First", Object::Reference(overview_id)),
+            ("Last", Object::Reference(overview_id)),
+            ("Count", Object::Integer(1)),
+            ("Dest", fit(0)),
+        ])),
+        (overview_id, Dictionary::from_iter(vec![
+            ("Title", title("Overview")),
+            ("Parent", Object::Reference(chapter1_id)),
+            ("A", Object::Reference(goto_action)),
+        ])),
+        (chapter2_id, Dictionary::from_iter(vec![
+            ("Title", title("Chapter 2 Setup")),
+            ("Parent", Object::Reference(outlines_id)),
+            ("Prev", Object::Reference(chapter1_id)),
+            ("Next", Object::Reference(appendix_id)),
+            ("First", Object::Reference(installation_id)),
+            ("Last", Object::Reference(configuration_id)),
+            ("Count", Object::Integer(2)),
+            ("Dest", Object::String(b"chapter-2".to_vec(), StringFormat::Literal)),
+        ])),
+        (installation_id, Dictionary::from_iter(vec![
+            ("Title", title("Installation")),
+            ("Parent", Object::Reference(chapter2_id)),
+            ("Next", Object::Reference(configuration_id)),
+            ("Dest", Object::Name(b"install".to_vec())),
+        ])),
+        (configuration_id, Dictionary::from_iter(vec![
+            ("Title", title("Configuration")),
+            ("Parent", Object::Reference(chapter2_id)),
+            ("Prev", Object::Reference(installation_id)),
+            ("Dest", fit(4)),
+        ])),
+        (appendix_id, Dictionary::from_iter(vec![
+            ("Title", title("Appendix")),
+            ("Parent", Object::Reference(outlines_id)),
+            ("Prev", Object::Reference(chapter2_id)),
+            ("Dest", fit(5)),
+        ])),
+    ];
+    for (id, item) in items {
+        doc.objects.insert(id, Object::Dictionary(item));
+    }
+    doc.objects.insert(outlines_id, Object::Dictionary(Dictionary::from_iter(vec![
+        ("Type", Object::Name(b"Outlines".to_vec())),
+        ("First", Object::Reference(chapter1_id)),
+        ("Last", Object::Reference(appendix_id)),
+        ("Count", Object::Integer(6)),
+    ])));
+    
+    // "chapter-2" lives in the name tree, "install" in the older /Dests dictionary
+    let dests_tree_id = doc.add_object(Dictionary::from_iter(vec![
+        ("Names", Object::Array(vec![
+            Object::String(b"chapter-2".to_vec(), StringFormat::Literal),
+            Object::Dictionary(Dictionary::from_iter(vec![("D", fit(2))])),
+        ])),
+    ]));
+    
+    let catalog_id = doc.add_object(Dictionary::from_iter(vec![
+        ("Type", Object::Name(b"Catalog".to_vec())),
+        ("Pages", Object::Reference(pages_id)),
+        ("Outlines", Object::Reference(outlines_id)),
+        ("PageMode", Object::Name(b"UseOutlines".to_vec())),
+        ("Names", Dictionary::from_iter(vec![
+            ("Dests", Object::Reference(dests_tree_id)),
+        ]).into()),
+        ("Dests", Dictionary::from_iter(vec![
+            ("install", fit(3)),
+        ]).into()),
+    ]));
+    
+    let info_id = doc.add_object(Dictionary::from_iter(vec![
+        ("Title", Object::String(b"Outline Test Manual".to_vec(), StringFormat::Literal)),
+    ]));
+    
+    doc.trailer.set("Root", Object::Reference(catalog_id));
+    doc.trailer.set("Info", Object::Reference(info_id));
+    
+    doc
+}
+
+/// Create a PDF with one line of text per page
+fn create_text_pdf(title: &str, page_texts: &[String]) -> Document {
+    let mut doc = Document::with_version("1.5");
+    
+    let font_id = doc.add_object(Dictionary::from_iter(vec![
+        ("Type", Object::Name(b"Font".to_vec())),
+        ("Subtype", Object::Name(b"Type1".to_vec())),
+        ("BaseFont", Object::Name(b"Helvetica".to_vec())),
+    ]));
+    
+    let resources_id = doc.add_object(Dictionary::from_iter(vec![
+        ("Font", Dictionary::from_iter(vec![
+            ("F1", Object::Reference(font_id)),
+        ]).into()),
+    ]));
+    
+    let mut page_ids = Vec::new();
+    for text in page_texts {
+        let content = Content {
+            operations: vec![
+                Operation::new("BT", vec![]),
+                Operation::new("Tf", vec![Object::Name(b"F1".to_vec()), Object::Integer(12)]),
+                Operation::new("Td", vec![Object::Integer(100), Object::Integer(700)]),
+                Operation::new("Tj", vec![Object::String(text.as_bytes().to_vec(), StringFormat::Literal)]),
+                Operation::new("ET", vec![]),
+            ],
+        };
+        let content_id = doc.add_object(Stream::new(Dictionary::new(), content.encode().unwrap()));
+        page_ids.push(doc.add_object(Dictionary::from_iter(vec![
+            ("Type", Object::Name(b"Page".to_vec())),
+            ("MediaBox", vec![0.into(), 0.into(), 612.into(), 792.into()].into()),
+            ("Resources", Object::Reference(resources_id)),
+            ("Contents", Object::Reference(content_id)),
+        ])));
+    }
+    
+    let pages_id = doc.add_object(Dictionary::from_iter(vec![
+        ("Type", Object::Name(b"Pages".to_vec())),
+        ("Kids", page_ids.iter().map(|id| Object::Reference(*id)).collect::<Vec<_>>().into()),
+        ("Count", Object::Integer(page_ids.len() as i64)),
+    ]));
+    for page_id in &page_ids {
+        if let Ok(Object::Dictionary(dict)) = doc.get_object_mut(*page_id) {
+            dict.set("Parent", Object::Reference(pages_id));
+        }
+    }
+    
+    let catalog_id = doc.add_object(Dictionary::from_iter(vec![
+        ("Type", Object::Name(b"Catalog".to_vec())),
+        ("Pages", Object::Reference(pages_id)),
+    ]));
+    let info_id = doc.add_object(Dictionary::from_iter(vec![
+        ("Title", Object::String(title.as_bytes().to_vec(), StringFormat::Literal)),
+    ]));
+    
+    doc.trailer.set("Root", Object::Reference(catalog_id));
+    doc.trailer.set("Info", Object::Reference(info_id));
+    
+    doc
+}
+
+/// A run of text placed at an absolute position (x, y, font size, text)
+type TextRun = (f64, f64, f64, &'static str);
+
+/// Create a document whose pages place each text run with its own text matrix
+///
+/// Runs are written in the order given, which need not be the reading order.
+fn create_positioned_pdf(title: &str, pages: &[Vec<TextRun>]) -> Document {
+    let pages = pages
+        .iter()
+        .map(|runs| {
+            let mut operations = vec![Operation::new("BT", vec![])];
+            for (x, y, size, text) in runs {
+                operations.push(Operation::new("Tf", vec![Object::Name(b"F1".to_vec()), Object::Real(*size as f32)]));
+                operations.push(Operation::new("Tm", vec![
+                    1.into(), 0.into(), 0.into(), 1.into(), Object::Real(*x as f32), Object::Real(*y as f32),
+                ]));
+                operations.push(Operation::new("Tj", vec![Object::String(text.as_bytes().to_vec(), StringFormat::Literal)]));
+            }
+            operations.push(Operation::new("ET", vec![]));
+            operations
+        })
+        .collect();
+    create_helvetica_pdf(title, pages)
+}
+
+/// Create a document with one page per list of content operations, using Helvetica as /F1
+fn create_helvetica_pdf(title: &str, pages: Vec<Vec<Operation>>) -> Document {
+    let mut doc = Document::with_version("1.5");
+    
+    let font_id = doc.add_object(Dictionary::from_iter(vec![
+        ("Type", Object::Name(b"Font".to_vec())),
+        ("Subtype", Object::Name(b"Type1".to_vec())),
+        ("BaseFont", Object::Name(b"Helvetica".to_vec())),
+    ]));
+    
+    create_pdf_with_fonts(doc, title, vec![("F1", font_id)], pages)
+}
+
+/// Finish a document whose font objects were already added, one page per list of operations
+fn create_pdf_with_fonts(
+    mut doc: Document,
+    title: &str,
+    fonts: Vec<(&str, lopdf::ObjectId)>,
+    pages: Vec<Vec<Operation>>,
+) -> Document {
+    let fonts = fonts.into_iter().map(|(name, id)| (name, Object::Reference(id)));
+    let resources_id = doc.add_object(Dictionary::from_iter(vec![
+        ("Font", Dictionary::from_iter(fonts).into()),
+    ]));
+    
+    let mut page_ids = Vec::new();
+    for operations in pages {
+        let content = Content { operations };
+        let content_id = doc.add_object(Stream::new(Dictionary::new(), content.encode().unwrap()));
+        page_ids.push(doc.add_object(Dictionary::from_iter(vec![
+            ("Type", Object::Name(b"Page".to_vec())),
+            ("MediaBox", vec![0.into(), 0.into(), 612.into(), 792.into()].into()),
+            ("Resources", Object::Reference(resources_id)),
+            ("Contents", Object::Reference(content_id)),
+        ])));
+    }
+    
+    let pages_id = doc.add_object(Dictionary::from_iter(vec![
+        ("Type", Object::Name(b"Pages".to_vec())),
+        ("Kids", page_ids.iter().map(|id| Object::Reference(*id)).collect::<Vec<_>>().into()),
+        ("Count", Object::Integer(page_ids.len() as i64)),
+    ]));
+    for page_id in &page_ids {
+        if let Ok(Object::Dictionary(dict)) = doc.get_object_mut(*page_id) {
+            dict.set("Parent", Object::Reference(pages_id));
+        }
+    }
+    
+    let catalog_id = doc.add_object(Dictionary::from_iter(vec![
+        ("Type", Object::Name(b"Catalog".to_vec())),
+        ("Pages", Object::Reference(pages_id)),
+    ]));
+    let info_id = doc.add_object(Dictionary::from_iter(vec![
+        ("Title", Object::String(title.as_bytes().to_vec(), StringFormat::Literal)),
+    ]));
+    
+    doc.trailer.set("Root", Object::Reference(catalog_id));
+    doc.trailer.set("Info", Object::Reference(info_id));
+    
+    doc
+}
+
+/// Create a page whose words are separated by positioning alone, without space characters
+fn create_spacing_pdf() -> Document {
+    let string = |text: &str| Object::String(text.as_bytes().to_vec(), StringFormat::Literal);
+    let operations = vec![
+        Operation::new("BT", vec![]),
+        Operation::new("Tf", vec![Object::Name(b"F1".to_vec()), Object::Integer(12)]),
+        // Words spaced by TJ adjustments (a third of an em each)
+        Operation::new("Td", vec![Object::Integer(72), Object::Integer(700)]),
+        Operation::new("TJ", vec![Object::Array(vec![
+            string("The"), Object::Integer(-333), string("quick"), Object::Integer(-333),
+            string("brown"), Object::Integer(-333), string("fox"),
+        ])]),
+        // Kerning inside a word must not split it
+        Operation::new("Td", vec![Object::Integer(0), Object::Integer(-20)]),
+        Operation::new("TJ", vec![Object::Array(vec![
+            string("W"), Object::Integer(80), string("ave"), Object::Integer(-20),
+            string("rly"), Object::Integer(-333), string("AVA"), Object::Integer(60), string("TAR"),
+        ])]),
+        // One word per text matrix
+        Operation::new("Tm", vec![1.into(), 0.into(), 0.into(), 1.into(), 72.into(), 660.into()]),
+        Operation::new("Tj", vec![string("jumps")]),
+        Operation::new("Tm", vec![1.into(), 0.into(), 0.into(), 1.into(), 110.into(), 660.into()]),
+        Operation::new("Tj", vec![string("over")]),
+        Operation::new("Tm", vec![1.into(), 0.into(), 0.into(), 1.into(), 140.into(), 660.into()]),
+        Operation::new("Tj", vec![string("the")]),
+        // Word spacing applied through Tw and a real space
+        Operation::new("Tw", vec![Object::Integer(6)]),
+        Operation::new("Tm", vec![1.into(), 0.into(), 0.into(), 1.into(), 72.into(), 640.into()]),
+        Operation::new("Tj", vec![string("lazy dog")]),
+        Operation::new("ET", vec![]),
+    ];
+    create_helvetica_pdf("Word Spacing Test Document", vec![operations])
+}
+
+/// Create a page whose fonts need their encodings, CMaps and ToUnicode maps to be decoded
+fn create_fonts_pdf() -> Document {
+    let mut doc = Document::with_version("1.5");
+    let name = |name: &str| Object::Name(name.as_bytes().to_vec());
+    let to_unicode = |doc: &mut Document, mappings: &str| {
+        let cmap = format!(
+            "/CIDInit /ProcSet findresource begin\n12 dict begin\nbegincmap\n\
+             /CMapName /Adobe-Identity-UCS def\n/CMapType 2 def\n{mappings}\n\
+             endcmap\nCMapName currentdict /CMap defineresource pop\nend\nend\n"
+        );
+        doc.add_object(Stream::new(Dictionary::new(), cmap.into_bytes()))
+    };
+
+    // Simple font whose /Differences use AGL names, a ligature and a uniXXXX name
+    let differences = doc.add_object(Dictionary::from_iter(vec![
+        ("Type", name("Encoding")),
+        ("BaseEncoding", name("WinAnsiEncoding")),
+        ("Differences", vec![
+            1.into(), name("f_i"), name("uni00E9"), name("quotedblleft"), name("quotedblright"),
+        ].into()),
+    ]));
+    let differences_font = doc.add_object(Dictionary::from_iter(vec![
+        ("Type", name("Font")),
+        ("Subtype", name("Type1")),
+        ("BaseFont", name("Times-Roman")),
+        ("Encoding", Object::Reference(differences)),
+    ]));
+
+    // Subset font with arbitrary codes, decoded only through bfchar and bfrange
+    let subset_map = to_unicode(&mut doc, "1 begincodespacerange\n<00> <FF>\nendcodespacerange\n\
+        2 beginbfchar\n<01> <0020>\n<02> /period\nendbfchar\n\
+        2 beginbfrange\n<10> <14> <0061>\n<20> <22> [<0053> <0075> <0062>]\nendbfrange");
+    let subset_font = doc.add_object(Dictionary::from_iter(vec![
+        ("Type", name("Font")),
+        ("Subtype", name("TrueType")),
+        ("BaseFont", name("ABCDEF+Subset")),
+        ("ToUnicode", Object::Reference(subset_map)),
+    ]));
+
+    // Composite font with Identity-H CIDs mapped through a two-byte ToUnicode CMap
+    let cid_font = |doc: &mut Document, ordering: &str| {
+        doc.add_object(Dictionary::from_iter(vec![
+            ("Type", name("Font")),
+            ("Subtype", name("CIDFontType2")),
+            ("BaseFont", name("CJKFont")),
+            ("CIDSystemInfo", Dictionary::from_iter(vec![
+                ("Registry", Object::string_literal("Adobe")),
+                ("Ordering", Object::string_literal(ordering)),
+                ("Supplement", 0.into()),
+            ]).into()),
+            ("DW", 1000.into()),
+        ]))
+    };
+    let identity_map = to_unicode(&mut doc, "1 begincodespacerange\n<0000> <FFFF>\nendcodespacerange\n\
+        5 beginbfchar\n<0003> <0020>\n<0010> <65E5>\n<0011> <672C>\n<0012> <8A9E>\n<0013> <006600660069>\nendbfchar");
+    let identity_descendant = cid_font(&mut doc, "Identity");
+    let identity_font = doc.add_object(Dictionary::from_iter(vec![
+        ("Type", name("Font")),
+        ("Subtype", name("Type0")),
+        ("BaseFont", name("CJKFont")),
+        ("Encoding", name("Identity-H")),
+        ("DescendantFonts", vec![Object::Reference(identity_descendant)].into()),
+        ("ToUnicode", Object::Reference(identity_map)),
+    ]));
+
+    // Composite fonts with predefined CMaps and no ToUnicode: UTF-16 and Shift-JIS codes
+    let mut predefined_font = |cmap: &str, ordering: &str| {
+        let descendant = cid_font(&mut doc, ordering);
+        doc.add_object(Dictionary::from_iter(vec![
+            ("Type", name("Font")),
+            ("Subtype", name("Type0")),
+            ("BaseFont", name(&format!("CJKFont-{cmap}"))),
+            ("Encoding", name(cmap)),
+            ("DescendantFonts", vec![Object::Reference(descendant)].into()),
+        ]))
+    };
+    let utf16_font = predefined_font("UniGB-UTF16-H", "GB1");
+    let shift_jis_font = predefined_font("90ms-RKSJ-H", "Japan1");
+
+    // Type3 font with glyph procedures named after TeX character codes, as bitmap fonts
+    // from dvips and pdfTeX are
+    let glyph_names = ["a11", "a65", "a99", "a101", "a116"];
+    let procedure = doc.add_object(Stream::new(Dictionary::new(), b"500 0 0 0 400 600 d1 0 0 400 600 re f".to_vec()));
+    let char_procs = glyph_names.iter().map(|glyph| (*glyph, Object::Reference(procedure)));
+    let type3_font = doc.add_object(Dictionary::from_iter(vec![
+        ("Type", name("Font")),
+        ("Subtype", name("Type3")),
+        ("FontBBox", vec![0.into(), 0.into(), 500.into(), 600.into()].into()),
+        ("FontMatrix", vec![0.001.into(), 0.into(), 0.into(), 0.001.into(), 0.into(), 0.into()].into()),
+        ("CharProcs", Dictionary::from_iter(char_procs).into()),
+        ("Encoding", Dictionary::from_iter(vec![
+            ("Type", name("Encoding")),
+            ("Differences", vec![
+                11.into(), name("a11"), 65.into(), name("a65"), 99.into(), name("a99"),
+                101.into(), name("a101"), 116.into(), name("a116"),
+            ].into()),
+        ]).into()),
+        ("FirstChar", 11.into()),
+        ("LastChar", 116.into()),
+        ("Widths", vec![Object::Integer(500); 106].into()),
+    ]));
+
+    let line = |font: &str, y: i64, bytes: &[u8]| {
+        vec![
+            Operation::new("Tf", vec![name(font), 12.into()]),
+            Operation::new("Tm", vec![1.into(), 0.into(), 0.into(), 1.into(), 72.into(), y.into()]),
+            Operation::new("Tj", vec![Object::String(bytes.to_vec(), StringFormat::Hexadecimal)]),
+        ]
+    };
+    let mut operations = vec![Operation::new("BT", vec![])];
+    operations.extend(line("F1", 700, b"\x01nd caf\x02 \x03quoted\x04"));
+    operations.extend(line("F2", 680, b"\x20\x21\x22\x01\x10\x11\x12\x13\x14\x02"));
+    operations.extend(line("F3", 660, &[0x00, 0x10, 0x00, 0x11, 0x00, 0x12, 0x00, 0x03, 0x00, 0x13]));
+    operations.extend(line("F4", 640, &[0x4E, 0x2D, 0x65, 0x87, 0xD8, 0x3D, 0xDE, 0x00]));
+    operations.extend(line("F5", 620, &[0x83, 0x65, 0x83, 0x58, 0x83, 0x67, 0x20, 0x41]));
+    operations.extend(line("F6", 600, b"A\x0bect"));
+    operations.push(Operation::new("ET", vec![]));
+
+    let fonts = vec![
+        ("F1", differences_font),
+        ("F2", subset_font),
+        ("F3", identity_font),
+        ("F4", utf16_font),
+        ("F5", shift_jis_font),
+        ("F6", type3_font),
+    ];
+    create_pdf_with_fonts(doc, "Font Encoding Test Document", fonts, vec![operations])
+}
+
+/// Create a page with one line per predefined CMap family, each in a composite font
+/// without a ToUnicode map, so the text comes from the CMap encoding alone
+fn create_cjk_cmaps_pdf() -> Document {
+    let mut doc = Document::with_version("1.5");
+    let name = |name: &str| Object::Name(name.as_bytes().to_vec());
+    let lines: [(&str, &str, &[u8]); 11] = [
+        // UCS-2 and UTF-16, the latter with a surrogate pair
+        ("UniGB-UCS2-H", "GB1", &[0x4E, 0x2D, 0x65, 0x87]),
+        ("UniGB-UTF16-H", "GB1", &[0x4E, 0x2D, 0x65, 0x87, 0xD8, 0x3D, 0xDE, 0x00]),
+        ("UniJIS-UTF8-H", "Japan1", &[0xE6, 0x97, 0xA5, 0xE6, 0x9C, 0xAC, 0xE8, 0xAA, 0x9E]),
+        ("UniJIS-UTF32-H", "Japan1", &[0x00, 0x00, 0x8A, 0x9E, 0x00, 0x01, 0xF6, 0x00]),
+        // Legacy encodings, mixing one-byte ASCII with multi-byte codes
+        ("90ms-RKSJ-H", "Japan1", &[0x83, 0x65, 0x83, 0x58, 0x83, 0x67, 0x20, 0x41]),
+        ("EUC-H", "Japan1", &[0xC6, 0xFC, 0xCB, 0xDC, 0xB8, 0xEC, 0x20, 0x4A, 0x50]),
+        // ISO-2022-JP row and cell bytes, two per character
+        ("H", "Japan1", &[0x46, 0x7C, 0x4B, 0x5C]),
+        ("GBK-EUC-H", "GB1", &[0xBC, 0xF2, 0xCC, 0xE5, 0xD6, 0xD0, 0xCE, 0xC4, 0x20, 0x47, 0x42]),
+        // GB 18030 four-byte codes next to two-byte ones
+        ("GBK2K-H", "GB1", &[0xD6, 0xD0, 0xCE, 0xC4, 0x81, 0x30, 0x8A, 0x31]),
+        ("B5pc-H", "CNS1", &[0xC1, 0x63, 0xC5, 0xE9, 0xA4, 0xA4, 0xA4, 0xE5, 0x20, 0x42, 0x35]),
+        ("KSC-EUC-H", "Korea1", &[0xC7, 0xD1, 0xB1, 0xB9, 0xBE, 0xEE, 0x20, 0x4B, 0x52]),
+    ];
+
+    let mut fonts = Vec::new();
+    let mut operations = vec![Operation::new("BT", vec![])];
+    for (index, (cmap, ordering, bytes)) in lines.iter().enumerate() {
+        let descendant = doc.add_object(Dictionary::from_iter(vec![
+            ("Type", name("Font")),
+            ("Subtype", name("CIDFontType2")),
+            ("BaseFont", name("CJKFont")),
+            ("CIDSystemInfo", Dictionary::from_iter(vec![
+                ("Registry", Object::string_literal("Adobe")),
+                ("Ordering", Object::string_literal(*ordering)),
+                ("Supplement", 0.into()),
+            ]).into()),
+            ("DW", 1000.into()),
+        ]));
+        fonts.push(doc.add_object(Dictionary::from_iter(vec![
+            ("Type", name("Font")),
+            ("Subtype", name("Type0")),
+            ("BaseFont", name(&format!("CJKFont-{cmap}"))),
+            ("Encoding", name(cmap)),
+            ("DescendantFonts", vec![Object::Reference(descendant)].into()),
+        ])));
+
+        let y = 700 - 20 * index as i64;
+        operations.extend([
+            Operation::new("Tf", vec![name(&format!("F{}", index + 1)), 12.into()]),
+            Operation::new("Tm", vec![1.into(), 0.into(), 0.into(), 1.into(), 72.into(), y.into()]),
+            Operation::new("Tj", vec![Object::String(bytes.to_vec(), StringFormat::Hexadecimal)]),
+        ]);
+    }
+    operations.push(Operation::new("ET", vec![]));
+
+    let font_names: Vec<String> = (1..=fonts.len()).map(|index| format!("F{index}")).collect();
+    let fonts = font_names.iter().map(String::as_str).zip(fonts).collect();
+    create_pdf_with_fonts(doc, "CJK CMap Test Document", fonts, vec![operations])
+}
+
+/// Create a page whose fonts have widths at the edge of the code and CID range, as
+/// malformed files do: `/FirstChar` and `/W` groups near `u32::MAX` and negative CIDs
+fn create_malformed_widths_pdf() -> Document {
+    let mut doc = Document::with_version("1.5");
+    let name = |name: &str| Object::Name(name.as_bytes().to_vec());
+    let max = Object::Integer(u32::MAX as i64);
+
+    let simple_font = doc.add_object(Dictionary::from_iter(vec![
+        ("Type", name("Font")),
+        ("Subtype", name("Type1")),
+        ("BaseFont", name("Helvetica")),
+        ("FirstChar", max.clone()),
+        ("Widths", vec![500.into(); 3].into()),
+    ]));
+    let negative_font = doc.add_object(Dictionary::from_iter(vec![
+        ("Type", name("Font")),
+        ("Subtype", name("Type1")),
+        ("BaseFont", name("Helvetica")),
+        ("FirstChar", (-5).into()),
+        ("Widths", vec![500.into(); 3].into()),
+    ]));
+
+    // CIDs are the UTF-16 code units of the text
+    let cmap = "/CIDInit /ProcSet findresource begin\n12 dict begin\nbegincmap\n\
+        /CMapName /Adobe-Identity-UCS def\n/CMapType 2 def\n\
+        1 begincodespacerange\n<0000> <FFFF>\nendcodespacerange\n\
+        1 beginbfrange\n<0000> <00FF> <0000>\nendbfrange\n\
+        endcmap\nCMapName currentdict /CMap defineresource pop\nend\nend\n";
+    let to_unicode = doc.add_object(Stream::new(Dictionary::new(), cmap.as_bytes().to_vec()));
+    let mut cid_font = |encoding: &str, metrics: (&str, Vec<Object>)| {
+        let descendant = doc.add_object(Dictionary::from_iter(vec![
+            ("Type", name("Font")),
+            ("Subtype", name("CIDFontType2")),
+            ("BaseFont", name("MalformedCID")),
+            ("CIDSystemInfo", Dictionary::from_iter(vec![
+                ("Registry", Object::string_literal("Adobe")),
+                ("Ordering", Object::string_literal("Identity")),
+                ("Supplement", 0.into()),
+            ]).into()),
+            ("DW", 500.into()),
+            (metrics.0, metrics.1.into()),
+        ]));
+        doc.add_object(Dictionary::from_iter(vec![
+            ("Type", name("Font")),
+            ("Subtype", name("Type0")),
+            ("BaseFont", name("MalformedCID")),
+            ("Encoding", name(encoding)),
+            ("DescendantFonts", vec![Object::Reference(descendant)].into()),
+            ("ToUnicode", Object::Reference(to_unicode)),
+        ]))
+    };
+    let horizontal_font = cid_font("Identity-H", ("W", vec![
+        max.clone(), vec![600.into(), 600.into()].into(),
+        (-3).into(), vec![600.into()].into(),
+        (u32::MAX as i64 - 5).into(), 1_000_000_000_000i64.into(), 600.into(),
+        65.into(), vec![600.into(), 600.into()].into(),
+    ]));
+    let vertical_font = cid_font("Identity-V", ("W2", vec![
+        max.clone(), vec![(-1000).into(), 250.into(), 880.into(), (-1000).into(), 250.into(), 880.into()].into(),
+        (u32::MAX as i64 - 1).into(), max, (-1000).into(), 250.into(), 880.into(),
+        (-7).into(), 70.into(), (-1000).into(), 250.into(), 880.into(),
+    ]));
+
+    let utf16 = |text: &str| {
+        Object::String(text.encode_utf16().flat_map(u16::to_be_bytes).collect(), StringFormat::Hexadecimal)
+    };
+    let line = |font: &str, x: i64, y: i64, text: Object| {
+        vec![
+            Operation::new("Tf", vec![name(font), 12.into()]),
+            Operation::new("Tm", vec![1.into(), 0.into(), 0.into(), 1.into(), x.into(), y.into()]),
+            Operation::new("Tj", vec![text]),
+        ]
+    };
+    let mut operations = vec![Operation::new("BT", vec![])];
+    operations.extend(line("F1", 72, 700, Object::string_literal("Simple widths")));
+    operations.extend(line("F2", 72, 680, Object::string_literal("Negative first")));
+    operations.extend(line("F3", 72, 660, utf16("AB widths")));
+    operations.extend(line("F4", 400, 600, utf16("AB")));
+    operations.push(Operation::new("ET", vec![]));
+
+    let fonts = vec![("F1", simple_font), ("F2", negative_font), ("F3", horizontal_font), ("F4", vertical_font)];
+    create_pdf_with_fonts(doc, "Malformed Widths Test Document", fonts, vec![operations])
+}
+
+/// Create a page of Hebrew text drawn in visual order, as most producers do, and in
+/// logical order, one glyph at a time moving leftwards
+fn create_rtl_pdf() -> Document {
+    let mut doc = Document::with_version("1.5");
+    let name = |name: &str| Object::Name(name.as_bytes().to_vec());
+    // CIDs are the UTF-16 code units of the text, for Latin and Hebrew alike
+    let cmap = "/CIDInit /ProcSet findresource begin\n12 dict begin\nbegincmap\n\
+        /CMapName /Adobe-Identity-UCS def\n/CMapType 2 def\n\
+        1 begincodespacerange\n<0000> <FFFF>\nendcodespacerange\n\
+        2 beginbfrange\n<0000> <00FF> <0000>\n<0500> <05FF> <0500>\nendbfrange\n\
+        endcmap\nCMapName currentdict /CMap defineresource pop\nend\nend\n";
+    let to_unicode = doc.add_object(Stream::new(Dictionary::new(), cmap.as_bytes().to_vec()));
+    let descendant = doc.add_object(Dictionary::from_iter(vec![
+        ("Type", name("Font")),
+        ("Subtype", name("CIDFontType2")),
+        ("BaseFont", name("HebrewFont")),
+        ("CIDSystemInfo", Dictionary::from_iter(vec![
+            ("Registry", Object::string_literal("Adobe")),
+            ("Ordering", Object::string_literal("Identity")),
+            ("Supplement", 0.into()),
+        ]).into()),
+        ("DW", 500.into()),
+    ]));
+    let font = doc.add_object(Dictionary::from_iter(vec![
+        ("Type", name("Font")),
+        ("Subtype", name("Type0")),
+        ("BaseFont", name("HebrewFont")),
+        ("Encoding", name("Identity-H")),
+        ("DescendantFonts", vec![Object::Reference(descendant)].into()),
+        ("ToUnicode", Object::Reference(to_unicode)),
+    ]));
+
+    let utf16 = |text: &str| {
+        Object::String(text.encode_utf16().flat_map(u16::to_be_bytes).collect(), StringFormat::Hexadecimal)
+    };
+    let move_to = |y: i64| Operation::new("Tm", vec![1.into(), 0.into(), 0.into(), 1.into(), 72.into(), y.into()]);
+    let mut operations = vec![
+        Operation::new("BT", vec![]),
+        Operation::new("Tf", vec![name("F1"), 12.into()]),
+        // "שלום 2024 (עולם)" laid out left to right as displayed
+        move_to(700),
+        Operation::new("Tj", vec![utf16("(םלוע) 2024 םולש")]),
+        // "מסמך חתום" in reading order, each glyph moved back past the one before it
+        move_to(680),
+        Operation::new("Td", vec![100.into(), 0.into()]),
+    ];
+    let backwards: Vec<Object> = "מסמך חתום".chars().flat_map(|c| [utf16(&c.to_string()), 1000.into()]).collect();
+    operations.push(Operation::new("TJ", vec![Object::Array(backwards)]));
+    operations.extend([
+        // A Hebrew word inside an English sentence
+        move_to(660),
+        Operation::new("Tj", vec![utf16("Contract הזוח signed")]),
+        Operation::new("ET", vec![]),
+    ]);
+    create_pdf_with_fonts(doc, "Right-to-Left Test Document", vec![("F1", font)], vec![operations])
+}
+
+/// Create a page of Japanese set vertically with an `Identity-V` font: two columns read
+/// right to left, and a horizontal page number
+fn create_vertical_pdf() -> Document {
+    let mut doc = Document::with_version("1.5");
+    let name = |name: &str| Object::Name(name.as_bytes().to_vec());
+    let columns = ["縦書きの文章です。", "二行目も、縦に読む。"];
+    // CIDs are the UTF-16 code units of the text
+    let mut characters: Vec<char> = columns.concat().chars().collect();
+    characters.sort_unstable();
+    characters.dedup();
+    let mappings: String =
+        characters.iter().map(|c| format!("<{:04X}> <{:04X}>\n", *c as u32, *c as u32)).collect();
+    let cmap = format!(
+        "/CIDInit /ProcSet findresource begin\n12 dict begin\nbegincmap\n\
+         /CMapName /Adobe-Identity-UCS def\n/CMapType 2 def\n\
+         1 begincodespacerange\n<0000> <FFFF>\nendcodespacerange\n\
+         {} beginbfchar\n{mappings}endbfchar\n\
+         endcmap\nCMapName currentdict /CMap defineresource pop\nend\nend\n",
+        characters.len()
+    );
+    let to_unicode = doc.add_object(Stream::new(Dictionary::new(), cmap.into_bytes()));
+    let descendant = doc.add_object(Dictionary::from_iter(vec![
+        ("Type", name("Font")),
+        ("Subtype", name("CIDFontType2")),
+        ("BaseFont", name("MinchoFont")),
+        ("CIDSystemInfo", Dictionary::from_iter(vec![
+            ("Registry", Object::string_literal("Adobe")),
+            ("Ordering", Object::string_literal("Identity")),
+            ("Supplement", 0.into()),
+        ]).into()),
+        ("DW", 1000.into()),
+        ("DW2", vec![880.into(), (-1000).into()].into()),
+    ]));
+    let vertical_font = doc.add_object(Dictionary::from_iter(vec![
+        ("Type", name("Font")),
+        ("Subtype", name("Type0")),
+        ("BaseFont", name("MinchoFont")),
+        ("Encoding", name("Identity-V")),
+        ("DescendantFonts", vec![Object::Reference(descendant)].into()),
+        ("ToUnicode", Object::Reference(to_unicode)),
+    ]));
+    let helvetica = doc.add_object(Dictionary::from_iter(vec![
+        ("Type", name("Font")),
+        ("Subtype", name("Type1")),
+        ("BaseFont", name("Helvetica")),
+    ]));
+
+    let utf16 = |text: &str| {
+        Object::String(text.encode_utf16().flat_map(u16::to_be_bytes).collect(), StringFormat::Hexadecimal)
+    };
+    let mut operations = vec![Operation::new("BT", vec![]), Operation::new("Tf", vec![name("F1"), 14.into()])];
+    for (column, text) in columns.iter().enumerate() {
+        let x = 500 - 24 * column as i64;
+        operations.push(Operation::new("Tm", vec![1.into(), 0.into(), 0.into(), 1.into(), x.into(), 720.into()]));
+        operations.push(Operation::new("Tj", vec![utf16(text)]));
+    }
+    operations.extend([
+        Operation::new("Tf", vec![name("F2"), 10.into()]),
+        Operation::new("Tm", vec![1.into(), 0.into(), 0.into(), 1.into(), 300.into(), 40.into()]),
+        Operation::new("Tj", vec![Object::string_literal("1")]),
+        Operation::new("ET", vec![]),
+    ]);
+    let fonts = vec![("F1", vertical_font), ("F2", helvetica)];
+    create_pdf_with_fonts(doc, "Vertical Writing Test Document", fonts, vec![operations])
+}
+
+/// Create a page whose text needs cleaning up: ligatures, a private-use bullet, a soft
+/// hyphen, a combining accent, doubled spaces and a word hyphenated across lines
+fn create_normalization_pdf() -> Document {
+    let mut doc = Document::with_version("1.5");
+    let name = |name: &str| Object::Name(name.as_bytes().to_vec());
+    let font = doc.add_object(Dictionary::from_iter(vec![
+        ("Type", name("Font")),
+        ("Subtype", name("Type1")),
+        ("BaseFont", name("Helvetica")),
+        ("Encoding", Dictionary::from_iter(vec![
+            ("Type", name("Encoding")),
+            ("BaseEncoding", name("WinAnsiEncoding")),
+            ("Differences", vec![
+                1.into(), name("fi"), name("fl"), name("uniF0B7"), name("acutecomb"), name("uni00AD"),
+            ].into()),
+        ]).into()),
+    ]));
+
+    let lines: [&[u8]; 4] = [
+        b"\x03 The \x01rst \x02oor holds the docu-",
+        b"ment archive and the re\x05cords  room.",
+        b"Its cafe\x04 is open to self-",
+        b"Service visitors.",
+    ];
+    let mut operations = vec![
+        Operation::new("BT", vec![]),
+        Operation::new("Tf", vec![name("F1"), 12.into()]),
+        Operation::new("TL", vec![14.into()]),
+        Operation::new("Td", vec![72.into(), 700.into()]),
+    ];
+    for line in lines {
+        operations.push(Operation::new("Tj", vec![Object::String(line.to_vec(), StringFormat::Literal)]));
+        operations.push(Operation::new("T*", vec![]));
+    }
+    operations.push(Operation::new("ET", vec![]));
+    create_pdf_with_fonts(doc, "Normalization Test Document", vec![("F1", font)], vec![operations])
+}
+
+/// Create a six-page report with a running header, alternating on left and right-hand
+/// pages, and a "Page N of 6" footer
+fn create_furniture_pdf() -> Document {
+    const FOOTERS: [&str; 6] = ["Page 1 of 6", "Page 2 of 6", "Page 3 of 6", "Page 4 of 6", "Page 5 of 6", "Page 6 of 6"];
+    let bodies = [
+        ["1 Scope", "This report covers the survey of the", "northern ferns in the spring of 2024."],
+        ["2 Sites", "Twelve sites were visited along the", "river, each twice during the season."],
+        ["3 Counts", "Fronds were counted on a square metre", "at every site, 1,204 of them in total."],
+        ["4 Spores", "Spore prints were taken from each", "species found at more than two sites."],
+        ["5 Results", "Seven species were recorded, two of", "them for the first time in the valley."],
+        ["6 Outlook", "The survey will be repeated next year", "with the same sites and methods."],
+    ];
+
+    let pages: Vec<Vec<TextRun>> = bodies
+        .iter()
+        .zip(FOOTERS)
+        .enumerate()
+        .map(|(index, (body, footer))| {
+            let header = if index % 2 == 0 {
+                (72.0, 760.0, 9.0, "Fern Survey 2024")
+            } else {
+                (440.0, 760.0, 9.0, "Northern Valley Field Report")
+            };
+            vec![
+                header,
+                (72.0, 700.0, 14.0, body[0]),
+                (72.0, 676.0, 10.0, body[1]),
+                (72.0, 662.0, 10.0, body[2]),
+                (270.0, 40.0, 9.0, footer),
+            ]
+        })
+        .collect();
+    create_positioned_pdf("Page Furniture Test Document", &pages)
+}
+
+/// Create a two-page report with a ruled table and a table without vertical rulings
+///
+/// Page 1 draws a grid whose header merges "Segment" over two rows and "Revenue" over two
+/// columns. Page 2 has a title and a paragraph over an income statement set between two
+/// horizontal rules, with its year headings over the number columns only.
+fn create_tables_pdf() -> Document {
+    let text = |x: f64, y: f64, size: f64, text: &str| {
+        vec![
+            Operation::new("BT", vec![]),
+            Operation::new("Tf", vec![Object::Name(b"F1".to_vec()), Object::Real(size as f32)]),
+            Operation::new("Td", vec![Object::Real(x as f32), Object::Real(y as f32)]),
+            Operation::new("Tj", vec![Object::String(text.as_bytes().to_vec(), StringFormat::Literal)]),
+            Operation::new("ET", vec![]),
+        ]
+    };
+    let line = |x0: f64, y0: f64, x1: f64, y1: f64| {
+        vec![
+            Operation::new("m", vec![Object::Real(x0 as f32), Object::Real(y0 as f32)]),
+            Operation::new("l", vec![Object::Real(x1 as f32), Object::Real(y1 as f32)]),
+            Operation::new("S", vec![]),
+        ]
+    };
+
+    let mut ruled = vec![Operation::new("w", vec![Object::Real(0.5)])];
+    // Outer frame as one rectangle, inner lines one by one
+    ruled.push(Operation::new("re", vec![72.into(), 600.into(), 328.into(), 100.into()]));
+    ruled.push(Operation::new("S", vec![]));
+    ruled.extend(line(200.0, 680.0, 400.0, 680.0));
+    for y in [660.0, 640.0, 620.0] {
+        ruled.extend(line(72.0, y, 400.0, y));
+    }
+    ruled.extend(line(200.0, 600.0, 200.0, 700.0));
+    ruled.extend(line(300.0, 600.0, 300.0, 680.0));
+    let cells = [
+        (78.0, 676.0, "Segment"),
+        (206.0, 686.0, "Revenue"),
+        (206.0, 666.0, "2024"),
+        (306.0, 666.0, "2023"),
+        (78.0, 646.0, "Cloud"),
+        (206.0, 646.0, "1,204"),
+        (306.0, 646.0, "980"),
+        (78.0, 626.0, "Devices"),
+        (206.0, 626.0, "310"),
+        (306.0, 626.0, "355"),
+        (78.0, 606.0, "Total"),
+        (206.0, 606.0, "1,514"),
+        (306.0, 606.0, "1,335"),
+    ];
+    for (x, y, content) in cells {
+        ruled.extend(text(x, y, 10.0, content));
+    }
+
+    let mut statement = Vec::new();
+    statement.extend(text(72.0, 740.0, 14.0, "Consolidated Statement of Income"));
+    statement.extend(text(72.0, 715.0, 10.0, "Amounts are in thousands of dollars. The figures for 2023 have been"));
+    statement.extend(text(72.0, 701.0, 10.0, "restated to reflect the sale of the printing business in that year."));
+    statement.extend(line(72.0, 672.0, 500.0, 672.0));
+    statement.extend(line(72.0, 590.0, 500.0, 590.0));
+    let rows = [
+        ("", "2024", "2023"),
+        ("Revenue", "12,480", "11,020"),
+        ("Cost of sales", "(7,310)", "(6,950)"),
+        ("Gross profit", "5,170", "4,070"),
+        ("Operating expenses", "(2,900)", "(2,640)"),
+        ("Net income", "2,270", "1,430"),
+    ];
+    for (index, (label, current, previous)) in rows.iter().enumerate() {
+        let y = 660.0 - index as f64 * 14.0;
+        if !label.is_empty() {
+            statement.extend(text(72.0, y, 10.0, label));
+        }
+        statement.extend(text(380.0, y, 10.0, current));
+        statement.extend(text(450.0, y, 10.0, previous));
+    }
+
+    create_helvetica_pdf("Table Test Document", vec![ruled, statement])
+}
+
+/// Create an account statement whose table is drawn column by column
+fn create_markdown_pdf() -> Document {
+    let mut doc = Document::with_version("1.5");
+    let font = |doc: &mut Document, base_font: &str| {
+        doc.add_object(Dictionary::from_iter(vec![
+            ("Type", Object::Name(b"Font".to_vec())),
+            ("Subtype", Object::Name(b"Type1".to_vec())),
+            ("BaseFont", Object::Name(base_font.as_bytes().to_vec())),
+        ]))
+    };
+    let fonts = vec![("F1", font(&mut doc, "Helvetica")), ("F2", font(&mut doc, "Helvetica-Bold"))];
+
+    // Font, x, y, size and text; "\xB7" is the bullet of the standard encoding
+    let runs = |runs: &[(&str, f64, f64, f64, &[u8])]| {
+        let mut operations = vec![Operation::new("BT", vec![])];
+        for (font, x, y, size, text) in runs {
+            operations.push(Operation::new("Tf", vec![Object::Name(font.as_bytes().to_vec()), Object::Real(*size as f32)]));
+            operations.push(Operation::new("Tm", vec![
+                1.into(), 0.into(), 0.into(), 1.into(), Object::Real(*x as f32), Object::Real(*y as f32),
+            ]));
+            operations.push(Operation::new("Tj", vec![Object::String(text.to_vec(), StringFormat::Literal)]));
+        }
+        operations.push(Operation::new("ET", vec![]));
+        operations
+    };
+
+    let guide = runs(&[
+        ("F2", 72.0, 740.0, 20.0, b"Field Guide to Ferns"),
+        ("F2", 72.0, 704.0, 14.0, b"1 Habitat"),
+        ("F1", 72.0, 680.0, 10.0, b"Ferns grow in damp and shady places, from the floor of old woods to the"),
+        ("F1", 72.0, 666.0, 10.0, b"cracks of garden walls. Most species need a steady supply of water to"),
+        ("F1", 72.0, 652.0, 10.0, b"reproduce, since their spores first grow into a small separate plant."),
+        ("F1", 72.0, 630.0, 10.0, b"Three habitats hold most of the species in the valley:"),
+        ("F1", 72.0, 612.0, 10.0, b"\xB7"),
+        ("F1", 84.0, 612.0, 10.0, b"Woodland floors under oak and beech"),
+        ("F1", 72.0, 598.0, 10.0, b"\xB7"),
+        ("F1", 84.0, 598.0, 10.0, b"Stream banks, where the spray of the water keeps"),
+        ("F1", 84.0, 584.0, 10.0, b"the fronds wet all summer"),
+        ("F1", 96.0, 570.0, 10.0, b"-"),
+        ("F1", 106.0, 570.0, 10.0, b"Waterfall ledges"),
+        ("F1", 72.0, 556.0, 10.0, b"\xB7"),
+        ("F1", 84.0, 556.0, 10.0, b"Old stone walls"),
+        ("F2", 72.0, 526.0, 10.0, b"Collecting spores"),
+        ("F1", 72.0, 506.0, 10.0, b"1."),
+        ("F1", 86.0, 506.0, 10.0, b"Cut a ripe frond on a dry day."),
+        ("F1", 72.0, 492.0, 10.0, b"2."),
+        ("F1", 86.0, 492.0, 10.0, b"Lay it on white paper and cover it overnight."),
+        ("F1", 72.0, 478.0, 10.0, b"3."),
+        ("F1", 86.0, 478.0, 10.0, b"Fold the paper around the spores and keep it in a"),
+        ("F1", 86.0, 464.0, 10.0, b"dry place until sowing."),
+    ]);
+    let species = runs(&[
+        ("F2", 72.0, 740.0, 14.0, b"2 Species"),
+        ("F1", 72.0, 716.0, 10.0, b"Four species were found at every site, in these numbers:"),
+        ("F2", 72.0, 690.0, 10.0, b"Species"),
+        ("F2", 220.0, 690.0, 10.0, b"Sites"),
+        ("F2", 300.0, 690.0, 10.0, b"Fronds"),
+        ("F1", 72.0, 676.0, 10.0, b"Hart's-tongue"),
+        ("F1", 220.0, 676.0, 10.0, b"9"),
+        ("F1", 300.0, 676.0, 10.0, b"412"),
+        ("F1", 72.0, 662.0, 10.0, b"Lady fern"),
+        ("F1", 220.0, 662.0, 10.0, b"7"),
+        ("F1", 300.0, 662.0, 10.0, b"388"),
+        ("F1", 72.0, 648.0, 10.0, b"Male fern"),
+        ("F1", 220.0, 648.0, 10.0, b"12"),
+        ("F1", 300.0, 648.0, 10.0, b"404"),
+        ("F1", 72.0, 620.0, 10.0, b"Counts are the totals of both visits."),
+    ]);
+
+    create_pdf_with_fonts(doc, "Markdown Test Document", fonts, vec![guide, species])
+}
+
+/// Create a page of nested lists: an ordered list starting at 4 holding a bullet list and
+/// a list of lettered items, a numbered sub-list, and a bullet item after the numbered ones
+fn create_lists_pdf() -> Document {
+    let runs = vec![
+        (72.0, 740.0, 10.0, "Survey steps:"),
+        (72.0, 720.0, 10.0, "4. Mark the plots on the map"),
+        (86.0, 706.0, 10.0, "- Oak plots"),
+        (86.0, 692.0, 10.0, "- Beech plots"),
+        (100.0, 678.0, 10.0, "a) north slope"),
+        (100.0, 664.0, 10.0, "b) south slope"),
+        (72.0, 650.0, 10.0, "5. Count the ferns in each plot"),
+        (86.0, 636.0, 10.0, "1. Fronds"),
+        (86.0, 622.0, 10.0, "2. Spore cases"),
+        (72.0, 608.0, 10.0, "- Write up the counts"),
+        (72.0, 588.0, 10.0, "Send the report to the survey office."),
+    ];
+    create_positioned_pdf("List Test Document", &[runs])
+}
+
+/// Create a two-page document with link annotations: a web link, a link to the second
+/// page, a `javascript:` link, a named destination linking back to the first page, a link
+/// to another PDF file and a launch link whose `/QuadPoints` run over two lines
+fn create_links_pdf() -> Document {
+    let introduction = vec![
+        (72.0, 740.0, 18.0, "Survey Report"),
+        (72.0, 700.0, 10.0, "Read the full report on the project website before the meeting."),
+        (72.0, 686.0, 10.0, "Results are summarized in the table on page 2."),
+        (72.0, 672.0, 10.0, "Do not follow this script link."),
+    ];
+    let results = vec![
+        (72.0, 740.0, 18.0, "Results"),
+        (72.0, 700.0, 10.0, "All sites were visited twice. Back to the introduction."),
+        (72.0, 686.0, 10.0, "Compare with the 2023 survey and open the raw data sheet for"),
+        (72.0, 672.0, 10.0, "every site."),
+    ];
+    let mut doc = create_positioned_pdf("Links Test Document", &[introduction, results]);
+
+    let pages: Vec<lopdf::ObjectId> = doc.get_pages().into_values().collect();
+    let rect = |x0: f32, y0: f32, x1: f32, y1: f32| {
+        Object::Array(vec![Object::Real(x0), Object::Real(y0), Object::Real(x1), Object::Real(y1)])
+    };
+    let action = |entries: Vec<(&str, Object)>| Object::Dictionary(Dictionary::from_iter(entries));
+    let uri = |uri: &str| action(vec![
+        ("S", Object::Name(b"URI".to_vec())),
+        ("URI", Object::String(uri.as_bytes().to_vec(), StringFormat::Literal)),
+    ]);
+    let link = |rect: Object, target: (&str, Object)| {
+        Dictionary::from_iter(vec![
+            ("Type", Object::Name(b"Annot".to_vec())),
+            ("Subtype", Object::Name(b"Link".to_vec())),
+            ("Rect", rect),
+            ("Border", vec![0.into(), 0.into(), 0.into()].into()),
+            target,
+        ])
+    };
+
+    let first_page = vec![
+        link(rect(189.0, 696.0, 258.0, 709.0), ("A", uri("https://example.org/survey?year=2024&format=full"))),
+        link(rect(247.0, 682.0, 278.5, 695.0), ("A", action(vec![
+            ("S", Object::Name(b"GoTo".to_vec())),
+            ("D", vec![Object::Reference(pages[1]), Object::Name(b"Fit".to_vec())].into()),
+        ]))),
+        link(rect(150.0, 668.0, 196.0, 681.0), ("A", uri("javascript:alert(1)"))),
+    ];
+    // A link to another PDF file, and a launch link running over two lines
+    let mut launch = link(rect(72.0, 668.0, 352.0, 695.0), ("A", action(vec![
+        ("S", Object::Name(b"Launch".to_vec())),
+        ("F", Object::String(b"data/sites.csv".to_vec(), StringFormat::Literal)),
+        ("NewWindow", Object::Boolean(true)),
+    ])));
+    let quads = [
+        271.5, 695.0, 351.6, 695.0, 271.5, 682.0, 351.6, 682.0,
+        72.0, 681.0, 114.8, 681.0, 72.0, 668.0, 114.8, 668.0,
+    ];
+    launch.set("QuadPoints", quads.iter().map(|&value| Object::Real(value)).collect::<Vec<_>>());
+    let second_page = vec![
+        link(rect(245.0, 696.0, 301.0, 709.0), ("Dest", Object::Name(b"introduction".to_vec()))),
+        link(rect(153.0, 682.0, 207.7, 695.0), ("A", action(vec![
+            ("S", Object::Name(b"GoToR".to_vec())),
+            ("F", Dictionary::from_iter(vec![
+                ("Type", Object::Name(b"Filespec".to_vec())),
+                ("F", Object::String(b"survey-2023.pdf".to_vec(), StringFormat::Literal)),
+            ]).into()),
+            ("D", vec![3.into(), Object::Name(b"Fit".to_vec())].into()),
+        ]))),
+        launch,
+    ];
+    for (page_id, links) in pages.iter().zip([first_page, second_page]) {
+        let links: Vec<Object> = links.into_iter().map(|link| Object::Reference(doc.add_object(link))).collect();
+        if let Ok(Object::Dictionary(page)) = doc.get_object_mut(*page_id) {
+            page.set("Annots", links);
+        }
+    }
+
+    let destination: Object =
+        vec![Object::Reference(pages[0]), Object::Name(b"XYZ".to_vec()), 0.into(), 792.into(), Object::Null].into();
+    let catalog_id = doc.trailer.get(b"Root").and_then(Object::as_reference).unwrap();
+    if let Ok(Object::Dictionary(catalog)) = doc.get_object_mut(catalog_id) {
+        catalog.set("Dests", Dictionary::from_iter(vec![("introduction", destination)]));
+    }
+    doc
+}
+
+/// Create a two-page tagged document whose content is drawn out of its logical order
+///
+/// The structure tree reads a heading (under the custom role "Heading1", mapped to H1), a
+/// paragraph with an /ActualText span, a figure described by /Alt, a list and a table. The
+/// newsletter header and the page number are artifacts outside the tree.
+fn create_tagged_pdf() -> Document {
+    // Marked-content sequence of text runs: properties, then x, y, size and text of each run
+    let sequence = |tag: &str, properties: Object, runs: &[(f64, f64, f64, &str)]| {
+        let mut operations = vec![Operation::new("BDC", vec![Object::Name(tag.as_bytes().to_vec()), properties])];
+        for (x, y, size, text) in runs {
+            operations.push(Operation::new("Tf", vec![Object::Name(b"F1".to_vec()), Object::Real(*size as f32)]));
+            operations.push(Operation::new("Tm", vec![
+                1.into(), 0.into(), 0.into(), 1.into(), Object::Real(*x as f32), Object::Real(*y as f32),
+            ]));
+            operations.push(Operation::new("Tj", vec![Object::String(text.as_bytes().to_vec(), StringFormat::Literal)]));
+        }
+        operations.push(Operation::new("EMC", vec![]));
+        operations
+    };
+    let mcid = |mcid: i64| Object::Dictionary(Dictionary::from_iter(vec![("MCID", Object::Integer(mcid))]));
+    let artifact = Object::Dictionary(Dictionary::from_iter(vec![("Type", Object::Name(b"Pagination".to_vec()))]));
+
+    let mut guide = vec![Operation::new("BT", vec![])];
+    guide.extend(sequence("Artifact", artifact.clone(), &[(72.0, 760.0, 9.0, "Garden Club Newsletter")]));
+    guide.extend(sequence("TH", mcid(9), &[(72.0, 470.0, 10.0, "Crop")]));
+    guide.extend(sequence("TH", mcid(10), &[(200.0, 470.0, 10.0, "Soil temperature")]));
+    guide.extend(sequence("TD", mcid(11), &[(72.0, 454.0, 10.0, "Tomatoes")]));
+    guide.extend(sequence("TD", mcid(12), &[(200.0, 454.0, 10.0, "21 C")]));
+    guide.extend(sequence("Heading1", mcid(0), &[(72.0, 720.0, 18.0, "Spring Planting Guide")]));
+    guide.extend(sequence("Lbl", mcid(5), &[(72.0, 530.0, 10.0, "1.")]));
+    guide.extend(sequence("LBody", mcid(6), &[(86.0, 530.0, 10.0, "Loosen the soil.")]));
+    guide.extend(sequence("Lbl", mcid(7), &[(72.0, 516.0, 10.0, "2.")]));
+    guide.extend(sequence("LBody", mcid(8), &[(86.0, 516.0, 10.0, "Sow the seeds thinly.")]));
+    guide.extend(sequence("P", mcid(1), &[
+        (72.0, 690.0, 10.0, "Sow seeds after the last frost, when the soil is warm enough for ger-"),
+        (72.0, 676.0, 10.0, "mination, and keep them moist. Quick crops,"),
+    ]));
+    let replaced = Dictionary::from_iter(vec![
+        ("MCID", Object::Integer(2)),
+        ("ActualText", Object::String(b"for example".to_vec(), StringFormat::Literal)),
+    ]);
+    guide.extend(sequence("Span", replaced.into(), &[(72.0, 662.0, 10.0, "e.g.")]));
+    guide.extend(sequence("P", mcid(3), &[(88.68, 662.0, 10.0, ", radishes, are ready within a month.")]));
+    guide.push(Operation::new("ET", vec![]));
+    // The figure: a frame without text
+    guide.push(Operation::new("BDC", vec![Object::Name(b"Figure".to_vec()), mcid(4)]));
+    guide.push(Operation::new("re", vec![72.into(), 560.into(), 200.into(), 80.into()]));
+    guide.push(Operation::new("S", vec![]));
+    guide.push(Operation::new("EMC", vec![]));
+
+    let mut watering = vec![Operation::new("BT", vec![])];
+    watering.extend(sequence("Artifact", artifact, &[(300.0, 40.0, 9.0, "2")]));
+    watering.extend(sequence("P", mcid(1), &[
+        (72.0, 690.0, 10.0, "Water in the early morning so the leaves dry before evening."),
+    ]));
+    watering.extend(sequence("H2", mcid(0), &[(72.0, 720.0, 14.0, "Watering")]));
+    watering.push(Operation::new("ET", vec![]));
+
+    let mut doc = create_helvetica_pdf("Tagged Test Document", vec![guide, watering]);
+    let pages: Vec<lopdf::ObjectId> = doc.get_pages().into_values().collect();
+
+    // Structure elements: role, page, kids and further entries
+    let element = |doc: &mut Document, role: &str, page: Option<usize>, kids: Vec<Object>, extra: Vec<(&str, Object)>| {
+        let mut dict = Dictionary::from_iter(vec![
+            ("Type", Object::Name(b"StructElem".to_vec())),
+            ("S", Object::Name(role.as_bytes().to_vec())),
+            ("K", Object::Array(kids)),
+        ]);
+        if let Some(page) = page {
+            dict.set("Pg", Object::Reference(pages[page]));
+        }
+        for (key, value) in extra {
+            dict.set(key, value);
+        }
+        Object::Reference(doc.add_object(dict))
+    };
+    let heading = element(&mut doc, "Heading1", Some(0), vec![0.into()], vec![]);
+    let span = element(&mut doc, "Span", None, vec![2.into()], vec![]);
+    let paragraph = element(&mut doc, "P", Some(0), vec![1.into(), span, 3.into()], vec![]);
+    let alt = b"Frame for a chart of germination by soil temperature".to_vec();
+    let figure = element(&mut doc, "Figure", Some(0), vec![4.into()], vec![("Alt", Object::String(alt, StringFormat::Literal))]);
+    let mut items = Vec::new();
+    for (label, body) in [(5, 6), (7, 8)] {
+        let label = element(&mut doc, "Lbl", None, vec![label.into()], vec![]);
+        let body = element(&mut doc, "LBody", None, vec![body.into()], vec![]);
+        items.push(element(&mut doc, "LI", Some(0), vec![label, body], vec![]));
+    }
+    let list = element(&mut doc, "L", None, items, vec![]);
+    let mut rows = Vec::new();
+    for cells in [[("TH", 9), ("TH", 10)], [("TD", 11), ("TD", 12)]] {
+        let cells = cells
+            .iter()
+            .map(|(role, mcid)| element(&mut doc, role, None, vec![(*mcid).into()], vec![]))
+            .collect();
+        rows.push(element(&mut doc, "TR", Some(0), cells, vec![]));
+    }
+    let table = element(&mut doc, "Table", None, rows, vec![]);
+    let subheading = element(&mut doc, "H2", Some(1), vec![0.into()], vec![]);
+    let reference = Dictionary::from_iter(vec![
+        ("Type", Object::Name(b"MCR".to_vec())),
+        ("Pg", Object::Reference(pages[1])),
+        ("MCID", Object::Integer(1)),
+    ]);
+    let advice = element(&mut doc, "P", None, vec![reference.into()], vec![]);
+    let kids = vec![heading, paragraph, figure, list, table, subheading, advice];
+    let document = element(&mut doc, "Document", None, kids, vec![]);
+
+    let root_id = doc.add_object(Dictionary::from_iter(vec![
+        ("Type", Object::Name(b"StructTreeRoot".to_vec())),
+        ("K", document),
+        ("RoleMap", Dictionary::from_iter(vec![("Heading1", Object::Name(b"H1".to_vec()))]).into()),
+    ]));
+    let catalog_id = doc.trailer.get(b"Root").and_then(Object::as_reference).unwrap();
+    if let Ok(Object::Dictionary(catalog)) = doc.get_object_mut(catalog_id) {
+        catalog.set("StructTreeRoot", Object::Reference(root_id));
+        catalog.set("MarkInfo", Dictionary::from_iter(vec![("Marked", Object::Boolean(true))]));
+    }
+    doc
+}
+
+fn create_layout_pdf() -> Document {
+    let statement = vec![
+        (72.0, 720.0, 16.0, "Account Statement"),
+        (400.0, 720.0, 10.0, "Period: March 2024"),
+        // Column by column, as some report generators do
+        (72.0, 680.0, 10.0, "Date"),
+        (72.0, 664.0, 10.0, "03/01"),
+        (72.0, 648.0, 10.0, "03/09"),
+        (72.0, 632.0, 10.0, "03/22"),
+        (150.0, 680.0, 10.0, "Description"),
+        (150.0, 664.0, 10.0, "Opening balance"),
+        (150.0, 648.0, 10.0, "Office supplies"),
+        (150.0, 632.0, 10.0, "Client payment"),
+        (400.0, 680.0, 10.0, "Amount"),
+        (400.0, 664.0, 10.0, "1,250.00"),
+        (406.0, 648.0, 10.0, "-84.50"),
+        (400.0, 632.0, 10.0, "3,400.00"),
+        (150.0, 584.0, 10.0, "Closing balance"),
+        (400.0, 584.0, 10.0, "4,565.50"),
+    ];
+    create_positioned_pdf("Layout Test Document", &[statement])
+}
+
+/// Create a research-paper style page: a full-width title block over two columns
+///
+/// The body is drawn row by row across both columns, so content order interleaves them.
+fn create_columns_pdf() -> Document {
+    let left = [
+        "1 Introduction",
+        "Documents are often set in two",
+        "columns to keep lines short and",
+        "easy to read. Extracting their text",
+        "in the order it is drawn mixes the",
+        "columns line by line.",
+        "2 Method",
+        "We find gutters between columns",
+        "and read each column in turn.",
+    ];
+    let right = [
+        "3 Results",
+        "Each column now reads from top",
+        "to bottom before the next begins.",
+        "Title blocks that span the page",
+        "stay in front of the columns.",
+        "4 Conclusion",
+        "Reading order follows the layout",
+        "of the page instead of the order",
+        "in which the text was drawn.",
+    ];
+    
+    let mut runs = vec![
+        (160.0, 730.0, 18.0, "Reading Order in Two Columns"),
+        (250.0, 708.0, 11.0, "A. Author and B. Author"),
+        (72.0, 680.0, 10.0, "Abstract: this page has a title block that spans both columns, followed by"),
+        (72.0, 668.0, 10.0, "a body set in two columns and a centered page number in the footer."),
+    ];
+    for (row, (left, right)) in left.iter().zip(right.iter()).enumerate() {
+        let y = 630.0 - row as f64 * 14.0;
+        runs.push((72.0, y, 10.0, left));
+        runs.push((320.0, y, 10.0, right));
+    }
+    runs.push((300.0, 40.0, 10.0, "1"));
+    
+    create_positioned_pdf("Two Column Test Document", &[runs])
+}
+
+/// Create a ten-page book labelled i, ii, 1-4, A-11, A-12, Z, AA
+///
+/// The label ranges are split across two leaves of the number tree.
+fn create_page_labels_pdf() -> Document {
+    let page_texts: Vec<String> = (1..=10).map(|i| format!("Physical page {}", i)).collect();
+    let mut doc = create_text_pdf("Page Labels Test PDF", &page_texts);
+    
+    let label = |style: &[u8], prefix: Option<&str>, start: Option<i64>| {
+        let mut dict = Dictionary::from_iter(vec![("S", Object::Name(style.to_vec()))]);
+        if let Some(prefix) = prefix {
+            dict.set("P", Object::String(prefix.as_bytes().to_vec(), StringFormat::Literal));
+        }
+        if let Some(start) = start {
+            dict.set("St", Object::Integer(start));
+        }
+        Object::Dictionary(dict)
+    };
+    
+    let front_id = doc.add_object(Dictionary::from_iter(vec![
+        ("Limits", vec![0.into(), 2.into()].into()),
+        ("Nums", vec![0.into(), label(b"r", None, None), 2.into(), label(b"D", None, None)].into()),
+    ]));
+    let back_id = doc.add_object(Dictionary::from_iter(vec![
+        ("Limits", vec![6.into(), 8.into()].into()),
+        ("Nums", vec![6.into(), label(b"D", Some("A-"), Some(11)), 8.into(), label(b"A", None, Some(26))].into()),
+    ]));
+    let labels_id = doc.add_object(Dictionary::from_iter(vec![
+        ("Kids", vec![Object::Reference(front_id), Object::Reference(back_id)].into()),
+    ]));
+    
+    let catalog_id = doc.trailer.get(b"Root").and_then(Object::as_reference).unwrap();
+    if let Ok(Object::Dictionary(catalog)) = doc.get_object_mut(catalog_id) {
+        catalog.set("PageLabels", Object::Reference(labels_id));
+    }
+    
+    doc
+}
+
+/// Create a seven-page document whose label ranges start at `/St` values far too large to
+/// count from: decimal labels saturate, roman and letter labels fall back to decimal
+fn create_page_labels_huge_start_pdf() -> Document {
+    let page_texts: Vec<String> = (1..=7).map(|i| format!("Physical page {}", i)).collect();
+    let mut doc = create_text_pdf("Huge Page Label Start Test PDF", &page_texts);
+
+    let label = |style: &[u8], start: i64| {
+        Object::Dictionary(Dictionary::from_iter(vec![
+            ("S", Object::Name(style.to_vec())),
+            ("St", Object::Integer(start)),
+        ]))
+    };
+    let labels_id = doc.add_object(Dictionary::from_iter(vec![
+        ("Nums", vec![
+            0.into(), label(b"D", i64::MAX),
+            2.into(), label(b"R", i64::MAX),
+            3.into(), label(b"a", i64::MAX),
+            4.into(), label(b"A", 259),
+        ].into()),
+    ]));
+
+    let catalog_id = doc.trailer.get(b"Root").and_then(Object::as_reference).unwrap();
+    if let Ok(Object::Dictionary(catalog)) = doc.get_object_mut(catalog_id) {
+        catalog.set("PageLabels", Object::Reference(labels_id));
+    }
+
+    doc
+}
+
+/// XMP packet for xmp-metadata.pdf, newer than (and disagreeing with) its Info dictionary
+const XMP_PACKET: &str = r#"<?xpacket begin="" id="W5M0MpCehiHzreSzNTczkc9d"?>
+<x:xmpmeta xmlns:x="adobe:ns:meta/">
+  <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+    <rdf:Description rdf:about=""
+        xmlns:pdf="http://ns.adobe.com/pdf/1.3/"
+        pdf:Producer="XMP Test Producer 2.0">
+      <pdf:Keywords>xmp, metadata</pdf:Keywords>
+    </rdf:Description>
+    <rdf:Description rdf:about="" xmlns:dc="http://purl.org/dc/elements/1.1/">
+      <dc:title>
+        <rdf:Alt>
+          <rdf:li xml:lang="de-DE">XMP-Testdokument</rdf:li>
+          <rdf:li xml:lang="x-default">XMP Test Document</rdf:li>
+        </rdf:Alt>
+      </dc:title>
+      <dc:creator>
+        <rdf:Seq>
+          <rdf:li>Ada Lovelace</rdf:li>
+          <rdf:li>Charles Babbage</rdf:li>
+        </rdf:Seq>
+      </dc:creator>
+      <dc:description>
+        <rdf:Alt>
+          <rdf:li xml:lang="x-default">Metadata &amp; precedence test</rdf:li>
+        </rdf:Alt>
+      </dc:description>
+      <dc:subject>
+        <rdf:Bag>
+          <rdf:li>xmp</rdf:li>
+          <rdf:li>metadata</rdf:li>
+        </rdf:Bag>
+      </dc:subject>
+    </rdf:Description>
+    <rdf:Description rdf:about="" xmlns:xmp="http://ns.adobe.com/xap/1.0/">
+      <xmp:CreateDate>2024-05-01T08:00:00+02:00</xmp:CreateDate>
+      <xmp:ModifyDate>2024-06-10T12:30:00+02:00</xmp:ModifyDate>
+      <xmp:CreatorTool>XMP Test Writer</xmp:CreatorTool>
+    </rdf:Description>
+    <rdf:Description rdf:about="" xmlns:pdfaid="http://www.aiim.org/pdfa/ns/id/">
+      <pdfaid:part>2</pdfaid:part>
+      <pdfaid:conformance>B</pdfaid:conformance>
+    </rdf:Description>
+    <rdf:Description rdf:about="" xmlns:acme="http://example.com/ns/acme/1.0/">
+      <acme:ContractNumber>C-2024-0042</acme:ContractNumber>
+    </rdf:Description>
+  </rdf:RDF>
+</x:xmpmeta>
+<?xpacket end="w"?>"#;
+
+/// Create a PDF whose catalog carries an XMP metadata stream next to a stale Info dictionary
+fn create_xmp_pdf() -> Document {
+    let mut doc = create_simple_pdf();
+    
+    let metadata_id = doc.add_object(Stream::new(
+        Dictionary::from_iter(vec![
+            ("Type", Object::Name(b"Metadata".to_vec())),
+            ("Subtype", Object::Name(b"XML".to_vec())),
+        ]),
+        XMP_PACKET.as_bytes().to_vec(),
+    ));
+    
+    let catalog_id = doc.trailer.get(b"Root").and_then(Object::as_reference).unwrap();
+    if let Ok(Object::Dictionary(catalog)) = doc.get_object_mut(catalog_id) {
+        catalog.set("Metadata", Object::Reference(metadata_id));
+    }
+    
+    doc
+}
+
+/// Security handler used when encrypting a fixture
+enum Cipher {
+    Rc4,
+    Aes128,
+    Aes256,
+}
+
+/// Create an RC4-encrypted copy of the simple PDF with its dictionaries packed in an object
+/// stream (user password "secret", owner password "owner")
+///
+/// lopdf encrypts the strings inside object streams instead of the object streams
+/// themselves, so the object stream is written in plain text and then encrypted in
+/// place. RC4 keeps the length, so the offsets in the cross-reference stream stay valid.
+fn create_encrypted_object_streams_pdf() -> Vec<u8> {
+    let mut doc = create_simple_pdf();
+    let id = b"pdf-reader-tests".to_vec();
+    doc.trailer.set("ID", Object::Array(vec![
+        Object::String(id.clone(), StringFormat::Hexadecimal),
+        Object::String(id, StringFormat::Hexadecimal),
+    ]));
+    let state = EncryptionState::try_from(EncryptionVersion::V2 {
+        document: &doc,
+        owner_password: "owner",
+        user_password: "secret",
+        key_length: 128,
+        permissions: Permissions::all(),
+    })
+    .expect("Failed to build encryption state");
+    
+    // Streams are written as they are; encrypt them up front
+    for (&object_id, object) in doc.objects.iter_mut() {
+        if matches!(object, Object::Stream(_)) {
+            encrypt_object(&state, object_id, object).expect("Failed to encrypt stream");
+        }
+    }
+    let encrypt = doc.add_object(state.encode().expect("Failed to encode encryption dictionary"));
+    doc.trailer.set("Encrypt", encrypt);
+    
+    // The object stream the writer is going to create, and the number it will get
+    let options = SaveOptions::builder().use_object_streams(true).use_xref_streams(true).build();
+    let mut packed = ObjectStream::builder()
+        .max_objects(options.object_stream_config.max_objects_per_stream)
+        .compression_level(options.object_stream_config.compression_level)
+        .build();
+    for (&object_id, object) in &doc.objects {
+        if ObjectStream::can_be_compressed(object_id, object, &doc) {
+            packed.add_object(object_id, object.clone()).expect("Failed to pack object");
+        }
+    }
+    let packed_id = (doc.max_id + 1, 0);
+    let packed = packed.to_stream_object().expect("Failed to build object stream");
+    
+    let mut bytes = Vec::new();
+    doc.save_with_options(&mut bytes, options).expect("Failed to save document");
+    
+    let start = bytes.windows(packed.content.len())
+        .position(|window| window == packed.content.as_slice())
+        .expect("Object stream not found in the saved document");
+    let mut encrypted = Object::Stream(packed);
+    encrypt_object(&state, packed_id, &mut encrypted).expect("Failed to encrypt object stream");
+    let encrypted = encrypted.as_stream().unwrap().content.clone();
+    bytes[start..start + encrypted.len()].copy_from_slice(&encrypted);
+    
+    bytes
+}
+
+/// Create an encrypted copy of the simple PDF
+///
+/// An empty `user_password` produces a document that only has an owner password.
+fn create_encrypted_pdf(cipher: Cipher, owner_password: &str, user_password: &str, permissions: Permissions) -> Document {
+    let mut doc = create_simple_pdf();
+    
+    // The file identifier is part of the key derivation for RC4 and AES-128
+    let id = b"pdf-reader-tests".to_vec();
+    doc.trailer.set("ID", Object::Array(vec![
+        Object::String(id.clone(), StringFormat::Hexadecimal),
+        Object::String(id, StringFormat::Hexadecimal),
+    ]));
+    
+    // Fixed key so regenerated fixtures are byte-for-byte reproducible
+    let file_encryption_key = [0x42u8; 32];
+    
+    let version = match cipher {
+        Cipher::Rc4 => EncryptionVersion::V2 {
+            document: &doc,
+            owner_password,
+            user_password,
+            key_length: 128,
+            permissions,
+        },
+        Cipher::Aes128 => {
+            let crypt_filter: Arc<dyn CryptFilter> = Arc::new(Aes128CryptFilter);
+            EncryptionVersion::V4 {
+                document: &doc,
+                encrypt_metadata: true,
+                crypt_filters: BTreeMap::from([(b"StdCF".to_vec(), crypt_filter)]),
+                stream_filter: b"StdCF".to_vec(),
+                string_filter: b"StdCF".to_vec(),
+                owner_password,
+                user_password,
+                permissions,
+            }
+        }
+        Cipher::Aes256 => {
+            let crypt_filter: Arc<dyn CryptFilter> = Arc::new(Aes256CryptFilter);
+            EncryptionVersion::V5 {
+                encrypt_metadata: true,
+                crypt_filters: BTreeMap::from([(b"StdCF".to_vec(), crypt_filter)]),
+                file_encryption_key: &file_encryption_key,
+                stream_filter: b"StdCF".to_vec(),
+                string_filter: b"StdCF".to_vec(),
+                owner_password,
+                user_password,
+                permissions,
+            }
+        }
+    };
+    
+    let state = EncryptionState::try_from(version).expect("Failed to build encryption state");
+    doc.encrypt(&state).expect("Failed to encrypt document");
+    
+    doc
+}
+
 /// Generate all test fixtures
 pub fn generate_all_fixtures() -> std::io::Result<()> {
     let fixtures_path = Path::new(FIXTURES_DIR);
@@ -189,18 +1707,159 @@ pub fn generate_all_fixtures() -> std::io::Result<()> {
     // Generate simple.pdf
     let mut simple_pdf = create_simple_pdf();
     simple_pdf.save(fixtures_path.join("simple.pdf"))
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))?;
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
     println!("Created: tests/fixtures/simple.pdf");
     
     // Generate multi-page.pdf
     let mut multi_page_pdf = create_multi_page_pdf();
     multi_page_pdf.save(fixtures_path.join("multi-page.pdf"))
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))?;
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
     println!("Created: tests/fixtures/multi-page.pdf");
     
+    // Generate outline.pdf
+    let mut outline_pdf = create_outline_pdf();
+    outline_pdf.save(fixtures_path.join("outline.pdf"))
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    println!("Created: tests/fixtures/outline.pdf");
+    
+    // Generate page-labels.pdf
+    let mut page_labels = create_page_labels_pdf();
+    page_labels.save(fixtures_path.join("page-labels.pdf"))
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    println!("Created: tests/fixtures/page-labels.pdf");
+    
+    // Generate page-labels-huge-start.pdf
+    let mut huge_start = create_page_labels_huge_start_pdf();
+    huge_start.save(fixtures_path.join("page-labels-huge-start.pdf"))
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    println!("Created: tests/fixtures/page-labels-huge-start.pdf");
+    
+    // Generate layout.pdf
+    let mut layout_pdf = create_layout_pdf();
+    layout_pdf.save(fixtures_path.join("layout.pdf"))
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    println!("Created: tests/fixtures/layout.pdf");
+    
+    // Generate columns.pdf
+    let mut columns_pdf = create_columns_pdf();
+    columns_pdf.save(fixtures_path.join("columns.pdf"))
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    println!("Created: tests/fixtures/columns.pdf");
+    
+    // Generate spacing.pdf
+    let mut spacing_pdf = create_spacing_pdf();
+    spacing_pdf.save(fixtures_path.join("spacing.pdf"))
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    println!("Created: tests/fixtures/spacing.pdf");
+    
+    // Generate fonts.pdf
+    let mut fonts_pdf = create_fonts_pdf();
+    fonts_pdf.save(fixtures_path.join("fonts.pdf"))
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    println!("Created: tests/fixtures/fonts.pdf");
+    
+    // Generate cjk-cmaps.pdf
+    let mut cjk_cmaps_pdf = create_cjk_cmaps_pdf();
+    cjk_cmaps_pdf.save(fixtures_path.join("cjk-cmaps.pdf"))
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    println!("Created: tests/fixtures/cjk-cmaps.pdf");
+    
+    // Generate malformed-widths.pdf
+    let mut malformed_widths_pdf = create_malformed_widths_pdf();
+    malformed_widths_pdf.save(fixtures_path.join("malformed-widths.pdf"))
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    println!("Created: tests/fixtures/malformed-widths.pdf");
+    
+    // Generate rtl.pdf
+    let mut rtl_pdf = create_rtl_pdf();
+    rtl_pdf.save(fixtures_path.join("rtl.pdf"))
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    println!("Created: tests/fixtures/rtl.pdf");
+    
+    // Generate vertical.pdf
+    let mut vertical_pdf = create_vertical_pdf();
+    vertical_pdf.save(fixtures_path.join("vertical.pdf"))
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    println!("Created: tests/fixtures/vertical.pdf");
+    
+    // Generate normalization.pdf
+    let mut normalization_pdf = create_normalization_pdf();
+    normalization_pdf.save(fixtures_path.join("normalization.pdf"))
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    println!("Created: tests/fixtures/normalization.pdf");
+    
+    // Generate furniture.pdf
+    let mut furniture_pdf = create_furniture_pdf();
+    furniture_pdf.save(fixtures_path.join("furniture.pdf"))
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    println!("Created: tests/fixtures/furniture.pdf");
+    
+    // Generate tables.pdf
+    let mut tables_pdf = create_tables_pdf();
+    tables_pdf.save(fixtures_path.join("tables.pdf"))
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    println!("Created: tests/fixtures/tables.pdf");
+    
+    // Generate markdown.pdf
+    let mut markdown_pdf = create_markdown_pdf();
+    markdown_pdf.save(fixtures_path.join("markdown.pdf"))
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    println!("Created: tests/fixtures/markdown.pdf");
+    
+    // Generate lists.pdf
+    let mut lists_pdf = create_lists_pdf();
+    lists_pdf.save(fixtures_path.join("lists.pdf"))
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    println!("Created: tests/fixtures/lists.pdf");
+    
+    // Generate links.pdf
+    let mut links_pdf = create_links_pdf();
+    links_pdf.save(fixtures_path.join("links.pdf"))
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    println!("Created: tests/fixtures/links.pdf");
+    
+    // Generate tagged.pdf
+    let mut tagged_pdf = create_tagged_pdf();
+    tagged_pdf.save(fixtures_path.join("tagged.pdf"))
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    println!("Created: tests/fixtures/tagged.pdf");
+    
+    // Generate xmp-metadata.pdf
+    let mut xmp_pdf = create_xmp_pdf();
+    xmp_pdf.save(fixtures_path.join("xmp-metadata.pdf"))
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    println!("Created: tests/fixtures/xmp-metadata.pdf");
+    
+    // Generate password-protected variants of simple.pdf (user password "secret")
+    let encrypted = [
+        ("encrypted-rc4.pdf", Cipher::Rc4),
+        ("encrypted-aes128.pdf", Cipher::Aes128),
+        ("encrypted-aes256.pdf", Cipher::Aes256),
+    ];
+    for (file_name, cipher) in encrypted {
+        let mut encrypted_pdf = create_encrypted_pdf(cipher, "owner", "secret", Permissions::all());
+        encrypted_pdf.save(fixtures_path.join(file_name))
+            .map_err(|e| std::io::Error::other(e.to_string()))?;
+        println!("Created: tests/fixtures/{}", file_name);
+    }
+    
+    // Generate encrypted-object-streams.pdf (page tree packed in an encrypted object stream)
+    std::fs::write(fixtures_path.join("encrypted-object-streams.pdf"), create_encrypted_object_streams_pdf())?;
+    println!("Created: tests/fixtures/encrypted-object-streams.pdf");
+    
+    // Generate owner-password-only.pdf (opens without a password, printing only)
+    let mut owner_only_pdf = create_encrypted_pdf(Cipher::Aes128, "owner", "", Permissions::PRINTABLE);
+    owner_only_pdf.save(fixtures_path.join("owner-password-only.pdf"))
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    println!("Created: tests/fixtures/owner-password-only.pdf");
+    
     Ok(())
 }
 
+fn main() {
+    generate_all_fixtures().expect("Failed to generate test fixtures");
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -211,7 +1870,3 @@ mod tests {
         generate_all_fixtures().expect("Failed to generate test fixtures");
     }
 }
-
-fn main() {
-    generate_all_fixtures().expect("Failed to generate test fixtures");
-}
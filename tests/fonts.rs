@@ -0,0 +1,70 @@
+//! Font decoding: encodings, ToUnicode maps, CID fonts and their widths
+
+mod common;
+
+use common::fixture;
+use pdf_reader_mcp_server::{FontRecovery, PageSelection, PdfReader, RecoveredFont, TextOptions};
+
+#[test]
+fn decodes_differences_tounicode_and_cid_fonts() {
+    let text = PdfReader::extract_text(&fixture("fonts.pdf"), None).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    // /Differences with AGL names, a bfchar/bfrange subset, Identity-H with a ToUnicode
+    // map, and predefined UTF-16 and Shift-JIS CMaps without one
+    for expected in ["find café “quoted”", "Sub abcde.", "日本語 ffi", "中文😀", "テスト A"] {
+        assert!(lines.contains(&expected), "{text}");
+    }
+}
+
+#[test]
+fn decodes_every_predefined_cmap_family() {
+    let text = PdfReader::extract_text(&fixture("cjk-cmaps.pdf"), None).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            // UniGB-UCS2-H, UniGB-UTF16-H, UniJIS-UTF8-H and UniJIS-UTF32-H
+            "中文",
+            "中文😀",
+            "日本語",
+            "語😀",
+            // 90ms-RKSJ-H, EUC-H and H
+            "テスト A",
+            "日本語 JP",
+            "日本",
+            // GBK-EUC-H, GBK2K-H, B5pc-H and KSC-EUC-H
+            "简体中文 GB",
+            "中文ä",
+            "繁體中文 B5",
+            "한국어 KR",
+        ]
+    );
+}
+
+#[test]
+fn widths_outside_the_code_range_are_skipped() {
+    let text = PdfReader::extract_text(&fixture("malformed-widths.pdf"), None).unwrap();
+    for expected in ["Simple widths", "Negative first", "AB widths"] {
+        assert!(text.contains(expected), "{text}");
+    }
+
+    // The /W groups after the bad ones still apply: A and B are 600 units wide, the
+    // rest take the default width of 500
+    let options = TextOptions::default();
+    let layout = PdfReader::extract_page_layout(&fixture("malformed-widths.pdf"), 1, true, &options, None).unwrap();
+    let line = layout.lines.iter().find(|line| line.text == "AB widths").unwrap();
+    let glyphs = line.words.iter().flat_map(|word| word.glyphs.as_deref().unwrap_or_default());
+    let widths: Vec<f64> = glyphs.map(|glyph| glyph.bbox.x1 - glyph.bbox.x0).collect();
+    assert!((widths[0] - 7.2).abs() < 1e-6 && (widths[1] - 7.2).abs() < 1e-6, "{widths:?}");
+    assert!((widths[2] - 6.0).abs() < 1e-6, "{widths:?}");
+}
+
+#[test]
+fn recovers_type3_text_from_glyph_names_and_reports_it() {
+    let options = TextOptions::default();
+    let records = PdfReader::extract_page_texts(&fixture("fonts.pdf"), &PageSelection::All, &options, None).unwrap();
+    assert!(records[0].text.lines().any(|line| line == "Affect"), "{}", records[0].text);
+    // TeX glyph names such as /a11 give the character code of the ff ligature
+    let recovered = RecoveredFont { font: "F6".to_string(), method: FontRecovery::Type3GlyphNames };
+    assert_eq!(records[0].recovered_fonts, [recovered]);
+}
@@ -0,0 +1,38 @@
+//! Removing running headers, footers and page numbers
+
+mod common;
+
+use common::{fixture, page_texts};
+use pdf_reader_mcp_server::{PageSelection, PdfReader, TextOptions};
+
+#[test]
+fn strips_alternating_headers_and_page_numbers() {
+    let options = TextOptions { strip_furniture: true, ..Default::default() };
+    let selection = PageSelection::All;
+    let records = PdfReader::extract_page_texts(&fixture("furniture.pdf"), &selection, &options, None).unwrap();
+    assert_eq!(records.len(), 6);
+
+    // Odd and even pages carry different running heads
+    assert_eq!(records[0].furniture, ["Fern Survey 2024", "Page 1 of 6"]);
+    assert_eq!(records[1].furniture, ["Northern Valley Field Report", "Page 2 of 6"]);
+    let counts = "3 Counts\nFronds were counted on a square metre\nat every site, 1,204 of them in total.";
+    assert_eq!(records[2].text, counts);
+    // Section numbers equal to the page number are not mistaken for page numbers
+    assert!(records.iter().all(|record| record.text.starts_with(&format!("{} ", record.page))));
+}
+
+#[test]
+fn short_selections_are_compared_with_their_neighbours() {
+    let options = TextOptions { strip_furniture: true, ..Default::default() };
+    let selection = PageSelection::Page(4.into());
+    let records = PdfReader::extract_page_texts(&fixture("furniture.pdf"), &selection, &options, None).unwrap();
+    assert_eq!(records[0].furniture, ["Northern Valley Field Report", "Page 4 of 6"]);
+    assert!(records[0].text.starts_with("4 Spores"), "{}", records[0].text);
+}
+
+#[test]
+fn furniture_is_kept_by_default() {
+    let text = &page_texts("furniture.pdf", &TextOptions::default())[0];
+    assert_eq!(text.lines().next(), Some("Fern Survey 2024"));
+    assert_eq!(text.lines().last(), Some("Page 1 of 6"));
+}
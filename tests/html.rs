@@ -0,0 +1,85 @@
+//! HTML export
+
+mod common;
+
+use common::fixture;
+use pdf_reader_mcp_server::{PageSelection, PdfReader, TextOptions};
+
+#[test]
+fn pages_become_sections_with_links_from_annotations() {
+    let html =
+        PdfReader::export_html(&fixture("links.pdf"), &PageSelection::All, &TextOptions::default(), None).unwrap();
+    assert!(html.contains("<section id=\"page-1\" data-page=\"1\">\n<h1>Survey Report</h1>"), "{html}");
+    assert!(html.contains("<section id=\"page-2\" data-page=\"2\">"), "{html}");
+    // URIs are escaped, and GoTo links point at the section of their page
+    let uri_link = "<a href=\"https://example.org/survey?year=2024&amp;format=full\">project website</a>";
+    assert!(html.contains(uri_link), "{html}");
+    assert!(html.contains("<a href=\"#page-2\">page 2</a>"), "{html}");
+    // javascript: URIs are not turned into links
+    assert!(html.contains("<p>Do not follow this script link.</p>"), "{html}");
+}
+
+#[test]
+fn title_follows_the_same_metadata_precedence_as_get_info() {
+    let file = fixture("xmp-metadata.pdf");
+    let html = PdfReader::export_html(&file, &PageSelection::All, &TextOptions::default(), None).unwrap();
+    let info = PdfReader::get_info(&file, None).unwrap();
+    assert_eq!(info.title.as_deref(), Some("XMP Test Document"));
+    assert!(html.contains("<title>XMP Test Document</title>"), "{html}");
+}
+
+#[test]
+fn nested_lists_close_and_reopen_with_their_items() {
+    let html =
+        PdfReader::export_html(&fixture("lists.pdf"), &PageSelection::All, &TextOptions::default(), None).unwrap();
+    // Sub-lists open inside the <li> of their parent item, and close with it when a
+    // shallower item follows, over two levels at once after "b) south slope"
+    let lists = "<p>Survey steps:</p>\n\
+        <ol start=\"4\">\n\
+        <li>Mark the plots on the map\n\
+        <ul>\n\
+        <li>Oak plots</li>\n\
+        <li>Beech plots\n\
+        <ul>\n\
+        <li>a) north slope</li>\n\
+        <li>b) south slope</li>\n\
+        </ul>\n\
+        </li>\n\
+        </ul>\n\
+        </li>\n\
+        <li>Count the ferns in each plot\n\
+        <ol>\n\
+        <li>Fronds</li>\n\
+        <li>Spore cases</li>\n\
+        </ol>\n\
+        </li>\n\
+        </ol>\n\
+        <ul>\n\
+        <li>Write up the counts</li>\n\
+        </ul>\n\
+        <p>Send the report to the survey office.</p>\n";
+    assert!(html.contains(lists), "{html}");
+}
+
+#[test]
+fn tables_have_header_rows_and_spanning_cells() {
+    let html =
+        PdfReader::export_html(&fixture("tables.pdf"), &PageSelection::All, &TextOptions::default(), None).unwrap();
+    // Two header rows, the first with a cell over both of them and one over two columns
+    let table = "<table>\n\
+        <thead>\n\
+        <tr><th rowspan=\"2\">Segment</th><th colspan=\"2\">Revenue</th></tr>\n\
+        <tr><th>2024</th><th>2023</th></tr>\n\
+        </thead>\n\
+        <tbody>\n\
+        <tr><td>Cloud</td><td>1,204</td><td>980</td></tr>\n\
+        <tr><td>Devices</td><td>310</td><td>355</td></tr>\n\
+        <tr><td>Total</td><td>1,514</td><td>1,335</td></tr>\n\
+        </tbody>\n\
+        </table>\n";
+    assert!(html.contains(table), "{html}");
+    // One header row, whose empty corner cell is still a heading cell
+    let header = "<thead>\n<tr><th></th><th>2024</th><th>2023</th></tr>\n</thead>\n<tbody>\n\
+        <tr><td>Revenue</td><td>12,480</td><td>11,020</td></tr>\n";
+    assert!(html.contains(header), "{html}");
+}
@@ -0,0 +1,42 @@
+//! Page layout: lines, words and glyphs with their positions
+
+mod common;
+
+use common::fixture;
+use pdf_reader_mcp_server::{PdfError, PdfReader, TextOptions};
+
+#[test]
+fn reports_page_geometry_and_lines_with_bounding_boxes() {
+    let options = TextOptions::default();
+    let layout = PdfReader::extract_page_layout(&fixture("layout.pdf"), 1, false, &options, None).unwrap();
+    assert_eq!((layout.page, layout.width, layout.height, layout.rotation), (1, 612.0, 792.0, 0));
+
+    let date = layout.lines.iter().find(|line| line.text == "Date").unwrap();
+    assert_eq!((date.bbox.x0, date.bbox.y0, date.bbox.y1), (72.0, 678.0, 688.0));
+    assert!(!date.vertical);
+
+    // Runs on one baseline form one line, whatever their font size
+    let heading = &layout.lines[0];
+    assert_eq!(heading.text, "Account Statement Period: March 2024");
+    let words: Vec<(&str, f64)> = heading.words.iter().map(|word| (word.text.as_str(), word.font_size)).collect();
+    assert_eq!(words, [("Account", 16.0), ("Statement", 16.0), ("Period:", 10.0), ("March", 10.0), ("2024", 10.0)]);
+    assert!(heading.words.iter().all(|word| word.font == "Helvetica" && word.glyphs.is_none()));
+}
+
+#[test]
+fn lists_glyphs_when_asked() {
+    let options = TextOptions::default();
+    let layout = PdfReader::extract_page_layout(&fixture("layout.pdf"), 1, true, &options, None).unwrap();
+    let word = layout.lines.iter().flat_map(|line| &line.words).find(|word| word.text == "1,250.00").unwrap();
+    let glyphs = word.glyphs.as_ref().unwrap();
+    assert_eq!(glyphs.iter().map(|glyph| glyph.text.as_str()).collect::<String>(), "1,250.00");
+    assert_eq!(glyphs[0].bbox.x0, word.bbox.x0);
+    assert_eq!(glyphs.last().unwrap().bbox.x1, word.bbox.x1);
+    assert!(glyphs.windows(2).all(|pair| pair[0].bbox.x1 <= pair[1].bbox.x0 + 1e-9));
+}
+
+#[test]
+fn page_past_the_end_is_reported() {
+    let error = PdfReader::extract_page_layout(&fixture("layout.pdf"), 2, false, &TextOptions::default(), None);
+    assert!(matches!(error, Err(PdfError::PageNotFound(2, 1))));
+}
@@ -0,0 +1,51 @@
+//! Link annotations with their targets and anchor text
+
+mod common;
+
+use common::fixture;
+use pdf_reader_mcp_server::{Link, LinkAction, PageSelection, PdfReader, TextOptions};
+
+fn links(selection: &PageSelection) -> Vec<Link> {
+    PdfReader::extract_links(&fixture("links.pdf"), selection, &TextOptions::default(), None).unwrap()
+}
+
+#[test]
+fn lists_links_with_their_anchor_text_in_page_order() {
+    let links = links(&PageSelection::All);
+    let anchors: Vec<(u32, &str)> = links.iter().map(|link| (link.page, link.text.as_str())).collect();
+    assert_eq!(
+        anchors,
+        [
+            (1, "project website"),
+            (1, "page 2"),
+            (1, "script link."),
+            (2, "introduction."),
+            (2, "2023 survey"),
+            (2, "raw data sheet for every site"),
+        ]
+    );
+}
+
+#[test]
+fn resolves_every_action_kind() {
+    let actions: Vec<LinkAction> = links(&PageSelection::All).into_iter().map(|link| link.action).collect();
+    let survey = "https://example.org/survey?year=2024&format=full".to_string();
+    assert_eq!(actions[0], LinkAction::Uri { uri: survey });
+    assert_eq!(actions[1], LinkAction::GoTo { page: Some(2), destination: None });
+    // Named destinations are resolved to their page and keep their name
+    let introduction = Some("introduction".to_string());
+    assert_eq!(actions[3], LinkAction::GoTo { page: Some(1), destination: introduction });
+    let file = "survey-2023.pdf".to_string();
+    assert_eq!(actions[4], LinkAction::GoToR { file, page: Some(4), destination: None, new_window: None });
+    let file = Some("data/sites.csv".to_string());
+    assert_eq!(actions[5], LinkAction::Launch { file, parameters: None, new_window: Some(true) });
+}
+
+#[test]
+fn quad_points_limit_the_anchor_text_of_links_that_wrap_lines() {
+    let links = links(&PageSelection::Page(2.into()));
+    let launch = links.last().unwrap();
+    // The rectangle spans both lines in full; only the words under the quadrilaterals count
+    assert_eq!((launch.bbox.x0, launch.bbox.y0, launch.bbox.x1, launch.bbox.y1), (72.0, 668.0, 352.0, 695.0));
+    assert_eq!(launch.text, "raw data sheet for every site");
+}
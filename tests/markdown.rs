@@ -0,0 +1,45 @@
+//! Markdown conversion: headings, paragraphs, lists and tables
+
+mod common;
+
+use common::fixture;
+use pdf_reader_mcp_server::{PageSelection, PdfReader, TextOptions};
+
+fn markdown(name: &str, selection: &PageSelection, page_anchors: bool) -> String {
+    PdfReader::extract_markdown(&fixture(name), selection, &TextOptions::default(), page_anchors, None).unwrap()
+}
+
+#[test]
+fn infers_heading_levels_from_font_size() {
+    let markdown = markdown("markdown.pdf", &PageSelection::All, false);
+    let headings: Vec<&str> = markdown.lines().filter(|line| line.starts_with('#')).collect();
+    assert_eq!(headings, ["# Field Guide to Ferns", "## 1 Habitat", "### Collecting spores", "## 2 Species"]);
+}
+
+#[test]
+fn joins_paragraph_and_list_item_lines() {
+    let markdown = markdown("markdown.pdf", &PageSelection::All, false);
+    assert!(markdown.contains("\n\nThree habitats hold most of the species in the valley:\n\n"), "{markdown}");
+    let bullets = "- Woodland floors under oak and beech\n\
+        - Stream banks, where the spray of the water keeps the fronds wet all summer\n    \
+        - Waterfall ledges\n\
+        - Old stone walls\n";
+    assert!(markdown.contains(bullets), "{markdown}");
+    assert!(markdown.contains("\n3. Fold the paper around the spores and keep it in a dry place until sowing.\n"));
+}
+
+#[test]
+fn converts_tables_to_pipe_tables() {
+    let markdown = markdown("markdown.pdf", &PageSelection::All, false);
+    let table = "| Species | Sites | Fronds |\n| --- | ---: | ---: |\n| Hart’s-tongue | 9 | 412 |\n";
+    assert!(markdown.contains(table), "{markdown}");
+    assert!(markdown.contains("| Male fern | 12 | 404 |\n\nCounts are the totals of both visits."), "{markdown}");
+}
+
+#[test]
+fn page_anchors_start_each_page() {
+    let markdown = markdown("multi-page.pdf", &PageSelection::Pages("1-2".to_string()), true);
+    assert!(markdown.starts_with("<a id=\"page-1\"></a>\n\nPage 1: Introduction"), "{markdown}");
+    assert!(markdown.contains("\n\n<a id=\"page-2\"></a>\n\nPage 2: Main content section."), "{markdown}");
+    assert!(!markdown.contains("page-3"), "{markdown}");
+}
@@ -0,0 +1,108 @@
+//! Info dictionary metadata: standard entries, PDF dates and custom keys
+
+mod common;
+
+use common::fixture;
+use lopdf::{dictionary, Dictionary, Document, Object};
+use pdf_reader_mcp_server::{PdfInfo, PdfReader};
+use tempfile::NamedTempFile;
+
+/// Document info of a one-page document with the given Info dictionary
+fn info_of(info: Dictionary) -> PdfInfo {
+    let mut doc = Document::with_version("1.5");
+    let pages_id = doc.new_object_id();
+    let page = doc.add_object(dictionary! {
+        "Type" => "Page",
+        "Parent" => pages_id,
+        "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+    });
+    let pages = dictionary! { "Type" => "Pages", "Kids" => vec![page.into()], "Count" => 1 };
+    doc.objects.insert(pages_id, Object::Dictionary(pages));
+    let catalog = doc.add_object(dictionary! { "Type" => "Catalog", "Pages" => pages_id });
+    let info = doc.add_object(info);
+    doc.trailer.set("Root", catalog);
+    doc.trailer.set("Info", info);
+
+    let file = NamedTempFile::new().unwrap();
+    doc.save(file.path()).unwrap();
+    PdfReader::get_info(file.path().to_str().unwrap(), None).unwrap()
+}
+
+/// The creation date reported for a raw `/CreationDate` string
+fn creation_date(raw: &str) -> Option<String> {
+    info_of(dictionary! { "CreationDate" => Object::string_literal(raw) }).creation_date
+}
+
+#[test]
+fn reads_the_standard_info_entries() {
+    let info = PdfReader::get_info(&fixture("simple.pdf"), None).unwrap();
+    assert_eq!(info.title.as_deref(), Some("Simple Test PDF"));
+    assert_eq!(info.author.as_deref(), Some("Test Author"));
+    assert_eq!(info.subject.as_deref(), Some("Testing PDF Reader"));
+    assert_eq!(info.keywords.as_deref(), Some("pdf, testing, fixtures"));
+    assert_eq!(info.creator.as_deref(), Some("PDF Reader Test Suite"));
+    assert_eq!(info.producer.as_deref(), Some("lopdf"));
+    assert_eq!(info.creation_date.as_deref(), Some("2024-01-15T09:30:00+01:00"));
+    assert_eq!(info.modification_date.as_deref(), Some("2024-03-01T17:05:00Z"));
+    assert_eq!(info.trapped.as_deref(), Some("False"));
+}
+
+#[test]
+fn converts_full_and_partial_dates() {
+    assert_eq!(creation_date("D:20240315123456").as_deref(), Some("2024-03-15T12:34:56"));
+    assert_eq!(creation_date("D:202403151234").as_deref(), Some("2024-03-15T12:34:00"));
+    assert_eq!(creation_date("D:20240315").as_deref(), Some("2024-03-15T00:00:00"));
+    assert_eq!(creation_date("D:202403").as_deref(), Some("2024-03-01T00:00:00"));
+    assert_eq!(creation_date("D:2024").as_deref(), Some("2024-01-01T00:00:00"));
+    // The `D:` prefix is optional in practice
+    assert_eq!(creation_date("20240315123456").as_deref(), Some("2024-03-15T12:34:56"));
+}
+
+#[test]
+fn converts_time_zone_offsets() {
+    assert_eq!(creation_date("D:20240315123456Z").as_deref(), Some("2024-03-15T12:34:56Z"));
+    assert_eq!(creation_date("D:20240315123456Z00'00'").as_deref(), Some("2024-03-15T12:34:56Z"));
+    assert_eq!(creation_date("D:20240315123456+01'00'").as_deref(), Some("2024-03-15T12:34:56+01:00"));
+    assert_eq!(creation_date("D:20240315123456-05'30").as_deref(), Some("2024-03-15T12:34:56-05:30"));
+    assert_eq!(creation_date("D:20240315123456+0930").as_deref(), Some("2024-03-15T12:34:56+09:30"));
+    assert_eq!(creation_date("D:20240315123456-08").as_deref(), Some("2024-03-15T12:34:56-08:00"));
+}
+
+#[test]
+fn keeps_malformed_dates_as_written() {
+    for raw in [
+        "last Tuesday",
+        "D:202",
+        "D:2024031",
+        "D:20241315",
+        "D:20240332",
+        "D:20240315250000",
+        "D:20240315123456+25'00'",
+        "D:20240315123456X",
+    ] {
+        assert_eq!(creation_date(raw).as_deref(), Some(raw), "{raw}");
+    }
+}
+
+#[test]
+fn lists_custom_info_entries() {
+    let info = PdfReader::get_info(&fixture("simple.pdf"), None).unwrap();
+    assert_eq!(info.custom.get("Department").map(String::as_str), Some("Quality Assurance"));
+    assert!(!info.custom.contains_key("Title"));
+
+    let info = info_of(dictionary! {
+        "Title" => Object::string_literal("Report"),
+        "Client" => Object::string_literal("ACME"),
+        "Status" => "Final",
+        "Revision" => 3,
+        "Score" => 0.5,
+        "Reviewed" => true,
+        "Tags" => vec![Object::string_literal("a")],
+    });
+    let custom: Vec<(&str, &str)> = info.custom.iter().map(|(key, value)| (key.as_str(), value.as_str())).collect();
+    // Standard keys stay out of `custom`, and values that are not plain text are skipped
+    assert_eq!(
+        custom,
+        [("Client", "ACME"), ("Reviewed", "true"), ("Revision", "3"), ("Score", "0.5"), ("Status", "Final")]
+    );
+}
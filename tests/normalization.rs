@@ -0,0 +1,53 @@
+//! Text normalization: ligatures, control characters, hyphenation and Unicode forms
+
+mod common;
+
+use common::page_texts;
+use pdf_reader_mcp_server::{Normalization, TextOptions, UnicodeForm};
+
+#[test]
+fn default_expands_ligatures_and_strips_invisible_characters() {
+    let text = &page_texts("normalization.pdf", &TextOptions::default())[0];
+    let lines: Vec<&str> = text.lines().collect();
+    // The private-use bullet and the soft hyphen are dropped; line-end hyphens are kept
+    assert_eq!(
+        lines,
+        [
+            "The first floor holds the docu-",
+            "ment archive and the records room.",
+            "Its cafe\u{301} is open to self-",
+            "Service visitors.",
+        ]
+    );
+}
+
+#[test]
+fn every_step_can_be_switched() {
+    let normalization = Normalization {
+        ligatures: false,
+        dehyphenate: true,
+        unicode_form: UnicodeForm::Nfc,
+        strip_control: false,
+        collapse_whitespace: true,
+    };
+    let text = &page_texts("normalization.pdf", &TextOptions { normalization, ..Default::default() })[0];
+    let lines: Vec<&str> = text.lines().collect();
+    // Words broken across lines are joined, but hyphenated compounds keep their hyphen
+    assert_eq!(
+        lines,
+        [
+            "\u{f0b7} The \u{fb01}rst \u{fb02}oor holds the document",
+            "archive and the re\u{ad}cords room.",
+            "Its café is open to self-",
+            "Service visitors.",
+        ]
+    );
+}
+
+#[test]
+fn nfkc_folds_compatibility_characters() {
+    let normalization = Normalization { ligatures: false, unicode_form: UnicodeForm::Nfkc, ..Default::default() };
+    let text = &page_texts("normalization.pdf", &TextOptions { normalization, ..Default::default() })[0];
+    assert!(text.starts_with("The first floor"), "{text}");
+    assert!(text.contains("Its café is open"), "{text}");
+}
@@ -0,0 +1,39 @@
+//! Document outline (bookmarks)
+
+mod common;
+
+use common::fixture;
+use pdf_reader_mcp_server::{OutlineItem, PdfReader};
+
+/// Titles, levels and pages of an outline, depth first
+fn flatten(items: &[OutlineItem]) -> Vec<(String, usize, Option<u32>)> {
+    items
+        .iter()
+        .flat_map(|item| {
+            std::iter::once((item.title.clone(), item.level, item.page)).chain(flatten(&item.children))
+        })
+        .collect()
+}
+
+#[test]
+fn resolves_every_destination_form_to_a_page() {
+    let outline = PdfReader::get_outline(&fixture("outline.pdf"), None).unwrap();
+    assert_eq!(outline.len(), 3);
+    // Explicit /Dest arrays, GoTo actions, /Names tree names and catalog /Dests names
+    let expected = [
+        ("Chapter 1 Getting Started", 1, Some(1)),
+        ("Overview", 2, Some(2)),
+        ("Chapter 2 Setup", 1, Some(3)),
+        ("Installation", 2, Some(4)),
+        ("Configuration", 2, Some(5)),
+        ("Appendix", 1, Some(6)),
+    ];
+    let expected: Vec<_> = expected.iter().map(|&(title, level, page)| (title.to_string(), level, page)).collect();
+    assert_eq!(flatten(&outline), expected);
+}
+
+#[test]
+fn document_without_an_outline_has_no_entries() {
+    let outline = PdfReader::get_outline(&fixture("simple.pdf"), None).unwrap();
+    assert!(outline.is_empty());
+}
@@ -0,0 +1,58 @@
+//! Page labels: reading `/PageLabels` and addressing pages by label
+
+mod common;
+
+use common::fixture;
+use pdf_reader_mcp_server::{PageRef, PdfError, PdfReader};
+
+#[test]
+fn labels_every_page_across_number_tree_leaves() {
+    let info = PdfReader::get_info(&fixture("page-labels.pdf"), None).unwrap();
+    let labels = info.page_labels.expect("document defines page labels");
+    assert_eq!(labels, ["i", "ii", "1", "2", "3", "4", "A-11", "A-12", "Z", "AA"]);
+}
+
+#[test]
+fn reads_a_page_by_its_label() {
+    let text = PdfReader::extract_page_text(&fixture("page-labels.pdf"), PageRef::Label("A-12".into()), None).unwrap();
+    assert!(text.contains("Physical page 8"), "{text}");
+
+    let text = PdfReader::extract_page_text(&fixture("page-labels.pdf"), PageRef::Label("ii".into()), None).unwrap();
+    assert!(text.contains("Physical page 2"), "{text}");
+}
+
+#[test]
+fn numbers_are_physical_pages_in_ranges_and_selectors_alike() {
+    // Physical pages 3 and 4 are labelled "1" and "2"
+    let file = fixture("page-labels.pdf");
+    let selected = PdfReader::extract_pages_text(&file, "1-2", None).unwrap();
+    let range = PdfReader::extract_page_range_text(&file, "1", "2", None).unwrap();
+    let numbers = PdfReader::extract_page_range_text(&file, 1, 2, None).unwrap();
+    assert_eq!(range, selected);
+    assert_eq!(numbers, selected);
+    assert!(selected.contains("Physical page 1") && selected.contains("Physical page 2"), "{selected}");
+    assert!(!selected.contains("Physical page 3"), "{selected}");
+
+    let page = PdfReader::extract_page_text(&file, "2", None).unwrap();
+    assert_eq!(page, PdfReader::extract_pages_text(&file, "2", None).unwrap());
+    assert!(page.contains("Physical page 2"), "{page}");
+}
+
+#[test]
+fn unknown_label_is_reported() {
+    let error = PdfReader::extract_page_text(&fixture("page-labels.pdf"), PageRef::Label("xiv".into()), None);
+    assert!(matches!(error, Err(PdfError::PageLabelNotFound(label)) if label == "xiv"));
+}
+
+#[test]
+fn huge_start_numbers_neither_overflow_nor_hang() {
+    let info = PdfReader::get_info(&fixture("page-labels-huge-start.pdf"), None).unwrap();
+    let labels = info.page_labels.expect("document defines page labels");
+    assert_eq!(labels, [
+        "2147483647", "2147483648", "2147483647", "2147483647", "YYYYYYYYYY", "ZZZZZZZZZZ", "261",
+    ]);
+
+    // Every tool reads the labels, so text extraction must survive them too
+    let text = PdfReader::extract_text(&fixture("page-labels-huge-start.pdf"), None).unwrap();
+    assert!(text.contains("Physical page 7"), "{text}");
+}
@@ -0,0 +1,94 @@
+//! Per-page text records with labels and status
+
+mod common;
+
+use common::fixture;
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Document, Object, Stream};
+use pdf_reader_mcp_server::{PageError, PageSelection, PageStatus, PageText, PdfError, PdfReader, TextOptions};
+use tempfile::NamedTempFile;
+
+/// Save a two-page document whose second page draws nothing
+fn document_with_a_blank_page() -> NamedTempFile {
+    let mut doc = Document::with_version("1.5");
+    let pages_id = doc.new_object_id();
+    let font = doc.add_object(dictionary! { "Type" => "Font", "Subtype" => "Type1", "BaseFont" => "Helvetica" });
+    let resources = doc.add_object(dictionary! { "Font" => dictionary! { "F1" => font } });
+    let text = Content {
+        operations: vec![
+            Operation::new("BT", vec![]),
+            Operation::new("Tf", vec!["F1".into(), 12.into()]),
+            Operation::new("Td", vec![72.into(), 700.into()]),
+            Operation::new("Tj", vec![Object::string_literal("Only page with text")]),
+            Operation::new("ET", vec![]),
+        ],
+    };
+    let text = doc.add_object(Stream::new(dictionary! {}, text.encode().unwrap()));
+    let blank = doc.add_object(Stream::new(dictionary! {}, Vec::new()));
+    let kids: Vec<Object> = [text, blank]
+        .into_iter()
+        .map(|contents| doc.add_object(dictionary! { "Type" => "Page", "Parent" => pages_id, "Contents" => contents }))
+        .map(Object::Reference)
+        .collect();
+    doc.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => kids,
+            "Count" => 2,
+            "Resources" => resources,
+            "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+        }),
+    );
+    let catalog = doc.add_object(dictionary! { "Type" => "Catalog", "Pages" => pages_id });
+    doc.trailer.set("Root", catalog);
+
+    let file = NamedTempFile::new().unwrap();
+    doc.save(file.path()).unwrap();
+    file
+}
+
+#[test]
+fn records_carry_page_numbers_and_labels() {
+    let selection = PageSelection::Pages("1,A-12".to_string());
+    let records =
+        PdfReader::extract_page_texts(&fixture("page-labels.pdf"), &selection, &TextOptions::default(), None).unwrap();
+    let pages: Vec<(u32, Option<&str>)> = records.iter().map(|record| (record.page, record.label.as_deref())).collect();
+    assert_eq!(pages, [(1, Some("i")), (8, Some("A-12"))]);
+    assert!(records.iter().all(|record| record.status == PageStatus::Ok && record.error.is_none()));
+}
+
+#[test]
+fn blank_pages_are_reported_as_empty() {
+    let file = document_with_a_blank_page();
+    let path = file.path().to_str().unwrap();
+    let records = PdfReader::extract_page_texts(path, &PageSelection::All, &TextOptions::default(), None).unwrap();
+
+    assert_eq!(records.len(), 2);
+    assert_eq!((records[0].status, records[0].text.as_str()), (PageStatus::Ok, "Only page with text"));
+    assert_eq!((records[1].status, records[1].text.as_str()), (PageStatus::Empty, ""));
+
+    let json = serde_json::to_value(&records).unwrap();
+    assert_eq!(json[1]["status"], "empty");
+    assert_eq!(json[1]["page"], 2);
+}
+
+#[test]
+fn page_errors_carry_the_error_code() {
+    let error = PageError::from(&PdfError::MissingObject(12, 0));
+    assert_eq!(error.code, "missing_object");
+    assert_eq!(error.message, "Object 12 0 R is missing from the document");
+
+    let record = PageText {
+        page: 3,
+        label: None,
+        text: String::new(),
+        status: PageStatus::Error,
+        error: Some(error),
+        recovered_fonts: Vec::new(),
+        furniture: Vec::new(),
+    };
+    let json = serde_json::to_value(&record).unwrap();
+    assert_eq!(json["status"], "error");
+    assert_eq!(json["error"]["code"], "missing_object");
+}
@@ -0,0 +1,107 @@
+//! Page selector syntax: numbers, labels, ranges, keywords and trailing pages
+
+mod common;
+
+use common::fixture;
+use lopdf::{dictionary, Document, Object};
+use pdf_reader_mcp_server::{PageSelection, PdfError, PdfReader, TextOptions};
+use proptest::prelude::*;
+use tempfile::NamedTempFile;
+
+/// Physical pages a selector picks from a document, in the order returned
+fn select(file: &str, selector: &str) -> Result<Vec<u32>, PdfError> {
+    let selection = PageSelection::Pages(selector.to_string());
+    let records = PdfReader::extract_page_texts(&fixture(file), &selection, &TextOptions::default(), None)?;
+    Ok(records.iter().map(|record| record.page).collect())
+}
+
+#[test]
+fn resolves_numbers_ranges_and_keywords() {
+    assert_eq!(select("multi-page.pdf", "2").unwrap(), [2]);
+    assert_eq!(select("multi-page.pdf", "last").unwrap(), [3]);
+    assert_eq!(select("multi-page.pdf", "-2").unwrap(), [2, 3]);
+    assert_eq!(select("multi-page.pdf", "odd").unwrap(), [1, 3]);
+    assert_eq!(select("multi-page.pdf", "even").unwrap(), [2]);
+    assert_eq!(select("multi-page.pdf", "2-").unwrap(), [2, 3]);
+    assert_eq!(select("multi-page.pdf", "1-2").unwrap(), [1, 2]);
+}
+
+#[test]
+fn keeps_the_given_order_without_duplicates() {
+    assert_eq!(select("multi-page.pdf", "3, 1, 3, 1-2").unwrap(), [3, 1, 2]);
+}
+
+#[test]
+fn resolves_labels_including_labels_with_dashes() {
+    assert_eq!(select("page-labels.pdf", "i-ii").unwrap(), [1, 2]);
+    // Numbers are physical pages even when a page is labelled with one
+    assert_eq!(select("page-labels.pdf", "ii-4").unwrap(), [2, 3, 4]);
+    assert_eq!(select("page-labels.pdf", "A-11-A-12").unwrap(), [7, 8]);
+    assert_eq!(select("page-labels.pdf", "A-12,i").unwrap(), [8, 1]);
+}
+
+#[test]
+fn concatenates_the_selected_pages() {
+    let text = PdfReader::extract_pages_text(&fixture("multi-page.pdf"), "3,1", None).unwrap();
+    let conclusion = text.find("Page 3: Conclusion").unwrap();
+    let introduction = text.find("Page 1: Introduction").unwrap();
+    assert!(conclusion < introduction, "{text}");
+    assert!(!text.contains("Page 2"), "{text}");
+}
+
+#[test]
+fn rejects_malformed_entries() {
+    for (selector, token) in [("0", "0"), ("1,,2", "1,,2"), ("4", "4"), ("3-1", "3-1"), ("-0", "-0"), ("", "")] {
+        let error = select("multi-page.pdf", selector).unwrap_err();
+        assert!(matches!(&error, PdfError::InvalidPageSelection(t, _) if t == token), "{selector}: {error}");
+    }
+}
+
+#[test]
+fn selects_nothing_from_a_document_without_pages() {
+    let mut doc = Document::with_version("1.5");
+    let pages = doc.add_object(dictionary! { "Type" => "Pages", "Kids" => Vec::<Object>::new(), "Count" => 0 });
+    let catalog = doc.add_object(dictionary! { "Type" => "Catalog", "Pages" => pages });
+    doc.trailer.set("Root", catalog);
+    let file = NamedTempFile::new().unwrap();
+    doc.save(file.path()).unwrap();
+    let path = file.path().to_str().unwrap();
+
+    for selector in ["last", "last-last", "1"] {
+        let error = PdfReader::extract_pages_text(path, selector, None).unwrap_err();
+        assert!(matches!(error, PdfError::InvalidPageSelection(..)), "{selector}: {error}");
+    }
+    for selector in ["odd", "-5"] {
+        assert_eq!(PdfReader::extract_pages_text(path, selector, None).unwrap(), "", "{selector}");
+    }
+    let error = PdfReader::extract_page_text(path, 1, None).unwrap_err();
+    assert!(matches!(error, PdfError::PageNotFound(1, 0)), "{error}");
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn ranges_select_their_pages_in_order_without_duplicates(
+        ranges in prop::collection::vec((1u32..=10, 1u32..=10), 1..6)
+    ) {
+        let ranges: Vec<(u32, u32)> = ranges.into_iter().map(|(a, b)| (a.min(b), a.max(b))).collect();
+        let selector: Vec<String> = ranges.iter().map(|(start, end)| format!("{start}-{end}")).collect();
+
+        let mut expected = Vec::new();
+        for page in ranges.iter().flat_map(|&(start, end)| start..=end) {
+            if !expected.contains(&page) {
+                expected.push(page);
+            }
+        }
+        prop_assert_eq!(select("page-labels.pdf", &selector.join(",")).unwrap(), expected);
+    }
+
+    #[test]
+    fn arbitrary_selectors_are_resolved_or_rejected(selector in "[0-9a-zA-Z, -]{0,12}") {
+        match select("page-labels.pdf", &selector) {
+            Ok(pages) => prop_assert!(pages.iter().all(|page| (1..=10).contains(page))),
+            Err(error) => prop_assert!(matches!(error, PdfError::InvalidPageSelection(..)), "{}", error),
+        }
+    }
+}
@@ -0,0 +1,37 @@
+//! Layout-preserving text: a fixed-width grid that keeps columns aligned
+
+mod common;
+
+use common::page_texts;
+use pdf_reader_mcp_server::{TextLayout, TextOptions};
+
+#[test]
+fn keeps_columns_aligned() {
+    let options = TextOptions { layout: TextLayout::Preserve, ..Default::default() };
+    let text = &page_texts("layout.pdf", &options)[0];
+    let lines: Vec<&str> = text.lines().collect();
+
+    // The statement is drawn column by column; the grid puts each row back together
+    let row = |date: &str| *lines.iter().find(|line| line.starts_with(date)).unwrap();
+    let opening = row("03/01");
+    let supplies = row("03/09");
+    assert!(opening.contains("Opening balance") && opening.trim_end().ends_with("1,250.00"), "{text}");
+    assert_eq!(opening.find("Opening"), supplies.find("Office"), "{text}");
+    // The closing balance keeps its indentation under the descriptions
+    let closing = lines.iter().find(|line| line.contains("Closing balance")).unwrap();
+    assert_eq!(closing.find("Closing"), opening.find("Opening"), "{text}");
+}
+
+#[test]
+fn keeps_vertical_gaps_as_blank_lines() {
+    let options = TextOptions { layout: TextLayout::Preserve, ..Default::default() };
+    let text = &page_texts("layout.pdf", &options)[0];
+    assert!(text.starts_with("Account Statement"), "{text}");
+    assert!(text.contains("Period: March 2024\n\n\nDate"), "{text}");
+}
+
+#[test]
+fn reading_layout_joins_the_words_of_a_row_with_single_spaces() {
+    let text = &page_texts("layout.pdf", &TextOptions::default())[0];
+    assert!(text.contains("\n03/09 Office supplies -84.50\n"), "{text}");
+}
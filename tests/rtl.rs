@@ -0,0 +1,23 @@
+//! Right-to-left text: logical and visual order
+
+mod common;
+
+use common::page_texts;
+use pdf_reader_mcp_server::{BidiOrder, TextOptions};
+
+#[test]
+fn reorders_hebrew_into_reading_order() {
+    let text = &page_texts("rtl.pdf", &TextOptions::default())[0];
+    let lines: Vec<&str> = text.lines().collect();
+    // Drawn in visual order with a number and brackets, drawn backwards glyph by glyph,
+    // and a Hebrew word inside an English sentence
+    assert_eq!(lines, ["שלום 2024 (עולם)", "מסמך חתום", "Contract חוזה signed"]);
+}
+
+#[test]
+fn visual_order_keeps_the_order_on_the_page() {
+    let options = TextOptions { bidi: BidiOrder::Visual, ..Default::default() };
+    let text = &page_texts("rtl.pdf", &options)[0];
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines, ["(םלוע) 2024 םולש", "םותח ךמסמ", "Contract הזוח signed"]);
+}
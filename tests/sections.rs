@@ -0,0 +1,35 @@
+//! Reading a section of the document by its outline entry
+
+mod common;
+
+use common::fixture;
+use pdf_reader_mcp_server::{PdfError, PdfReader};
+
+#[test]
+fn section_runs_to_the_next_entry_at_the_same_level() {
+    let text = PdfReader::extract_section_text(&fixture("outline.pdf"), "Chapter 2 Setup", None).unwrap();
+    assert!(text.contains("Chapter 2: Setup"), "{text}");
+    assert!(text.contains("Installation steps"), "{text}");
+    assert!(text.contains("Configuration options"), "{text}");
+    assert!(!text.contains("Appendix"), "{text}");
+    assert!(!text.contains("Overview"), "{text}");
+}
+
+#[test]
+fn last_section_runs_to_the_end_of_the_document() {
+    let text = PdfReader::extract_section_text(&fixture("outline.pdf"), "Appendix", None).unwrap();
+    assert_eq!(text.trim(), "Appendix: Troubleshooting");
+}
+
+#[test]
+fn path_selects_a_nested_entry_case_insensitively() {
+    let text =
+        PdfReader::extract_section_text(&fixture("outline.pdf"), "chapter 2 setup > installation", None).unwrap();
+    assert_eq!(text.trim(), "Installation steps for all supported platforms.");
+}
+
+#[test]
+fn unknown_section_is_reported() {
+    let error = PdfReader::extract_section_text(&fixture("outline.pdf"), "Chapter 9", None).unwrap_err();
+    assert!(matches!(&error, PdfError::SectionNotFound(section) if section == "Chapter 9"), "{error}");
+}
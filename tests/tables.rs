@@ -0,0 +1,41 @@
+//! Table detection from ruling lines and from text alignment
+
+mod common;
+
+use common::fixture;
+use pdf_reader_mcp_server::{PdfReader, TableMethod, TextOptions};
+
+#[test]
+fn ruled_table_keeps_spans_and_header_rows() {
+    let tables = PdfReader::extract_tables(&fixture("tables.pdf"), 1, &TextOptions::default(), None).unwrap();
+    assert_eq!(tables.len(), 1);
+    let table = &tables[0];
+    assert_eq!(table.method, TableMethod::RulingLines);
+    assert_eq!((table.rows, table.columns, table.header_rows), (5, 3, 2));
+
+    let spans: Vec<(&str, usize, usize)> =
+        table.cells.iter().take(2).map(|cell| (cell.text.as_str(), cell.row_span, cell.column_span)).collect();
+    assert_eq!(spans, [("Segment", 2, 1), ("Revenue", 1, 2)]);
+
+    let csv = "Segment,Revenue,\n,2024,2023\nCloud,\"1,204\",980\nDevices,310,355\nTotal,\"1,514\",\"1,335\"\n";
+    assert_eq!(table.csv, csv);
+    // Header rows are combined into the heading; numeric columns are right-aligned
+    assert!(table.markdown.starts_with("| Segment | Revenue 2024 | Revenue 2023 |\n| --- | ---: | ---: |\n"));
+}
+
+#[test]
+fn unruled_table_is_found_from_text_alignment() {
+    let tables = PdfReader::extract_tables(&fixture("tables.pdf"), 2, &TextOptions::default(), None).unwrap();
+    assert_eq!(tables.len(), 1);
+    let table = &tables[0];
+    assert_eq!(table.method, TableMethod::TextAlignment);
+    assert_eq!((table.rows, table.columns, table.header_rows), (6, 3, 1));
+    assert!(table.markdown.contains("| Cost of sales | (7,310) | (6,950) |\n"), "{}", table.markdown);
+    assert!(table.csv.ends_with("Net income,\"2,270\",\"1,430\"\n"), "{}", table.csv);
+}
+
+#[test]
+fn pages_without_tables_have_none() {
+    let tables = PdfReader::extract_tables(&fixture("simple.pdf"), 1, &TextOptions::default(), None).unwrap();
+    assert!(tables.is_empty());
+}
@@ -0,0 +1,47 @@
+//! Tagged documents read in structure-tree order
+
+mod common;
+
+use common::{fixture, page_texts};
+use pdf_reader_mcp_server::{PdfReader, TextLayout, TextOptions};
+
+fn tagged() -> TextOptions {
+    TextOptions { layout: TextLayout::Tagged, ..Default::default() }
+}
+
+#[test]
+fn reads_the_structure_tree_with_role_labels() {
+    let texts = page_texts("tagged.pdf", &tagged());
+    let lines: Vec<&str> = texts[0].lines().collect();
+    assert_eq!(lines[0], "[H1] Spring Planting Guide", "custom roles follow the role map");
+    let list = ["[LI] 1. Loosen the soil.", "[LI] 2. Sow the seeds thinly."];
+    let table = ["[TR] Crop | Soil temperature", "[TR] Tomatoes | 21 C"];
+    assert_eq!(lines[3..], [list, table].concat());
+    assert_eq!(texts[1], "[H2] Watering\n[P] Water in the early morning so the leaves dry before evening.");
+}
+
+#[test]
+fn uses_actual_text_and_alt_text() {
+    let text = &page_texts("tagged.pdf", &tagged())[0];
+    assert!(text.contains("Quick crops, for example, radishes, are ready within a month."), "{text}");
+    assert!(!text.contains("e.g."), "{text}");
+    assert!(text.contains("\n[Figure] Frame for a chart of germination by soil temperature\n"), "{text}");
+}
+
+#[test]
+fn leaves_out_artifacts() {
+    let texts = page_texts("tagged.pdf", &tagged());
+    assert!(!texts[0].contains("Garden Club Newsletter"), "{}", texts[0]);
+    assert!(!texts[1].ends_with("\n2"), "{}", texts[1]);
+
+    // The reading layout keeps them, in the order they are drawn
+    let texts = page_texts("tagged.pdf", &TextOptions::default());
+    assert!(texts[0].starts_with("Garden Club Newsletter\nSpring Planting Guide"), "{}", texts[0]);
+}
+
+#[test]
+fn untagged_documents_fall_back_to_the_reading_layout() {
+    assert_eq!(page_texts("simple.pdf", &tagged()), page_texts("simple.pdf", &TextOptions::default()));
+    assert!(PdfReader::get_info(&fixture("tagged.pdf"), None).unwrap().tagged);
+    assert!(!PdfReader::get_info(&fixture("simple.pdf"), None).unwrap().tagged);
+}
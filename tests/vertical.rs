@@ -0,0 +1,26 @@
+//! Vertical writing mode
+
+mod common;
+
+use common::{fixture, page_texts};
+use pdf_reader_mcp_server::{PdfReader, TextOptions};
+
+#[test]
+fn reads_vertical_columns_right_to_left() {
+    let text = &page_texts("vertical.pdf", &TextOptions::default())[0];
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines, ["縦書きの文章です。", "二行目も、縦に読む。", "1"]);
+}
+
+#[test]
+fn marks_vertical_lines_in_the_layout() {
+    let options = TextOptions::default();
+    let layout = PdfReader::extract_page_layout(&fixture("vertical.pdf"), 1, false, &options, None).unwrap();
+    let lines: Vec<(&str, bool)> = layout.lines.iter().map(|line| (line.text.as_str(), line.vertical)).collect();
+    assert_eq!(lines, [("縦書きの文章です。", true), ("二行目も、縦に読む。", true), ("1", false)]);
+
+    // A vertical line is taller than it is wide, and the first column is the right one
+    let [first, second, _] = &layout.lines[..] else { panic!("expected three lines") };
+    assert!(first.bbox.y1 - first.bbox.y0 > first.bbox.x1 - first.bbox.x0);
+    assert!(first.bbox.x0 > second.bbox.x1);
+}
@@ -0,0 +1,30 @@
+//! Word breaks inferred from glyph gaps
+
+mod common;
+
+use common::{fixture, page_texts};
+use pdf_reader_mcp_server::{PageSelection, PdfError, PdfReader, TextOptions};
+
+#[test]
+fn splits_words_placed_by_positioning_alone() {
+    let text = &page_texts("spacing.pdf", &TextOptions::default())[0];
+    let lines: Vec<&str> = text.lines().collect();
+    // TJ adjustments, kerning within a word, one text matrix per word and Tw word spacing
+    assert_eq!(lines, ["The quick brown fox", "Waverly AVATAR", "jumps over the", "lazy dog"]);
+}
+
+#[test]
+fn larger_word_gap_joins_words() {
+    let options = TextOptions { word_gap: 2.0, ..Default::default() };
+    let text = &page_texts("spacing.pdf", &options)[0];
+    assert!(text.starts_with("Thequickbrownfox\nWaverlyAVATAR\njumpsoverthe\n"), "{text}");
+    // A space character always separates words
+    assert!(text.ends_with("lazy dog"), "{text}");
+}
+
+#[test]
+fn word_gap_must_be_positive() {
+    let options = TextOptions { word_gap: 0.0, ..Default::default() };
+    let error = PdfReader::extract_page_texts(&fixture("spacing.pdf"), &PageSelection::All, &options, None);
+    assert!(matches!(error, Err(PdfError::InvalidOption(option, _)) if option == "word_gap"));
+}
@@ -0,0 +1,193 @@
+//! XMP metadata streams: parsing and precedence over the Info dictionary
+
+mod common;
+
+use common::fixture;
+use lopdf::{dictionary, Dictionary, Document, Object, Stream};
+use pdf_reader_mcp_server::{PdfInfo, PdfReader};
+use tempfile::NamedTempFile;
+
+/// Document info of a one-page document with the given Info dictionary and XMP packet
+fn info_with_xmp(info: Dictionary, packet: &str) -> PdfInfo {
+    let mut doc = Document::with_version("1.5");
+    let pages_id = doc.new_object_id();
+    let page = doc.add_object(dictionary! {
+        "Type" => "Page",
+        "Parent" => pages_id,
+        "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+    });
+    let pages = dictionary! { "Type" => "Pages", "Kids" => vec![page.into()], "Count" => 1 };
+    doc.objects.insert(pages_id, Object::Dictionary(pages));
+    let metadata = Stream::new(dictionary! { "Type" => "Metadata", "Subtype" => "XML" }, packet.as_bytes().to_vec());
+    let metadata = doc.add_object(metadata);
+    let catalog = doc.add_object(dictionary! { "Type" => "Catalog", "Pages" => pages_id, "Metadata" => metadata });
+    let info = doc.add_object(info);
+    doc.trailer.set("Root", catalog);
+    doc.trailer.set("Info", info);
+
+    let file = NamedTempFile::new().unwrap();
+    doc.save(file.path()).unwrap();
+    PdfReader::get_info(file.path().to_str().unwrap(), None).unwrap()
+}
+
+/// An XMP packet holding one rdf:Description with the given attributes and properties
+fn packet(attributes: &str, properties: &str) -> String {
+    format!(
+        r#"<?xpacket begin="" id="W5M0MpCehiHzreSzNTczkc9d"?>
+<x:xmpmeta xmlns:x="adobe:ns:meta/">
+  <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+    <rdf:Description rdf:about=""
+        xmlns:dc="http://purl.org/dc/elements/1.1/"
+        xmlns:xmp="http://ns.adobe.com/xap/1.0/"
+        xmlns:pdf="http://ns.adobe.com/pdf/1.3/"
+        xmlns:acme="http://example.com/ns/acme/1.0/" {attributes}>
+      {properties}
+    </rdf:Description>
+  </rdf:RDF>
+</x:xmpmeta>
+<?xpacket end="w"?>"#
+    )
+}
+
+/// Document info of a document whose only metadata is the given XMP properties
+fn xmp_info(attributes: &str, properties: &str) -> PdfInfo {
+    info_with_xmp(Dictionary::new(), &packet(attributes, properties))
+}
+
+#[test]
+fn reads_the_fixture_field_by_field() {
+    let info = PdfReader::get_info(&fixture("xmp-metadata.pdf"), None).unwrap();
+
+    // XMP is newer than the Info dictionary, so it wins where both define a field; the
+    // Info title is "Simple Test PDF" and its producer "lopdf"
+    assert_eq!(info.title.as_deref(), Some("XMP Test Document"));
+    assert_eq!(info.author.as_deref(), Some("Ada Lovelace; Charles Babbage"));
+    assert_eq!(info.subject.as_deref(), Some("Metadata & precedence test"));
+    assert_eq!(info.keywords.as_deref(), Some("xmp, metadata"));
+    assert_eq!(info.creator.as_deref(), Some("XMP Test Writer"));
+    assert_eq!(info.producer.as_deref(), Some("XMP Test Producer 2.0"));
+    assert_eq!(info.creation_date.as_deref(), Some("2024-05-01T08:00:00+02:00"));
+    assert_eq!(info.modification_date.as_deref(), Some("2024-06-10T12:30:00+02:00"));
+    // Only the Info dictionary has these
+    assert_eq!(info.trapped.as_deref(), Some("False"));
+    assert_eq!(info.custom.get("Department").map(String::as_str), Some("Quality Assurance"));
+
+    let xmp = info.xmp.expect("the fixture has an XMP stream");
+    assert_eq!(xmp.title.as_deref(), Some("XMP Test Document"));
+    assert_eq!(xmp.creators, ["Ada Lovelace", "Charles Babbage"]);
+    assert_eq!(xmp.description.as_deref(), Some("Metadata & precedence test"));
+    assert_eq!(xmp.subjects, ["xmp", "metadata"]);
+    assert_eq!(xmp.create_date.as_deref(), Some("2024-05-01T08:00:00+02:00"));
+    assert_eq!(xmp.modify_date.as_deref(), Some("2024-06-10T12:30:00+02:00"));
+    assert_eq!(xmp.metadata_date, None);
+    assert_eq!(xmp.creator_tool.as_deref(), Some("XMP Test Writer"));
+    assert_eq!(xmp.producer.as_deref(), Some("XMP Test Producer 2.0"));
+    assert_eq!(xmp.keywords.as_deref(), Some("xmp, metadata"));
+    assert_eq!(xmp.trapped, None);
+    assert_eq!((xmp.pdfa_part.as_deref(), xmp.pdfa_conformance.as_deref()), (Some("2"), Some("B")));
+    assert_eq!(xmp.custom.get("acme:ContractNumber").map(String::as_str), Some("C-2024-0042"));
+}
+
+#[test]
+fn reads_alt_seq_and_bag_containers() {
+    let info = xmp_info(
+        "",
+        r#"<dc:title><rdf:Alt>
+             <rdf:li xml:lang="fr-FR">Rapport</rdf:li>
+             <rdf:li xml:lang="x-default">Report</rdf:li>
+           </rdf:Alt></dc:title>
+           <dc:creator><rdf:Seq><rdf:li>Second Author</rdf:li><rdf:li>First Author</rdf:li></rdf:Seq></dc:creator>
+           <dc:subject><rdf:Bag><rdf:li>alpha</rdf:li><rdf:li></rdf:li><rdf:li>beta</rdf:li></rdf:Bag></dc:subject>
+           <acme:Reviewers><rdf:Bag><rdf:li>Kim</rdf:li><rdf:li>Lee</rdf:li></rdf:Bag></acme:Reviewers>"#,
+    );
+    let xmp = info.xmp.unwrap();
+    // The x-default alternative is taken over the first one
+    assert_eq!(xmp.title.as_deref(), Some("Report"));
+    // Sequences keep their order; empty items are dropped
+    assert_eq!(xmp.creators, ["Second Author", "First Author"]);
+    assert_eq!(xmp.subjects, ["alpha", "beta"]);
+    assert_eq!(xmp.custom.get("acme:Reviewers").map(String::as_str), Some("Kim; Lee"));
+    // Subjects stand in for missing pdf:Keywords
+    assert_eq!(info.keywords.as_deref(), Some("alpha, beta"));
+}
+
+#[test]
+fn reads_properties_written_as_attributes_or_elements() {
+    let info = xmp_info(
+        r#"pdf:Producer="Attribute Producer" xmp:CreatorTool="Attribute Tool" acme:Batch="7""#,
+        r#"<pdf:Keywords>element, keywords</pdf:Keywords>
+           <xmp:CreateDate>2024-05-01T08:00:00Z</xmp:CreateDate>
+           <acme:Source rdf:resource="http://example.com/source"/>
+           <acme:Address><rdf:Description><acme:City>Springfield</acme:City></rdf:Description></acme:Address>"#,
+    );
+    let xmp = info.xmp.unwrap();
+    assert_eq!(xmp.producer.as_deref(), Some("Attribute Producer"));
+    assert_eq!(xmp.creator_tool.as_deref(), Some("Attribute Tool"));
+    assert_eq!(xmp.keywords.as_deref(), Some("element, keywords"));
+    assert_eq!(xmp.create_date.as_deref(), Some("2024-05-01T08:00:00Z"));
+    assert_eq!(xmp.custom.get("acme:Batch").map(String::as_str), Some("7"));
+    assert_eq!(xmp.custom.get("acme:Source").map(String::as_str), Some("http://example.com/source"));
+    // Structured values are not simple properties
+    assert!(!xmp.custom.contains_key("acme:Address"), "{:?}", xmp.custom);
+}
+
+#[test]
+fn decodes_entities_and_cdata() {
+    let info = xmp_info(
+        r#"acme:Note="Tom &amp; &quot;Jerry&quot;""#,
+        r#"<dc:description><rdf:Alt>
+             <rdf:li xml:lang="x-default">a &lt; b &amp;&amp; c &gt; d</rdf:li>
+           </rdf:Alt></dc:description>
+           <xmp:CreatorTool>Caf&#233; &#x2014; Writer</xmp:CreatorTool>
+           <pdf:Producer><![CDATA[Producer <1.0> & co]]></pdf:Producer>"#,
+    );
+    let xmp = info.xmp.unwrap();
+    assert_eq!(xmp.description.as_deref(), Some("a < b && c > d"));
+    assert_eq!(xmp.creator_tool.as_deref(), Some("Café — Writer"));
+    assert_eq!(xmp.producer.as_deref(), Some("Producer <1.0> & co"));
+    assert_eq!(xmp.custom.get("acme:Note").map(String::as_str), Some("Tom & \"Jerry\""));
+}
+
+#[test]
+fn newer_info_dictionary_wins_and_gaps_are_filled_from_xmp() {
+    let info_dictionary = || {
+        dictionary! {
+            "Title" => Object::string_literal("Info Title"),
+            "Author" => Object::string_literal("Info Author"),
+            "ModDate" => Object::string_literal("D:20240701120000Z"),
+        }
+    };
+    let properties = r#"<dc:title><rdf:Alt><rdf:li xml:lang="x-default">XMP Title</rdf:li></rdf:Alt></dc:title>
+        <pdf:Producer>XMP Producer</pdf:Producer>"#;
+
+    // The Info dictionary was edited after the XMP packet
+    let info = info_with_xmp(
+        info_dictionary(),
+        &packet("", &format!("{properties}<xmp:ModifyDate>2024-06-01T12:00:00Z</xmp:ModifyDate>")),
+    );
+    assert_eq!(info.title.as_deref(), Some("Info Title"));
+    assert_eq!(info.author.as_deref(), Some("Info Author"));
+    assert_eq!(info.producer.as_deref(), Some("XMP Producer"));
+    assert_eq!(info.modification_date.as_deref(), Some("2024-07-01T12:00:00Z"));
+
+    // The metadata date stands in for a missing modify date, offsets included: 13:00+02:00
+    // is 11:00 UTC, before the Info date
+    let info = info_with_xmp(
+        info_dictionary(),
+        &packet("", &format!("{properties}<xmp:MetadataDate>2024-07-01T13:00:00+02:00</xmp:MetadataDate>")),
+    );
+    assert_eq!(info.title.as_deref(), Some("Info Title"));
+
+    // An XMP packet as new as the Info dictionary wins
+    let info = info_with_xmp(
+        info_dictionary(),
+        &packet("", &format!("{properties}<xmp:ModifyDate>2024-07-01T14:00:00+02:00</xmp:ModifyDate>")),
+    );
+    assert_eq!(info.title.as_deref(), Some("XMP Title"));
+    assert_eq!(info.author.as_deref(), Some("Info Author"));
+
+    // Without an XMP date the packet cannot be stale
+    let info = info_with_xmp(info_dictionary(), &packet("", properties));
+    assert_eq!(info.title.as_deref(), Some("XMP Title"));
+    assert_eq!(info.modification_date.as_deref(), Some("2024-07-01T12:00:00Z"));
+}